@@ -1,19 +1,79 @@
+mod action;
+mod blame;
+mod branches;
+mod checkout;
+mod commit;
+mod control;
+mod diff_summary;
+mod grouping;
+mod history;
+mod hunks;
+mod incoming;
+mod output;
+mod prompt;
+mod report;
+mod revision_graph;
+mod scope;
+mod three_way;
 mod ui;
+mod undo;
+mod watch;
+pub mod worker;
+pub mod workspace;
 use crate::{
-    command::{CmdResult, run_command},
-    config::Config,
+    command::{
+        self as command, CmdResult, CommandProgress, Precondition, copy_to_clipboard, notify,
+        run_command, run_editor, run_pager, spawn_detached, spawn_shell,
+    },
+    config::{self, Config, GroupBy, MAX_PANE_PERCENT, MIN_PANE_PERCENT, StatusDepth, Theme},
     event::{AppEvent, Direction, Event, EventHandler},
-    svn::{self, ParsedStatusLine},
+    ipc::{self, ControlSocket},
+    recording::{EventRecorder, RecordedEvent},
+    svn::{
+        self, StatusEntry,
+        checkout::{Checkout, CheckoutProgress, default_checkout_dir},
+        log::{LogEntry, get_last_commit},
+        state::State,
+        three_way::MergeBlock,
+        version::{ClientFeature, SvnVersion},
+    },
+    vcs::{self, SvnBackend, VcsBackend},
 };
+use action::{Action, PendingFix, available_actions};
+use blame::BlameScreen;
+use branches::{BranchPurpose, BranchSwitcher, load_cached_branches, save_cached_branches};
+use checkout::CheckoutScreen;
 use chrono::{DateTime, Utc};
+use commit::CommitReview;
 use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use diff_summary::DiffSummaryScreen;
+use grouping::{Row, RowKey, group_rows};
+use history::{HistoryPurpose, HistoryScreen};
+use hunks::HunkScreen;
+use incoming::{BehindCheck, IncomingPane};
+use output::{Operation, OutputScreen, UpdateSummary};
+use prompt::{Prompt, PromptKind};
 use ratatui::{
-    DefaultTerminal,
+    DefaultTerminal, Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
     crossterm::event::{Event as CtEvent, KeyCode, KeyEvent, KeyModifiers},
     layout::{Position, Rect},
     widgets::{ListState, ScrollbarState},
 };
-use std::path::PathBuf;
+use report::{Report, ReportFormat, timestamped_path};
+use revision_graph::{RevisionGraphScreen, graph_rows};
+use std::{
+    cell::RefCell,
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use three_way::ThreeWayScreen;
+use undo::{LocalChanges, Undo};
+use worker::{Job, Worker};
+use workspace::WorkspaceSummary;
 
 #[derive(Debug)]
 pub struct App {
@@ -23,8 +83,12 @@ pub struct App {
     events: EventHandler,
     /// The name of the current branch
     current_branch: String,
+    /// The last branch name that was read successfully, to spot when the branch changes
+    known_branch: Option<String>,
     /// The output from 'svn status'
     file_list: svn::FileList,
+    /// Where the status and branch come from, svn unless a git checkout is detected
+    backend: Box<dyn VcsBackend>,
     /// The state of the displayed changes list
     list_state: ListState,
     /// The last time 'svn status' was run
@@ -32,22 +96,165 @@ pub struct App {
     /// The current working directory
     cwd: PathBuf,
     changes_scrollbar_state: ScrollbarState,
+    conflicts_scrollbar_state: ScrollbarState,
+    /// The first line shown in the conflicts pane
+    conflicts_scroll_offset: usize,
+    /// How many lines the conflicts pane had when it was last drawn
+    conflicts_line_count: usize,
+    /// The pane that the arrow and paging keys move through
+    focus: AppSection,
     // UI areas mainly used for mouse clicks etc.
     changes_area: Option<Rect>,
+    conflicts_area: Option<Rect>,
     change_popup_area: Option<Rect>,
     config: Config,
     mouse_loc: (u16, u16), // row, col
     state: AppState,
     has_focus: bool,
-    last_message: String,
-    buttons: Vec<(Rect, fn(&mut App))>,
-    _multiselection: Option<Vec<usize>>,
+    /// Every message shown in the message box this session, oldest first
+    messages: Vec<String>,
+    /// When the last message was pushed, after a while the message box shows hints instead
+    last_message_at: Option<DateTime<Utc>>,
+    /// When a refresh last finished, flashed in the status bar so that a refresh which found
+    /// nothing new is still seen to have happened
+    refreshed_at: Option<DateTime<Utc>>,
+    buttons: Vec<Button>,
+    /// Paths of the changes picked for a multi-file action, empty when only the
+    /// highlighted change is being acted upon
+    multiselection: Vec<PathBuf>,
+    /// Paths (and the depth to query them at) still to be loaded for the initial status,
+    /// popped from the back. `None` once the status has been fully loaded.
+    loading_status: Option<Vec<(PathBuf, &'static str)>>,
+    /// The working copies on the workspaces dashboard, loaded when it's opened
+    workspaces: Vec<WorkspaceSummary>,
+    workspaces_list_state: ListState,
+    bookmarks_list_state: ListState,
+    /// The revision the working copy was last updated to from the app, shown in the branch box
+    /// so it's clear when it isn't at HEAD
+    updated_revision: Option<u32>,
+    /// The directories read at full depth when the status is shallower, relative to the
+    /// working copy
+    drilled_dirs: Vec<PathBuf>,
+    /// The directory the changes are listed for instead of the whole working copy, relative
+    /// to it
+    scope: Option<PathBuf>,
+    /// The newest revision the working copy has, its message shown under the branch
+    last_commit: Option<LogEntry>,
+    /// Whether the last commit needs reading again, after the working copy changed revision
+    last_commit_stale: bool,
+    /// The changes being committed together, from when their message is typed until they're
+    /// committed after being reviewed in [`AppState::CommitReview`]
+    commit_review: Option<CommitReview>,
+    /// The message of the last commit that was cancelled or failed, to start the next one with
+    commit_draft: String,
+    /// The hunks being picked to commit, while in [`AppState::Hunks`] and then prompting for
+    /// the commit message
+    hunks: Option<HunkScreen>,
+    /// The sides of the conflict shown while in [`AppState::ThreeWay`]
+    three_way: Option<ThreeWayScreen>,
+    /// The comparison with another branch shown while in [`AppState::DiffSummary`]
+    diff_summary: Option<DiffSummaryScreen>,
+    /// The merge, update or commit shown while in [`AppState::Output`], kept while it runs
+    output: Option<OutputScreen>,
+    /// What the last update did, shown while in [`AppState::UpdateSummary`]
+    update_summary: Option<UpdateSummary>,
+    /// The svn command running in the background and the jobs waiting for it
+    worker: Worker,
+    /// The history shown while in [`AppState::RevisionGraph`]
+    revision_graph: Option<RevisionGraphScreen>,
+    /// The file's revisions shown while in [`AppState::History`]
+    history: Option<HistoryScreen>,
+    /// Who changed the file's lines, shown while in [`AppState::Blame`]
+    blame: Option<BlameScreen>,
+    /// What an update would pull in, listed below the changes while toggled with 'F'
+    incoming: Option<IncomingPane>,
+    /// How many revisions the working copy is behind, shown in the branch box
+    behind: BehindCheck,
+    /// The branches offered to switch to, while in [`AppState::Branches`]
+    branch_switcher: Option<BranchSwitcher>,
+    /// The text being typed into the message box, while in [`AppState::Prompt`]
+    prompt: Option<Prompt>,
+    /// The index of the change the left mouse button was pressed on, while it is held
+    drag_anchor: Option<usize>,
+    /// The checkout shown on the checkout screen
+    checkout: Option<CheckoutScreen>,
+    /// Text to show in the pager once the current event has been handled
+    pending_pager: Option<String>,
+    /// File to open in the configured editor once the current event has been handled
+    pending_editor: Option<PathBuf>,
+    /// Where the terminal's events are written, when started with --record-events
+    recorder: Option<EventRecorder>,
+    /// The socket other programs send requests to, when started with --control-socket
+    control_socket: Option<ControlSocket>,
+    /// The theme to go back to when high contrast is toggled off
+    theme_before_high_contrast: Option<Theme>,
+    /// The directories whose changes are hidden under their header, when grouped by directory
+    collapsed_dirs: HashSet<PathBuf>,
+    /// The directories whose changes are shown, when grouped as a tree
+    expanded_dirs: HashSet<PathBuf>,
+    /// The states whose changes are hidden under their section, when grouped by state
+    collapsed_states: HashSet<State>,
+    /// Why the working copy can't be reached, e.g. its network drive was unmounted. Refreshing
+    /// only checks whether it's back while this is set.
+    unavailable: Option<String>,
+    /// svn isn't installed, so how to install it is shown instead of the changes and nothing
+    /// runs svn until another working copy that doesn't need it is opened
+    svn_missing: bool,
+    /// Why the directory it was started in isn't a working copy, shown instead of the changes
+    /// until one is opened with 'C' or from the workspaces
+    not_working_copy: Option<String>,
+    /// The version of the installed svn client, `None` if it isn't known
+    svn_version: Option<SvnVersion>,
+    /// How to reverse the last add, delete, revert or lock, undone with 'z'
+    last_undo: Option<Undo>,
+    /// Why the last svn command failed, if doing something first would let it succeed
+    unmet_precondition: Option<Precondition>,
+    /// The last action that failed on an unmet precondition, retried with 'f'
+    pending_fix: Option<PendingFix>,
+    /// Whether the last svn command failed with a transient network error, so is worth
+    /// running again with [`App::retry_later`]
+    transient_failure: bool,
+    /// Which retry of a job is running, 0 while it's the first try
+    retrying: u8,
+    /// The files shown above the rest of the changes, in the order they were pinned
+    pinned: Vec<PathBuf>,
+    /// The pinned files without changes, listed as clean in the favorites section
+    favorites: Vec<StatusEntry>,
 }
 
+/// How many messages are kept in the history
+const MESSAGE_HISTORY_LENGTH: usize = 100;
+
+/// How many working copies have their status read at once for the workspaces dashboard
+const WORKSPACE_STATUS_WORKERS: usize = 4;
+
+/// How long a message is shown before the message box goes back to showing hints
+const MESSAGE_DURATION: Duration = Duration::from_secs(5);
+
+/// How long the status bar says the changes were refreshed before showing how long ago
+const REFRESH_FLASH_DURATION: Duration = Duration::from_secs(1);
+
+/// A clickable area of the UI and the action it triggers
+type Button = (Rect, fn(&mut App));
+
 #[derive(Debug, PartialEq)]
 pub enum AppState {
-    Main,        // The main screen
-    ChangePopup, // A popup caused by a change is shown over the main screen
+    Main,          // The main screen
+    ChangePopup,   // A popup caused by a change is shown over the main screen
+    Workspaces,    // The dashboard of every configured working copy replaces the changes list
+    Prompt,        // Text is being typed into the message box
+    Checkout,      // The progress of a checkout replaces the changes list
+    Bookmarks,     // The bookmarked working copies are listed in a popup over the main screen
+    Branches,      // The repository's branches are listed in a popup over the main screen
+    RevisionGraph, // The history of the branch and those it was copied from replaces the changes list
+    History,       // The revisions that changed a file replace the changes list
+    Blame,         // Who last changed each line of a file replaces the changes list
+    Output,        // The output of a merge, update or commit replaces the changes list
+    DiffSummary,   // The paths that differ from another branch replace the changes list
+    Hunks,         // The hunks of a file's changes, picked to commit, replace the changes list
+    CommitReview,  // The changes about to be committed and their message are shown in a popup
+    ThreeWay,      // The three sides of a text conflict replace the changes list
+    UpdateSummary, // What an update did is shown in a popup over the main screen
 }
 
 impl Default for App {
@@ -66,83 +273,423 @@ impl App {
             running: true,
             events: EventHandler::new(),
             current_branch: String::new(),
+            known_branch: None,
             file_list,
+            backend: Box::new(SvnBackend),
             last_updated: Utc::now(),
             cwd: PathBuf::new(),
             list_state,
             changes_scrollbar_state,
+            conflicts_scrollbar_state: ScrollbarState::default(),
+            conflicts_scroll_offset: 0,
+            conflicts_line_count: 0,
+            focus: AppSection::Changes,
             changes_area: None,
+            conflicts_area: None,
             config: Config::default(),
             mouse_loc: (0, 0),
             state: AppState::Main,
             change_popup_area: None,
-            last_message: String::new(),
+            messages: vec![],
+            last_message_at: None,
+            refreshed_at: None,
             has_focus: true,
             buttons: vec![],
-            _multiselection: None,
+            multiselection: vec![],
+            loading_status: None,
+            workspaces: vec![],
+            workspaces_list_state: ListState::default(),
+            bookmarks_list_state: ListState::default(),
+            branch_switcher: None,
+            revision_graph: None,
+            history: None,
+            blame: None,
+            incoming: None,
+            behind: BehindCheck::default(),
+            output: None,
+            update_summary: None,
+            worker: Worker::default(),
+            diff_summary: None,
+            hunks: None,
+            three_way: None,
+            commit_review: None,
+            commit_draft: String::new(),
+            updated_revision: None,
+            drilled_dirs: vec![],
+            scope: None,
+            last_commit: None,
+            last_commit_stale: true,
+            prompt: None,
+            drag_anchor: None,
+            checkout: None,
+            pending_pager: None,
+            pending_editor: None,
+            recorder: None,
+            control_socket: None,
+            collapsed_dirs: HashSet::new(),
+            collapsed_states: HashSet::new(),
+            theme_before_high_contrast: None,
+            expanded_dirs: HashSet::new(),
+            unavailable: None,
+            svn_missing: false,
+            not_working_copy: None,
+            svn_version: None,
+            last_undo: None,
+            unmet_precondition: None,
+            pending_fix: None,
+            transient_failure: false,
+            retrying: 0,
+            pinned: vec![],
+            favorites: vec![],
         }
     }
 
-    pub fn with_config(self, config: Config) -> Self {
-        Self { config, ..self }
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.apply_config(config);
+        self
+    }
+
+    /// Replaces the config, along with the file list's filters that come from it
+    fn apply_config(&mut self, config: Config) {
+        let hidden = match config.hide_excluded_changelists {
+            true => config.excluded_changelists.clone(),
+            false => vec![],
+        };
+        self.file_list.set_hidden_changelists(hidden);
+        self.file_list
+            .set_hide_property_only(config.hide_property_only);
+        self.file_list
+            .set_hide_patterns(config.hide_patterns.clone());
+        self.config = config;
+    }
+
+    /// Run the application's main loop, returning a summary of the working copy's state as it
+    /// was when quitting.
+    pub fn run(mut self, terminal: DefaultTerminal) -> color_eyre::Result<String> {
+        if let Some(path) = self.config.record_events.clone() {
+            match EventRecorder::create(&path) {
+                Ok(recorder) => self.recorder = Some(recorder),
+                Err(e) => self.push_message(format!("can't record to {}: {e}", path.display())),
+            }
+        }
+        if let Some(path) = self.config.control_socket.clone() {
+            match ipc::listen(&path, self.events.app_sender()) {
+                Ok(socket) => self.control_socket = Some(socket),
+                Err(e) => self.push_message(format!("can't listen on {}: {e}", path.display())),
+            }
+        }
+        self.start();
+        self.main_loop(terminal)
     }
 
-    /// Run the application's main loop.
-    pub fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
+    /// Opens the working copy the app was started in, or whatever screen it was started for
+    fn start(&mut self) {
         let cwd = std::env::current_dir()
             .expect("does this directory exist? do you have permissions on this dir?");
-        self.current_branch = match svn::get_branch_name(&cwd) {
+        let needs_svn = self.config.checkout.is_some() || vcs::detect_backend(&cwd).name() == "svn";
+        if needs_svn && !self.detect_svn_client() {
+            self.cwd = cwd;
+            self.current_branch = "svn not found".into();
+            return;
+        }
+        if let Some(url) = self.config.checkout.clone() {
+            self.cwd = cwd;
+            self.start_checkout(&url);
+            return;
+        }
+        let roots = svn::find_wc_roots(&cwd);
+        if roots.len() > 1 {
+            // nested working copies, let the user pick which one they meant
+            self.cwd = cwd;
+            self.show_workspaces(roots);
+            self.push_message("found nested working copies, pick one to open".into());
+            return;
+        }
+        self.backend = vcs::detect_backend(&cwd);
+        let (cwd, branch) = match (self.backend.branch_name(&cwd), roots.first()) {
+            // e.g. an unversioned directory inside the working copy
+            (Err(_), Some(root)) => (root.clone(), self.backend.branch_name(root)),
+            (branch, _) => (cwd, branch),
+        };
+        self.current_branch = match branch {
             Ok(branch) => branch,
-            Err(e) => panic!("Issue in App creation: {e}"),
+            // started outside a working copy, e.g. in the parent of the workspaces
+            Err(e) if !self.config.workspaces.is_empty() => {
+                self.cwd = cwd;
+                self.current_branch = e.to_string();
+                self.open_workspaces();
+                return;
+            }
+            Err(e) => {
+                self.cwd = cwd;
+                self.current_branch = "not a working copy".into();
+                self.not_working_copy = Some(e.to_string());
+                return;
+            }
         };
-        if let Ok(status) = svn::get_svn_status(&cwd) {
-            *self.file_list.list_mut() = status;
-        }
+        // the status is loaded on the first ticks so the UI appears straight away
+        // the initial chunks read every directory in full, so they'd undo a shallower depth
+        let incremental = self.config.incremental_status
+            && self.backend.supports_depth()
+            && self.config.status_depth == StatusDepth::Infinity;
+        self.loading_status = match incremental {
+            true => Some(initial_status_queue(&cwd)),
+            false => Some(vec![]),
+        };
+        self.pinned = config::load_pins(&cwd);
         self.cwd = cwd;
+    }
+
+    /// Finds which svn client is installed, returning whether there is one
+    fn detect_svn_client(&mut self) -> bool {
+        if self.svn_version.is_some() {
+            return true;
+        }
+        match svn::version::get_svn_version() {
+            Ok(version) => self.svn_version = Some(version),
+            Err(svn::error::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.svn_missing = true;
+                return false;
+            }
+            // an unusual version string, e.g. a development build, so assume it's recent
+            Err(_) => {}
+        }
+        self.svn_missing = false;
+        true
+    }
+
+    /// Says why the selection's disabled actions can't be used with the installed svn client
+    fn explain_unsupported_actions(&mut self) {
+        self.close_change_popup();
+        for action in self.selection_actions() {
+            if let Some(reason) = action
+                .required_feature()
+                .and_then(|feature| self.unsupported(feature))
+            {
+                self.push_message(format!("{} {reason}", action.label().to_lowercase()));
+            }
+        }
+    }
+
+    /// Why the installed svn client can't do what the feature needs, if it can't
+    fn unsupported(&self, feature: ClientFeature) -> Option<String> {
+        self.svn_version
+            .and_then(|version| feature.unsupported_by(version))
+    }
+
+    fn main_loop(&mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<String> {
         while self.running {
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events()?;
+            if let Some(text) = self.pending_pager.take() {
+                self.show_in_pager(&mut terminal, &text)?;
+            }
+            if let Some(path) = self.pending_editor.take() {
+                self.open_in_editor(&mut terminal, &path)?;
+            }
+        }
+        Ok(self.exit_summary())
+    }
+
+    /// Replays recorded events against the app without a terminal, as fast as the recording
+    /// allows, returning the text of the screen after the last one. The app's own events, e.g.
+    /// a status refresh finishing, are handled between the recorded ones.
+    pub fn replay(mut self, recording: Vec<RecordedEvent>) -> color_eyre::Result<String> {
+        self.events = EventHandler::headless();
+        self.start();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24))?;
+        self.replay_on(&mut terminal, recording)?;
+        Ok(screen_text(terminal.backend().buffer()))
+    }
+
+    fn replay_on(
+        &mut self,
+        terminal: &mut Terminal<TestBackend>,
+        recording: Vec<RecordedEvent>,
+    ) -> color_eyre::Result<()> {
+        let started = Instant::now();
+        terminal.draw(|frame| self.draw(frame))?;
+        for RecordedEvent { at, event } in recording {
+            // background work gets the time it had when the events were recorded
+            std::thread::sleep(at.saturating_sub(started.elapsed()));
+            while let Some(event) = self.events.try_next() {
+                self.handle_event(event)?;
+            }
+            if let Event::Crossterm(CtEvent::Resize(width, height)) = event {
+                terminal.backend_mut().resize(width, height);
+            }
+            self.handle_event(event)?;
+            while let Some(event) = self.events.try_next() {
+                self.handle_event(event)?;
+            }
+            // there's no terminal to hand to the pager or editor
+            self.pending_pager = None;
+            self.pending_editor = None;
+            terminal.draw(|frame| self.draw(frame))?;
+            if !self.running {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// One plain line with the branch, working copy and how many changes of each state it
+    /// has, e.g. "trunk (/work/repo): 2 modified, 1 conflicted"
+    fn exit_summary(&self) -> String {
+        let counts = self.file_list.state_counts();
+        let changes = match counts.is_empty() {
+            true => "no changes".to_string(),
+            false => counts
+                .iter()
+                .map(|(state, count)| format!("{count} {}", state.name()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        format!(
+            "{} ({}): {changes}",
+            self.current_branch,
+            self.cwd.display()
+        )
+    }
+
+    /// Hands the terminal over to the pager to show the text, taking it back when the pager
+    /// quits
+    fn show_in_pager(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        text: &str,
+    ) -> color_eyre::Result<()> {
+        self.events.set_paused(true);
+        crate::restore_terminal();
+        let result = run_pager(&self.config.pager_command(), text);
+        crate::resume_terminal(terminal)?;
+        self.events.set_paused(false);
+        if let Err(e) = result {
+            self.push_message(format!("{}: {e}", self.config.pager_command()));
+        }
+        Ok(())
+    }
+
+    /// Hands the terminal over to the editor to open the file, taking it back when the editor
+    /// quits
+    fn open_in_editor(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        path: &Path,
+    ) -> color_eyre::Result<()> {
+        let Some(editor) = self.config.editor.clone() else {
+            return Ok(());
+        };
+        self.events.set_paused(true);
+        crate::restore_terminal();
+        let result = run_editor(&editor, path);
+        crate::resume_terminal(terminal)?;
+        self.events.set_paused(false);
+        if let Err(e) = result {
+            self.push_message(format!("{editor}: {e}"));
         }
         Ok(())
     }
 
     fn handle_events(&mut self) -> color_eyre::Result<()> {
-        match self.events.next()? {
+        let event = self.events.next()?;
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.record(&event)
+        {
+            self.recorder = None;
+            self.push_message(format!("stopped recording events: {e}"));
+        }
+        self.handle_event(event)
+    }
+
+    fn handle_event(&mut self, event: Event) -> color_eyre::Result<()> {
+        match event {
             Event::Tick => self.tick(),
             Event::Crossterm(event) => match event {
                 CtEvent::Key(key_event) => self.handle_key_event(key_event)?,
                 CtEvent::Mouse(mouse_event) => self.handle_mouse_event(mouse_event)?,
                 CtEvent::FocusLost => {
-                    self.close_change_popup();
-                    *self.list_state.selected_mut() = None;
+                    if self.config.clear_selection_on_blur {
+                        self.close_change_popup();
+                        *self.list_state.selected_mut() = None;
+                    }
                     self.has_focus = false;
                 }
                 CtEvent::FocusGained => {
-                    self.update_branch_name();
-                    self.update_svn_status();
+                    if self.config.refresh_on_focus {
+                        self.refresh();
+                    }
                     self.has_focus = true;
                 }
                 _ => {}
             },
-            Event::App(app_event) => match app_event {
-                AppEvent::Quit => self.quit(),
-                AppEvent::UpdateRequest => {
-                    self.update_branch_name();
-                    self.update_svn_status();
-                }
-                AppEvent::NextChange => self.list_state.select_next(),
-                AppEvent::PrevChange => self.list_state.select_previous(),
-                AppEvent::SelectChange => self.state = AppState::ChangePopup,
-                AppEvent::Message(msg) => self.last_message = msg,
-            },
+            Event::App(app_event) => self.handle_app_event(app_event),
         }
         Ok(())
     }
 
-    /// Handles the key events and updates the state of [`App`].
-    fn handle_key_event(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
+    fn handle_app_event(&mut self, app_event: AppEvent) {
+        match app_event {
+            AppEvent::Quit => self.quit(),
+            AppEvent::UpdateRequest => self.refresh(),
+            AppEvent::NextChange => match self.list_state.selected() {
+                Some(index) => self.select_change(index.saturating_add(1)),
+                None => self.select_change(0),
+            },
+            AppEvent::PrevChange => self.select_change(self.selected_index().saturating_sub(1)),
+            AppEvent::SelectChange => self.state = AppState::ChangePopup,
+            AppEvent::Message(msg) => self.push_message(msg),
+            AppEvent::Checkout(progress) => self.handle_checkout_progress(progress),
+            AppEvent::Output(progress) => self.handle_output_progress(progress),
+            AppEvent::DiffSummary { other, result } => {
+                // the screen may have been closed, or opened for another branch, since
+                if let Some(screen) = self.diff_summary.as_mut().filter(|s| s.other == other) {
+                    screen.result = Some(result);
+                    screen.list_state.select(Some(0));
+                }
+            }
+            AppEvent::RevisionGraph(result) => {
+                if let Some(screen) = self.revision_graph.as_mut() {
+                    screen.result = Some(result);
+                    screen.list_state.select(Some(0));
+                }
+            }
+            AppEvent::History { path, result } => {
+                // the screen may have been closed, or opened for another file, since
+                if let Some(screen) = self.history.as_mut().filter(|s| s.path == path) {
+                    screen.result = Some(result);
+                    screen.list_state.select(Some(0));
+                }
+            }
+            AppEvent::Blame { path, result } => {
+                if let Some(screen) = self.blame.as_mut().filter(|s| s.path == path) {
+                    screen.result = Some(result);
+                }
+            }
+            AppEvent::Incoming(result) => self.handle_incoming(result),
+            AppEvent::Behind(result) => self.handle_behind(result),
+            AppEvent::LastCommit { path, result } => self.handle_last_commit(&path, result),
+            AppEvent::Branches { uuid, result } => self.handle_branches_listed(&uuid, result),
+            AppEvent::Mergeinfo(Ok(text)) => self.pending_pager = Some(text),
+            AppEvent::Mergeinfo(Err(e)) => self.push_message(e),
+            AppEvent::Retry { job, retry } => {
+                self.retrying = retry;
+                self.run_job(job);
+                self.retrying = 0;
+            }
+            AppEvent::Control(control) => self.handle_control(control),
+            AppEvent::Workspace(summary) => {
+                let path = summary.path.clone();
+                if let Some(workspace) = self.workspaces.iter_mut().find(|w| w.path == path) {
+                    *workspace = summary;
+                }
+            }
+        }
+    }
+
+    /// Only quitting and refreshing make sense with nothing but the one line to see
+    fn handle_minimal_key_event(&mut self, key_event: KeyEvent) {
         match key_event.code {
-            KeyCode::Esc if self.state != AppState::Main => self.state = AppState::Main,
             KeyCode::Esc | KeyCode::Char('q') => self.events.send(AppEvent::Quit),
             KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
                 self.events.send(AppEvent::Quit)
@@ -150,366 +697,4817 @@ impl App {
             KeyCode::Char('r' | 'R') => self.events.send(AppEvent::UpdateRequest),
             _ => {}
         }
-        Ok(())
     }
 
-    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> color_eyre::Result<()> {
-        self.mouse_loc = (mouse_event.row, mouse_event.column);
-        match mouse_event.kind {
-            MouseEventKind::Down(btn) => self.handle_click(btn),
-            MouseEventKind::ScrollDown => self.handle_mouse_scroll(Direction::Down),
-            MouseEventKind::ScrollUp => self.handle_mouse_scroll(Direction::Up),
-            MouseEventKind::Moved => self.handle_mouse_move(),
+    /// Handles the key events and updates the state of [`App`].
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
+        if self.config.minimal {
+            self.handle_minimal_key_event(key_event);
+            return Ok(());
+        }
+        match self.state {
+            AppState::Workspaces => {
+                self.handle_workspaces_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Prompt => {
+                self.handle_prompt_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Checkout => {
+                self.handle_checkout_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Bookmarks => {
+                self.handle_bookmarks_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Branches => {
+                self.handle_branches_key_event(key_event);
+                return Ok(());
+            }
+            AppState::RevisionGraph => {
+                self.handle_revision_graph_key_event(key_event);
+                return Ok(());
+            }
+            AppState::History => {
+                self.handle_history_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Blame => {
+                self.handle_blame_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Output => {
+                self.handle_output_key_event(key_event);
+                return Ok(());
+            }
+            AppState::DiffSummary => {
+                self.handle_diff_summary_key_event(key_event);
+                return Ok(());
+            }
+            AppState::Hunks => {
+                self.handle_hunks_key_event(key_event);
+                return Ok(());
+            }
+            AppState::CommitReview => {
+                self.handle_commit_review_key_event(key_event);
+                return Ok(());
+            }
+            AppState::UpdateSummary => {
+                self.handle_update_summary_key_event(key_event);
+                return Ok(());
+            }
+            AppState::ThreeWay => {
+                self.handle_three_way_key_event(key_event);
+                return Ok(());
+            }
+            _ => {}
+        }
+        if self.state == AppState::Main
+            && let Some(template) = self.custom_command(&key_event)
+        {
+            self.run_custom_command(&template);
+            return Ok(());
+        }
+        match key_event.code {
+            KeyCode::Esc if self.state != AppState::Main => self.state = AppState::Main,
+            KeyCode::Esc if !self.multiselection.is_empty() => self.clear_multiselection(),
+            KeyCode::Esc | KeyCode::Char('q') => self.events.send(AppEvent::Quit),
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Char('r' | 'R') => {
+                // the repository is only asked on request, as it's much slower than a status
+                if self.incoming.is_some() {
+                    self.fetch_incoming();
+                }
+                self.events.send(AppEvent::UpdateRequest)
+            }
+            KeyCode::Char('F') => self.toggle_incoming(),
+            KeyCode::Char('h') => self.toggle_excluded_changelists(),
+            KeyCode::Char('H') => self.toggle_property_only(),
+            KeyCode::Char('w') => self.open_workspaces(),
+            KeyCode::Char('b') => self.open_bookmarks(),
+            KeyCode::Char('B') => self.open_branch_switcher(BranchPurpose::Switch),
+            KeyCode::Char('M') => self.open_branch_switcher(BranchPurpose::MergeFrom),
+            KeyCode::Char('I') => self.open_branch_switcher(BranchPurpose::Mergeinfo),
+            KeyCode::Char('D') => self.open_branch_switcher(BranchPurpose::DiffSummary),
+            KeyCode::Char('G') => self.open_revision_graph(),
+            KeyCode::Char('C') => self.open_prompt(PromptKind::ChangeDirectory, ""),
+            KeyCode::Char('P') => self.open_prompt(PromptKind::PinFavorite, ""),
+            KeyCode::Char('X') => self.drill_in_prompt(),
+            KeyCode::Char('s') => self.scope_prompt(),
+            KeyCode::Backspace => self.leave_scope(),
+            KeyCode::Char('v') => self.cycle_grouping(),
+            KeyCode::Char('e') => self.open_prompt(PromptKind::ExportReport, "status-report.md"),
+            // without a selection to export, the whole working copy is
+            KeyCode::Char('E') if self.selection_actions().is_empty() => self.export_prompt("."),
+            KeyCode::Char('z') => self.undo_last_action(),
+            KeyCode::Char('U') => self.open_prompt(PromptKind::UpdateToRevision, ""),
+            KeyCode::Char('f') => self.fix_and_retry(),
+            KeyCode::Char('J') if self.output.is_some() => self.state = AppState::Output,
+            KeyCode::Char('<') => self.resize_conflicts_pane(-5),
+            KeyCode::Char('>') => self.resize_conflicts_pane(5),
+            KeyCode::Char('L') => self.toggle_pane_layout(),
+            KeyCode::Char('Z') => self.toggle_high_contrast(),
+            KeyCode::Enter => self.toggle_row(self.selected_index()),
+            KeyCode::Right if self.file_list.has_conflicts() => self.focus = AppSection::Conflicts,
+            KeyCode::Left => self.focus = AppSection::Changes,
+            KeyCode::Down
+            | KeyCode::Up
+            | KeyCode::PageDown
+            | KeyCode::PageUp
+            | KeyCode::Home
+            | KeyCode::End
+                if self.focus == AppSection::Conflicts =>
+            {
+                self.handle_conflicts_key(key_event.code)
+            }
+            KeyCode::Down => self.events.send(AppEvent::NextChange),
+            KeyCode::Up => self.events.send(AppEvent::PrevChange),
+            KeyCode::PageDown => {
+                self.select_change(self.selected_index().saturating_add(self.page_height()))
+            }
+            KeyCode::PageUp => {
+                self.select_change(self.selected_index().saturating_sub(self.page_height()))
+            }
+            KeyCode::Home => self.select_change(0),
+            KeyCode::End => self.select_change(usize::MAX),
+            KeyCode::Char('a') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.select_all_changes()
+            }
+            KeyCode::Char('i') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.invert_multiselection()
+            }
+            KeyCode::Char(' ') => {
+                if let Some(index) = self.list_state.selected() {
+                    self.toggle_multiselection(index);
+                }
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(action) = Action::from_key(c) {
+                    self.perform_action_from_key(action);
+                }
+            }
             _ => {}
         }
         Ok(())
     }
 
-    /// Handles the tick event of the terminal.
-    ///
-    /// The tick event is where you can update the state of your application with any logic that
-    /// needs to be updated at a fixed frame rate. E.g. polling a server, updating an animation.
-    fn tick(&mut self) {
-        if time_for_update(self.last_updated, self.config.svn_status_timeout) {
-            self.events.send(AppEvent::UpdateRequest);
+    /// Performs the action, first asking whether to when it throws away local changes
+    fn perform_action_from_key(&mut self, action: Action) {
+        if action.needs_confirmation() && self.selection_actions().contains(&action) {
+            let count = self.selected_paths().len();
+            self.open_prompt(PromptKind::Confirm(action, count), "");
+        } else {
+            self.perform_action(action);
         }
     }
 
-    /// Set running to false to quit the application.
-    fn quit(&mut self) {
-        self.running = false;
-    }
-
-    fn update_svn_status(&mut self) {
-        // TODO error popup here?
-        match svn::get_svn_status(&self.cwd) {
-            Ok(status) => *self.file_list.list_mut() = status,
-            Err(error) => self.events.send(AppEvent::Message(error.to_string())),
+    fn handle_workspaces_key_event(&mut self, key_event: KeyEvent) {
+        let selected = self.workspaces_list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Char('q') => self.events.send(AppEvent::Quit),
+            KeyCode::Esc | KeyCode::Char('w') => self.state = AppState::Main,
+            KeyCode::Char('r' | 'R') => {
+                let paths = self.workspaces.iter().map(|w| w.path.clone()).collect();
+                self.show_workspaces(paths);
+            }
+            KeyCode::Down => self.select_workspace(selected.saturating_add(1)),
+            KeyCode::Up => self.select_workspace(selected.saturating_sub(1)),
+            KeyCode::Home => self.select_workspace(0),
+            KeyCode::End => self.select_workspace(usize::MAX),
+            KeyCode::Enter => {
+                if let Some(workspace) = self.workspaces.get(selected) {
+                    let path = workspace.path.clone();
+                    self.switch_working_copy(path);
+                }
+            }
+            _ => {}
         }
-        self.last_updated = Utc::now();
-    }
-
-    fn update_branch_name(&mut self) {
-        self.current_branch = match svn::get_branch_name(&self.cwd) {
-            Ok(branch) => branch,
-            Err(e) => e.to_string(),
-        };
     }
 
-    /// Handles any mouse clicks within the UI.
-    fn handle_click(&mut self, button: MouseButton) {
-        let section = self.current_mouse_section();
-        match section {
-            Some(AppSection::Changes) => {
-                if let Some(rect) = self.changes_area {
-                    let offset = self.mouse_loc.0 - rect.y;
-                    let index = (offset as usize + self.list_state.offset()).saturating_sub(1);
-                    if button == MouseButton::Right {
-                        if index <= self.file_list.renderable().len() {
-                            *self.list_state.selected_mut() = Some(index);
-                            self.change_popup_area = None;
-                            self.state = AppState::ChangePopup;
-                        }
-                    } else {
-                        self.close_change_popup();
-                    }
-                    if button == MouseButton::Left {
-                        if index <= self.file_list.renderable().len() {
-                            *self.list_state.selected_mut() = Some(index);
-                        } else {
-                            *self.list_state.selected_mut() = None;
-                        }
-                    }
+    fn handle_prompt_key_event(&mut self, key_event: KeyEvent) {
+        if let Some(Prompt {
+            kind: PromptKind::Confirm(action, _),
+            ..
+        }) = self.prompt
+        {
+            self.prompt = None;
+            self.state = AppState::Main;
+            match key_event.code {
+                KeyCode::Char('y' | 'Y') => self.perform_action(action),
+                _ => self.push_message(format!("{} cancelled", action.label().to_lowercase())),
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc => {
+                if let Some(Prompt {
+                    kind: PromptKind::CommitMessage(_),
+                    input,
+                }) = self.prompt.take()
+                {
+                    self.commit_draft = input;
                 }
+                self.hunks = None;
+                self.commit_review = None;
+                self.state = AppState::Main;
             }
-            Some(AppSection::ChangePopup) => {
-                let pos = Position {
-                    // TODO make App.mouse_loc a Position
-                    x: self.mouse_loc.1,
-                    y: self.mouse_loc.0,
-                };
-                if let Some(func) = self.buttons.iter().fold(None, |mut a, (rect, func)| {
-                    if rect.contains(pos) {
-                        a = Some(func);
-                    }
-                    a
-                }) {
-                    func(self);
+            KeyCode::Enter => {
+                self.state = AppState::Main;
+                if let Some(prompt) = self.prompt.take() {
+                    self.submit_prompt(prompt);
                 }
-                self.close_change_popup();
             }
             _ => {
-                *self.list_state.selected_mut() = None;
-                self.close_change_popup();
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.edit(key_event);
+                }
             }
         }
     }
 
-    fn close_change_popup(&mut self) {
-        self.state = AppState::Main;
-        self.change_popup_area = None;
+    fn handle_checkout_key_event(&mut self, key_event: KeyEvent) {
+        let running = self
+            .checkout
+            .as_ref()
+            .is_some_and(|checkout| checkout.handle.is_some());
+        let quit = match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => true,
+            KeyCode::Esc | KeyCode::Char('q') => !running,
+            _ => return,
+        };
+        if let Some(handle) = self.checkout.as_ref().and_then(|c| c.handle.as_ref()) {
+            handle.cancel();
+            self.push_message("cancelling the checkout".into());
+        }
+        if quit {
+            self.events.send(AppEvent::Quit);
+        }
     }
 
-    fn get_selected_changes(&self) -> Option<Vec<&ParsedStatusLine>> {
-        if let Some(index) = self.list_state.selected() {
-            if let Some(change) = self.file_list.get(index) {
-                Some(vec![change])
-            } else {
-                None
-            }
-        } else {
-            None
+    /// Checks the url out into the current directory, showing the progress on the checkout
+    /// screen until it finishes
+    fn start_checkout(&mut self, url: &str) {
+        let dest = self.cwd.join(default_checkout_dir(url));
+        let mut screen = CheckoutScreen::new(url, dest.clone());
+        let send = self.events.app_sender();
+        match Checkout::start(url, &dest, move |progress| {
+            send(AppEvent::Checkout(progress))
+        }) {
+            Ok(handle) => screen.handle = Some(handle),
+            Err(e) => screen.result = Some(Err(e.to_string())),
         }
+        self.checkout = Some(screen);
+        self.state = AppState::Checkout;
     }
 
-    fn handle_mouse_scroll(&mut self, dir: Direction) {
-        match self.current_mouse_section() {
-            Some(AppSection::Changes) => {
-                if let Some(selected) = self.list_state.selected_mut() {
-                    handle_scroll(&dir, selected, &mut self.changes_scrollbar_state)
-                }
+    fn handle_checkout_progress(&mut self, progress: CheckoutProgress) {
+        let Some(screen) = self.checkout.as_mut() else {
+            return;
+        };
+        screen.record(progress);
+        match &screen.result {
+            Some(Ok(revision)) => {
+                let msg = format!(
+                    "checked out revision {revision} into {}",
+                    screen.dest.display()
+                );
+                let dest = screen.dest.clone();
+                self.checkout = None;
+                self.switch_working_copy(dest);
+                self.push_message(msg);
             }
-            _ => {}
+            Some(Err(e)) => {
+                let msg = format!("checkout failed: {e}");
+                self.push_message(msg);
+            }
+            None => {}
         }
     }
 
-    fn current_mouse_section(&self) -> Option<AppSection> {
-        for (area, app_section) in [
-            // this needs to be in the order that popups/dialogs sit above section in Main,
-            // as the rects for each section are still Some(_) even wh en popups are above them
-            (self.change_popup_area, AppSection::ChangePopup),
-            (self.changes_area, AppSection::Changes),
-        ] {
-            if let Some(area) = area {
-                let pos = Position {
-                    x: self.mouse_loc.1,
-                    y: self.mouse_loc.0,
-                };
-                if area.contains(pos) {
-                    return Some(app_section);
+    /// Merges the revisions of the source into the working copy, showing svn's output on the
+    /// output screen until it finishes
+    fn start_merge(&mut self, source: &str, revisions: &str) {
+        let operation = Operation::Merge {
+            source: source.to_string(),
+            revisions: revisions.to_string(),
+        };
+        self.run_job(Job::Operation(operation));
+    }
+
+    /// Runs the job, or queues it if another svn command is running
+    fn run_job(&mut self, job: Job) {
+        if self.worker.is_busy() {
+            let waiting = self.worker.queue(job.clone());
+            self.push_message(format!("queued {}, {waiting} waiting", job.describe()));
+            return;
+        }
+        match job {
+            Job::Operation(operation) => {
+                let started = self.start_operation(&operation);
+                if started.is_ok() {
+                    self.worker.started(Job::Operation(operation.clone()));
+                }
+                self.show_output(operation, started);
+            }
+            Job::Action { action, paths } => {
+                let queued = paths.iter().map(PathBuf::from).collect();
+                let previous = std::mem::replace(&mut self.multiselection, queued);
+                match self.selection_actions().contains(&action) {
+                    true => self.perform_action(action),
+                    false => self.push_message(format!(
+                        "skipped {}, it no longer applies",
+                        Job::Action { action, paths }.describe()
+                    )),
+                }
+                self.multiselection = previous;
+            }
+            Job::Fix(fix) => self.run_fix(fix),
+            Job::Export { path, dest } => self.export(&path, &dest),
+            Job::CommitHunks { path, message } => {
+                match self
+                    .hunks
+                    .as_ref()
+                    .is_some_and(|screen| screen.path == path)
+                {
+                    true => self.commit_picked_hunks(&message),
+                    false => self.push_message(format!(
+                        "skipped {}, its hunks screen was closed",
+                        Job::CommitHunks { path, message }.describe()
+                    )),
                 }
             }
         }
-        None
     }
 
-    fn handle_mouse_move(&mut self) {}
-
-    fn perform_svn_function(&mut self, func: fn(&[&str]) -> svn::error::Result<CmdResult>) {
-        if let Some(selected) = self.get_selected_changes() {
-            let paths = selected.into_iter().fold(vec![], |mut a, b| {
-                a.push(b.1.to_string_lossy().to_string());
-                a
-            });
-            let path_strs: Vec<&str> = paths.iter().map(|s| s.as_ref()).collect();
-            match func(path_strs.as_slice()) {
-                Ok(res) if res.success() => self.update_svn_status(),
-                Ok(res) => self
-                    .events
-                    .send(AppEvent::Message(res.output().to_string())), // TODO delete reaches here when the file has modification, as svn requires --force to be passed, this could be used to have a "are you sure?" dialog
-                Err(e) => self.events.send(AppEvent::Message(e.to_string())),
-            }
+    /// Runs the job again after the retry policy's backoff, waiting on a thread of its own so
+    /// the event loop doesn't, unless it has been retried as many times as allowed
+    fn retry_later(&mut self, job: Job) {
+        let policy = self.config.retry_policy();
+        let retry = self.retrying + 1;
+        if retry > policy.retries {
+            return;
+        }
+        self.events
+            .send(AppEvent::Message(policy.retry_message(retry)));
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            std::thread::sleep(policy.delay(retry));
+            send(AppEvent::Retry { job, retry });
+        });
+    }
+
+    /// Starts the operation's svn command, sending its output back as it goes
+    fn start_operation(&self, operation: &Operation) -> svn::error::Result<()> {
+        let send = self.events.app_sender();
+        let on_progress = move |progress| send(AppEvent::Output(progress));
+        let policy = self.config.retry_policy();
+        match operation {
+            Operation::Merge { source, revisions } => {
+                let args = svn::merge::merge_revision_args(revisions)
+                    .map_err(svn::error::Error::Unknown)?;
+                svn::merge::start_merge(source, &args, &policy, on_progress)
+            }
+            Operation::Update { revision } => svn::start_update(revision, &policy, on_progress),
+            Operation::Commit {
+                paths,
+                message,
+                keep_locks,
+            } => svn::start_commit(message, *keep_locks, paths, &policy, on_progress),
+            Operation::Custom { command: line } => {
+                let (shell, flag) = command::shell();
+                Ok(command::stream_command(
+                    shell,
+                    &[flag, line],
+                    &policy,
+                    on_progress,
+                )?)
+            }
+        }
+    }
+
+    /// Shows the output of the operation as it arrives, or why it couldn't be started. A
+    /// queued operation starting doesn't take the user away from a prompt or popup.
+    fn show_output(&mut self, operation: Operation, started: svn::error::Result<()>) {
+        let mut screen = OutputScreen::new(operation);
+        if let Err(e) = started {
+            screen.result = Some(Err(e.to_string()));
+        }
+        self.output = Some(screen);
+        if matches!(self.state, AppState::Main | AppState::Output) {
+            self.state = AppState::Output;
+        }
+    }
+
+    fn handle_output_progress(&mut self, progress: CommandProgress) {
+        let Some(screen) = self.output.as_mut() else {
+            return;
+        };
+        screen.record(progress);
+        let Some(result) = screen.result.clone() else {
+            return;
+        };
+        let mut finished_update = None;
+        match (&screen.operation, result) {
+            (Operation::Merge { .. }, Err(e)) => {
+                self.push_message(format!("merge failed: {}", svn::error::Error::from(e)))
+            }
+            (Operation::Merge { source, .. }, Ok(())) => {
+                let msg = match screen.conflicts() {
+                    0 => format!("merged {source}"),
+                    n => format!("merged {source} with {n} conflicts to resolve"),
+                };
+                self.push_message(msg);
+            }
+            (Operation::Update { .. }, Err(e)) => {
+                self.push_message(format!("update failed: {}", svn::error::Error::from(e)))
+            }
+            (Operation::Update { .. }, Ok(())) => {
+                let summary = screen.update_summary();
+                if let Some(rev) = summary.revision {
+                    self.updated_revision = Some(rev);
+                    self.push_message(format!("updated to revision {rev}"));
+                }
+                // an update to HEAD leaves nothing behind, but one to an older revision may not
+                self.behind = BehindCheck::default();
+                self.last_commit_stale = true;
+                finished_update = Some(summary);
+            }
+            (Operation::Commit { paths, message, .. }, Ok(())) => {
+                let msg = match paths.len() {
+                    1 => format!("committed {}", paths[0]),
+                    count => format!("committed {count} changes"),
+                };
+                let (paths, message, output) =
+                    (paths.clone(), message.clone(), screen.output.join("\n"));
+                self.commit_draft.clear();
+                self.push_message(msg);
+                self.commit_succeeded(&paths, &message, &output);
+            }
+            (Operation::Commit { paths, message, .. }, Err(e)) => {
+                // the message is offered again when the commit is retried
+                self.commit_draft = message.clone();
+                if let Some(precondition) = command::unmet_precondition(&e) {
+                    self.pending_fix = Some(PendingFix {
+                        precondition,
+                        action: Action::Commit,
+                        paths: paths.clone(),
+                    });
+                }
+                self.push_message(format!("commit failed: {}", svn::error::Error::from(e)));
+            }
+            (Operation::Custom { command }, Ok(())) => {
+                let msg = match screen.output.last() {
+                    Some(last) => format!("{command}: {last}"),
+                    None => format!("{command} finished"),
+                };
+                self.push_message(msg);
+            }
+            (Operation::Custom { command }, Err(e)) => {
+                let msg = format!("{command} failed: {}", svn::error::Error::from(e));
+                self.push_message(msg);
+            }
+        }
+        self.update_svn_status();
+        if let Some(summary) = finished_update {
+            self.show_update_summary(summary);
+        }
+        // run what was queued while it ran, until one of them runs in the background
+        let mut next = self.worker.finished();
+        while let Some(job) = next {
+            self.run_job(job);
+            next = match self.worker.is_busy() {
+                true => None,
+                false => self.worker.finished(),
+            };
+        }
+    }
+
+    fn handle_output_key_event(&mut self, key_event: KeyEvent) {
+        let page = self.page_height();
+        let Some(screen) = self.output.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let running = screen.result.is_none();
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Down => screen.scroll_by(1, page),
+            KeyCode::Up => screen.scroll_by(-1, page),
+            KeyCode::PageDown => screen.scroll_by(page as isize, page),
+            KeyCode::PageUp => screen.scroll_by(-(page as isize), page),
+            KeyCode::Home => screen.scroll = Some(0),
+            KeyCode::End => screen.scroll = None,
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') if running => {
+                let title = screen.operation.title().to_lowercase();
+                self.state = AppState::Main;
+                self.push_message(format!("the {title} carries on, J to watch it"))
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                self.output = None;
+                self.state = AppState::Main;
+                // straight into resolving whatever the merge conflicted
+                if self.file_list.has_conflicts() {
+                    self.focus = AppSection::Conflicts;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Shows what the update did over the changes, unless the user has since gone on to
+    /// something else. Conflicts it left are focused with the first one selected, ready to
+    /// resolve once the popup is closed.
+    fn show_update_summary(&mut self, summary: UpdateSummary) {
+        if summary.conflicted > 0 && self.file_list.has_conflicts() {
+            self.focus = AppSection::Conflicts;
+            self.scroll_conflicts_to(0);
+            self.select_first_conflict();
+        }
+        if matches!(self.state, AppState::Main | AppState::Output) {
+            self.update_summary = Some(summary);
+            self.state = AppState::UpdateSummary;
+        }
+    }
+
+    fn handle_update_summary_key_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                self.update_summary = None;
+                self.output = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Char('J') if self.output.is_some() => {
+                self.update_summary = None;
+                self.state = AppState::Output;
+            }
+            _ => {}
+        }
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind, input: &str) {
+        self.close_change_popup();
+        self.prompt = Some(Prompt::new(kind, input));
+        self.state = AppState::Prompt;
+    }
+
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        let input = prompt.input.trim();
+        match prompt.kind {
+            PromptKind::RenameChangelist(name) => {
+                if input.is_empty() {
+                    self.push_message("a changelist name can't be empty".into());
+                } else if input != name {
+                    let members = self.changelist_member_paths(&name);
+                    self.run_svn_function(&members, |paths| svn::svn_changelist(input, paths));
+                }
+            }
+            PromptKind::ExportReport => self.export_report(input),
+            PromptKind::ChangeDirectory => self.change_directory(input),
+            PromptKind::PinFavorite => self.pin_favorite(input),
+            PromptKind::DrillIn => self.drill_in(input),
+            PromptKind::Scope => self.scope_to(input),
+            PromptKind::Export(path) => self.export(&path, input),
+            PromptKind::UpdateToRevision => self.update_to_revision(input),
+            PromptKind::CommitHunks if input.is_empty() => {
+                self.hunks = None;
+                self.push_message("commit cancelled, the message was empty".into());
+            }
+            PromptKind::CommitHunks => self.commit_picked_hunks(input),
+            PromptKind::CommitMessage(_) if input.is_empty() => {
+                self.commit_review = None;
+                self.push_message("commit cancelled, the message was empty".into());
+            }
+            PromptKind::CommitMessage(_) => {
+                if let Some(review) = self.commit_review.as_mut() {
+                    review.message = input.to_string();
+                    self.state = AppState::CommitReview;
+                }
+            }
+            PromptKind::MergeRevisions(source) => match svn::merge::merge_revision_args(input) {
+                Ok(_) => self.start_merge(&source, input),
+                Err(e) => self.push_message(e),
+            },
+            // answered with a single key rather than submitted
+            PromptKind::Confirm(..) => {}
+        }
+    }
+
+    fn report(&self) -> Report<'_> {
+        Report {
+            branch: &self.current_branch,
+            working_copy: &self.cwd,
+            generated: self.config.format_timestamp(Utc::now()),
+            changes: self.file_list.renderable(),
+            conflicts: self.file_list.conflicts(),
+        }
+    }
+
+    /// Writes a report of the branch, changes and conflicts to the path, or copies a Markdown
+    /// one to the clipboard if the path is empty. "{timestamp}" in the path is replaced by
+    /// the time.
+    fn export_report(&mut self, path: &str) {
+        let report = self.report();
+        let message = match path {
+            "" => match copy_to_clipboard(&report.render(ReportFormat::Markdown)) {
+                Ok(()) => "copied the status report to the clipboard".to_string(),
+                Err(e) => format!("could not copy the report: {e}"),
+            },
+            path => {
+                let path = timestamped_path(path, Utc::now());
+                let text = report.render(ReportFormat::from_path(&path));
+                match std::fs::write(&path, text) {
+                    Ok(()) => format!("exported the status report to {}", path.display()),
+                    Err(e) => format!("could not write {}: {e}", path.display()),
+                }
+            }
+        };
+        self.push_message(message);
+    }
+
+    /// Reads the status without a terminal and writes a report of it to the path, in the
+    /// format its extension picks
+    pub fn export_status(mut self, path: &str) -> color_eyre::Result<()> {
+        self.start_headless()?;
+        self.update_svn_status();
+        let path = timestamped_path(path, Utc::now());
+        let text = self.report().render(ReportFormat::from_path(&path));
+        std::fs::write(&path, text)
+            .map_err(|e| color_eyre::eyre::eyre!("could not write {}: {e}", path.display()))
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> color_eyre::Result<()> {
+        self.mouse_loc = (mouse_event.row, mouse_event.column);
+        match mouse_event.kind {
+            MouseEventKind::Down(btn) => self.handle_click(btn),
+            MouseEventKind::Drag(MouseButton::Left) => self.handle_mouse_drag(),
+            MouseEventKind::Up(MouseButton::Left) => self.drag_anchor = None,
+            MouseEventKind::ScrollDown => self.handle_mouse_scroll(Direction::Down),
+            MouseEventKind::ScrollUp => self.handle_mouse_scroll(Direction::Up),
+            MouseEventKind::Moved => self.handle_mouse_move(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handles the tick event of the terminal.
+    ///
+    /// The tick event is where you can update the state of your application with any logic that
+    /// needs to be updated at a fixed frame rate. E.g. polling a server, updating an animation.
+    fn tick(&mut self) {
+        if matches!(
+            self.state,
+            AppState::Workspaces | AppState::Checkout | AppState::Output
+        ) {
+            // the dashboard is only refreshed on request, as it runs a status per workspace,
+            // there's no working copy to refresh until a checkout finishes, and a merge has
+            // the working copy locked
+            return;
+        }
+        if self.loading_status.is_some() {
+            self.load_next_status_chunk();
+            return;
+        }
+        if self.config.auto_refresh
+            && time_for_update(self.last_updated, self.config.refresh_interval)
+        {
+            self.events.send(AppEvent::UpdateRequest);
+        }
+        self.check_behind_if_due();
+    }
+
+    /// Set running to false to quit the application.
+    fn quit(&mut self) {
+        self.running = false;
+    }
+
+    fn push_message(&mut self, msg: String) {
+        self.messages.push(msg);
+        self.last_message_at = Some(Utc::now());
+        if self.messages.len() > MESSAGE_HISTORY_LENGTH {
+            self.messages.remove(0);
+        }
+    }
+
+    /// Loads the next path in the initial status queue, or the whole status if the queue is
+    /// empty. The first chunk replaces the placeholder list, later chunks are appended.
+    fn load_next_status_chunk(&mut self) {
+        let Some(queue) = self.loading_status.as_mut() else {
+            return;
+        };
+        let Some((path, depth)) = queue.pop() else {
+            self.update_svn_status();
+            return;
+        };
+        let finished = queue.is_empty();
+        match self.backend.status_with_depth(&path, depth) {
+            Ok(status) => {
+                let selected = self.selected_row_key();
+                self.file_list.extend_changes(status);
+                self.reselect_row(selected);
+            }
+            Err(error) => self.events.send(AppEvent::Message(error.to_string())),
+        }
+        if finished {
+            self.loading_status = None;
+            self.last_updated = Utc::now();
+        }
+    }
+
+    /// Updates the branch and status, unless the working copy can't be reached
+    fn refresh(&mut self) {
+        if self.svn_missing || self.not_working_copy.is_some() {
+            return;
+        }
+        match (check_path_available(&self.cwd), self.unavailable.take()) {
+            (Ok(()), None) => {}
+            (Ok(()), Some(_)) => self.push_message("the working copy is available again".into()),
+            (Err(reason), previous) => {
+                if previous.is_none() {
+                    let now = self.config.format_timestamp(Utc::now());
+                    self.push_message(format!("{}: {reason} at {now}", self.cwd.display()));
+                }
+                self.unavailable = Some(reason);
+                // check again after the refresh interval
+                self.last_updated = Utc::now();
+                return;
+            }
+        }
+        self.update_branch_name();
+        self.fetch_last_commit_if_stale();
+        self.update_svn_status();
+        self.refreshed_at = Some(Utc::now());
+    }
+
+    /// Whether a refresh finished just now
+    fn refresh_flashing(&self) -> bool {
+        self.refreshed_at
+            .is_some_and(|at| !time_for_update(at, REFRESH_FLASH_DURATION))
+    }
+
+    fn update_svn_status(&mut self) {
+        // a full status makes any partially loaded initial status redundant
+        self.loading_status = None;
+        // TODO error popup here?
+        match self.read_status() {
+            Ok(status) => {
+                let conflicted: HashSet<PathBuf> = self
+                    .file_list
+                    .conflicted_paths()
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                let selected = self.selected_row_key();
+                let changed = self.file_list.replace_changes(status);
+                if changed {
+                    self.notify_new_conflicts(&conflicted);
+                    self.changes_updated();
+                    self.reselect_row(selected);
+                    self.reveal_new_conflicts(&conflicted);
+                    self.run_refresh_hook();
+                }
+                if self.config.auto_resolve_trivial
+                    && (!self.backend.read_only() || Action::AutoResolve.allowed_read_only())
+                {
+                    let new: Vec<PathBuf> = self
+                        .file_list
+                        .conflicted_paths()
+                        .into_iter()
+                        .filter(|path| !conflicted.contains(*path))
+                        .cloned()
+                        .collect();
+                    if !new.is_empty() {
+                        self.resolve_trivial_conflicts(&new);
+                    }
+                }
+            }
+            Err(error) => self.events.send(AppEvent::Message(error.to_string())),
+        }
+        self.last_updated = Utc::now();
+    }
+
+    /// Runs the on_refresh hook with the counts of the changes and conflicts now listed
+    fn run_refresh_hook(&mut self) {
+        if let Some(hook) = self.config.on_refresh.clone() {
+            let changes = self.file_list.renderable().len().to_string();
+            let conflicts = self.file_list.conflicted_paths().len().to_string();
+            self.run_hook(
+                "on_refresh",
+                &hook,
+                &[
+                    ("SVN_UTIL_CHANGES", &changes),
+                    ("SVN_UTIL_CONFLICTS", &conflicts),
+                ],
+            );
+        }
+    }
+
+    /// Refreshes only the status of the paths, which is much quicker than a full status in
+    /// a big working copy. Falls back to a full status if that fails.
+    fn update_svn_status_of(&mut self, paths: &[String]) {
+        if self.loading_status.is_some() {
+            // the rest of the initial status still has to be loaded
+            return self.update_svn_status();
+        }
+        let path_strs: Vec<&str> = paths.iter().map(String::as_str).collect();
+        match svn::get_svn_status_of(&path_strs) {
+            Ok(status) => {
+                let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+                let selected = self.selected_row_key();
+                self.file_list.patch_changes(&paths, status);
+                self.changes_updated();
+                self.reselect_row(selected);
+            }
+            Err(_) => self.update_svn_status(),
+        }
+    }
+
+    fn changes_updated(&mut self) {
+        if !self.file_list.has_conflicts() {
+            self.focus = AppSection::Changes;
+        }
+        self.expand_conflicted_dirs();
+        self.update_favorites();
+        // forget any selected paths that are no longer listed
+        let listed = self.listed_changes();
+        let retained: Vec<PathBuf> = self
+            .multiselection
+            .iter()
+            .filter(|path| listed.iter().any(|StatusEntry { path: p, .. }| p == *path))
+            .cloned()
+            .collect();
+        self.multiselection = retained;
+    }
+
+    /// Lists the pinned files that have no changes, and still exist, as favorites
+    fn update_favorites(&mut self) {
+        let changes = self.file_list.renderable();
+        self.favorites = self
+            .pinned
+            .iter()
+            .filter(|pin| !changes.iter().any(|StatusEntry { path, .. }| path == *pin))
+            .filter(|pin| self.cwd.join(pin).exists())
+            .map(|pin| StatusEntry::new(State::Clean, pin.clone()))
+            .collect();
+    }
+
+    /// The changes along with the clean favorites, everything the changes list can show
+    fn listed_changes(&self) -> Vec<&StatusEntry> {
+        let mut changes = self.file_list.renderable();
+        changes.extend(&self.favorites);
+        changes
+    }
+
+    /// Expands every directory above a conflict when grouped as a tree, so no conflict is
+    /// hidden under a collapsed one
+    fn expand_conflicted_dirs(&mut self) {
+        if self.config.group_by != GroupBy::Tree {
+            return;
+        }
+        let dirs: Vec<PathBuf> = self
+            .file_list
+            .conflicted_paths()
+            .into_iter()
+            .flat_map(|path| path.ancestors().skip(1))
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .collect();
+        self.expanded_dirs.extend(dirs);
+    }
+
+    /// Selects the first conflict when grouped as a tree and there are conflicts that weren't
+    /// in the `previous` ones, scrolling it into view
+    fn reveal_new_conflicts(&mut self, previous: &HashSet<PathBuf>) {
+        if self.config.group_by != GroupBy::Tree
+            || self
+                .file_list
+                .conflicted_paths()
+                .iter()
+                .all(|path| previous.contains(*path))
+        {
+            return;
+        }
+        self.select_first_conflict();
+    }
+
+    /// Selects the first conflicted change in the list, scrolling it into view
+    fn select_first_conflict(&mut self) {
+        let first = self.rows().iter().position(
+            |row| matches!(row, Row::Change(change) if change.state == State::Conflicting),
+        );
+        if let Some(index) = first {
+            self.select_change(index);
+        }
+    }
+
+    /// Lets the user know about conflicts that weren't in the previous status while they're
+    /// looking at another window
+    fn notify_new_conflicts(&mut self, previous: &HashSet<PathBuf>) {
+        let new: Vec<String> = self
+            .file_list
+            .conflicted_paths()
+            .into_iter()
+            .filter(|path| !previous.contains(*path))
+            .map(|path| path.display().to_string())
+            .collect();
+        if let Some(hook) = self.config.on_conflict_detected.clone()
+            && !new.is_empty()
+        {
+            let paths = new.join("\n");
+            self.run_hook("on_conflict_detected", &hook, &[("SVN_UTIL_PATHS", &paths)]);
+        }
+        if self.has_focus || !self.config.desktop_notifications {
+            return;
+        }
+        let title = match new.len() {
+            0 => return,
+            1 => "svn-util: new conflict".to_string(),
+            n => format!("svn-util: {n} new conflicts"),
+        };
+        if let Err(e) = notify(&title, &new.join("\n")) {
+            self.push_message(format!("could not send a desktop notification: {e}"));
+        }
+    }
+
+    fn update_branch_name(&mut self) {
+        match self.backend.branch_name(&self.cwd) {
+            Ok(branch) => {
+                self.current_branch = branch.clone();
+                if let Some(previous) = self.known_branch.replace(branch)
+                    && previous != self.current_branch
+                {
+                    self.last_commit_stale = true;
+                    self.run_branch_change_hook(&previous);
+                }
+            }
+            Err(e) => self.current_branch = e.to_string(),
+        }
+    }
+
+    /// Reads the working copy's newest revision in the background, if it may have changed
+    fn fetch_last_commit_if_stale(&mut self) {
+        if !self.last_commit_stale || self.backend.read_only() {
+            return;
+        }
+        self.last_commit_stale = false;
+        let path = self.cwd.to_string_lossy().to_string();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = get_last_commit(&path, &policy).map_err(|e| e.to_string());
+            send(AppEvent::LastCommit { path, result });
+        });
+    }
+
+    fn handle_last_commit(&mut self, path: &str, result: Result<Option<LogEntry>, String>) {
+        // the working copy may have been switched while it was read
+        if path != self.cwd.to_string_lossy() {
+            return;
+        }
+        match result {
+            Ok(entry) => self.last_commit = entry,
+            // the log needs the repository, so it's tried again on the next refresh
+            Err(_) => self.last_commit_stale = true,
+        }
+    }
+
+    fn run_branch_change_hook(&mut self, previous: &str) {
+        let Some(hook) = &self.config.on_branch_change else {
+            return;
+        };
+        let command = expand_branch_hook(hook, &self.current_branch, previous);
+        self.run_hook(
+            "on_branch_change",
+            &command,
+            &[("SVN_UTIL_PREVIOUS_BRANCH", previous)],
+        );
+    }
+
+    /// Follows up any commit that succeeded, whichever way it was made, with svn's output
+    fn commit_succeeded(&mut self, paths: &[String], message: &str, output: &str) {
+        self.last_commit_stale = true;
+        if let Some(hook) = self.config.on_commit_success.clone() {
+            let revision = svn::parse_committed_revision(output)
+                .map(|rev| rev.to_string())
+                .unwrap_or_default();
+            self.run_hook(
+                "on_commit_success",
+                &hook,
+                &[
+                    ("SVN_UTIL_REVISION", &revision),
+                    ("SVN_UTIL_COMMIT_MESSAGE", message),
+                    ("SVN_UTIL_PATHS", &paths.join("\n")),
+                ],
+            );
+        }
+    }
+
+    /// Runs a hook from the config without waiting for it, with the branch and working copy,
+    /// and anything more about what happened, in SVN_UTIL_* environment variables
+    fn run_hook(&mut self, name: &str, command: &str, context: &[(&str, &str)]) {
+        let working_copy = self.cwd.to_string_lossy().to_string();
+        let mut env = vec![
+            ("SVN_UTIL_BRANCH", self.current_branch.as_str()),
+            ("SVN_UTIL_WORKING_COPY", working_copy.as_str()),
+        ];
+        env.extend_from_slice(context);
+        if let Err(e) = spawn_shell(command, &env) {
+            self.push_message(format!("{name} hook failed: {e}"));
+        }
+    }
+
+    /// Shows the dashboard of the configured workspaces, reading each one's status
+    fn open_workspaces(&mut self) {
+        if self.config.workspaces.is_empty() {
+            self.push_message(
+                "no workspaces configured, add them to the workspaces setting".into(),
+            );
+            return;
+        }
+        self.show_workspaces(self.config.workspaces.clone());
+    }
+
+    /// Shows the dashboard of the working copies, reading each one's status
+    fn show_workspaces(&mut self, paths: Vec<PathBuf>) {
+        self.close_change_popup();
+        self.workspaces = paths
+            .iter()
+            .map(|path| WorkspaceSummary::loading(path))
+            .collect();
+        self.load_workspaces(paths);
+        let selected = self
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.path == self.cwd)
+            .unwrap_or(0);
+        self.workspaces_list_state.select(Some(selected));
+        self.state = AppState::Workspaces;
+    }
+
+    /// Reads the working copies' branches and statuses a few at a time in the background, each
+    /// shown on the dashboard as soon as it's read
+    fn load_workspaces(&self, paths: Vec<PathBuf>) {
+        let workers = WORKSPACE_STATUS_WORKERS.min(paths.len());
+        let queue = Arc::new(Mutex::new(VecDeque::from(paths)));
+        for _ in 0..workers {
+            let queue = Arc::clone(&queue);
+            let send = self.events.app_sender();
+            std::thread::spawn(move || {
+                loop {
+                    let next = queue.lock().ok().and_then(|mut queue| queue.pop_front());
+                    let Some(path) = next else {
+                        break;
+                    };
+                    send(AppEvent::Workspace(WorkspaceSummary::load(&path)));
+                }
+            });
+        }
+    }
+
+    fn open_bookmarks(&mut self) {
+        if self.config.bookmarks.is_empty() {
+            self.push_message("no bookmarks configured, add them to the bookmarks setting".into());
+            return;
+        }
+        self.close_change_popup();
+        let selected = self
+            .config
+            .bookmarks
+            .iter()
+            .position(|path| *path == self.cwd)
+            .unwrap_or(0);
+        self.bookmarks_list_state.select(Some(selected));
+        self.state = AppState::Bookmarks;
+    }
+
+    fn handle_bookmarks_key_event(&mut self, key_event: KeyEvent) {
+        let count = self.config.bookmarks.len();
+        let selected = self.bookmarks_list_state.selected().unwrap_or(0);
+        let open = match key_event.code {
+            KeyCode::Esc | KeyCode::Char('b' | 'q') => {
+                self.state = AppState::Main;
+                None
+            }
+            KeyCode::Down => {
+                self.bookmarks_list_state
+                    .select(Some((selected + 1).min(count - 1)));
+                None
+            }
+            KeyCode::Up => {
+                self.bookmarks_list_state
+                    .select(Some(selected.saturating_sub(1)));
+                None
+            }
+            KeyCode::Enter => Some(selected),
+            // the first nine can be jumped to by number
+            KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize),
+            _ => None,
+        };
+        if let Some(path) = open.and_then(|index| self.config.bookmarks.get(index)) {
+            match *path == self.cwd {
+                true => self.state = AppState::Main,
+                false => self.switch_working_copy(path.clone()),
+            }
+        }
+    }
+
+    /// Lists the cached branches straight away, listing them again in the background if the
+    /// cache is missing or old
+    fn open_branch_switcher(&mut self, purpose: BranchPurpose) {
+        if self.backend.read_only() {
+            self.push_message(format!(
+                "can't switch branches in a {} working copy",
+                self.backend.name()
+            ));
+            return;
+        }
+        if let Some(reason) = self.unsupported(ClientFeature::ShowItem) {
+            self.push_message(format!("the branch switcher {reason}"));
+            return;
+        }
+        let uuid = match svn::get_repo_uuid(&self.cwd) {
+            Ok(uuid) => uuid,
+            Err(e) => {
+                self.push_message(e.to_string());
+                return;
+            }
+        };
+        self.close_change_popup();
+        let cached =
+            config::branch_cache_path().and_then(|path| load_cached_branches(&path, &uuid));
+        let mut switcher = BranchSwitcher::new(purpose, uuid, cached);
+        if let Some(index) = switcher
+            .branches
+            .iter()
+            .position(|url| is_current_branch(url, &self.current_branch))
+        {
+            switcher.list_state.select(Some(index));
+        }
+        let stale = switcher.is_stale();
+        self.branch_switcher = Some(switcher);
+        self.state = AppState::Branches;
+        if stale {
+            self.list_branches();
+        }
+    }
+
+    /// Lists the branches in the background, as `svn ls` can take a while on big repositories
+    fn list_branches(&mut self) {
+        let Some(switcher) = self.branch_switcher.as_mut() else {
+            return;
+        };
+        if switcher.loading {
+            return;
+        }
+        switcher.loading = true;
+        switcher.error = None;
+        let uuid = switcher.uuid.clone();
+        let roots = self.config.branch_roots.clone();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = svn::list_branches(&roots, &policy).map_err(|e| e.to_string());
+            send(AppEvent::Branches { uuid, result });
+        });
+    }
+
+    fn handle_branches_listed(&mut self, uuid: &str, result: Result<Vec<String>, String>) {
+        // the popup may have been closed, or reopened in another repository, since
+        let Some(switcher) = self.branch_switcher.as_mut().filter(|s| s.uuid == uuid) else {
+            return;
+        };
+        switcher.loading = false;
+        match result {
+            Ok(branches) => {
+                switcher.set_branches(branches);
+                switcher.fetched = Some(Utc::now());
+                if let Some(path) = config::branch_cache_path()
+                    && let Err(e) = save_cached_branches(&path, uuid, switcher.to_cache())
+                {
+                    self.push_message(format!("could not cache the branches: {e}"));
+                }
+            }
+            Err(e) => switcher.error = Some(e),
+        }
+    }
+
+    fn handle_branches_key_event(&mut self, key_event: KeyEvent) {
+        let Some(switcher) = self.branch_switcher.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let count = switcher.branches.len();
+        let selected = switcher.list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('B' | 'q') => {
+                self.branch_switcher = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down if count > 0 => {
+                switcher
+                    .list_state
+                    .select(Some((selected + 1).min(count - 1)));
+            }
+            KeyCode::Up => switcher.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char('r') => self.list_branches(),
+            KeyCode::Enter => {
+                let Some(url) = switcher.selected().cloned() else {
+                    return;
+                };
+                let purpose = switcher.purpose;
+                let is_current = is_current_branch(&url, &self.current_branch);
+                self.branch_switcher = None;
+                self.state = AppState::Main;
+                match purpose {
+                    BranchPurpose::Switch if !is_current => self.switch_branch(&url),
+                    BranchPurpose::Switch => {}
+                    BranchPurpose::MergeFrom | BranchPurpose::Mergeinfo if is_current => {
+                        self.push_message("can't merge a branch into itself".into())
+                    }
+                    BranchPurpose::DiffSummary => self.open_diff_summary(&url),
+                    BranchPurpose::Mergeinfo => self.show_mergeinfo(&url),
+                    BranchPurpose::MergeFrom => {
+                        self.open_prompt(PromptKind::MergeRevisions(url), "")
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reads the history of the working copy's branch, and the branches it was copied from,
+    /// in the background while the graph screen shows it's loading
+    fn open_revision_graph(&mut self) {
+        if self.backend.read_only() {
+            let name = self.backend.name();
+            self.push_message(format!("no revision graph for a {name} working copy"));
+            return;
+        }
+        if let Some(reason) = self.unsupported(ClientFeature::ShowItem) {
+            self.push_message(format!("the revision graph {reason}"));
+            return;
+        }
+        self.close_change_popup();
+        self.revision_graph = Some(RevisionGraphScreen::default());
+        self.state = AppState::RevisionGraph;
+        let cwd = self.cwd.clone();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = svn::get_repo_path(&cwd)
+                .and_then(|path| svn::log::get_revision_graph(&path, &policy))
+                .map_err(|e| e.to_string());
+            send(AppEvent::RevisionGraph(result));
+        });
+    }
+
+    fn handle_revision_graph_key_event(&mut self, key_event: KeyEvent) {
+        let Some(screen) = self.revision_graph.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let count = match &screen.result {
+            Some(Ok(branches)) => graph_rows(branches).len(),
+            _ => 0,
+        };
+        let selected = screen.list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('G' | 'q') => {
+                self.revision_graph = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down if count > 0 => screen
+                .list_state
+                .select(Some((selected + 1).min(count - 1))),
+            KeyCode::Up => screen.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Home => screen.list_state.select(Some(0)),
+            KeyCode::End if count > 0 => screen.list_state.select(Some(count - 1)),
+            _ => {}
+        }
+    }
+
+    fn open_history(&mut self) {
+        self.open_history_for(HistoryPurpose::Browse);
+    }
+
+    /// Picks a revision from the selected file's history to restore its contents from
+    fn open_restore_revision(&mut self) {
+        self.open_history_for(HistoryPurpose::Restore);
+    }
+
+    /// Reads the revisions that changed the selected file in the background, while its
+    /// history screen shows it's loading
+    fn open_history_for(&mut self, purpose: HistoryPurpose) {
+        let path = match self.selected_paths().as_slice() {
+            [path] => path.clone(),
+            _ => {
+                self.push_message("pick a single path to show the history of".into());
+                return;
+            }
+        };
+        self.close_change_popup();
+        self.history = Some(HistoryScreen::new(&path, purpose));
+        self.state = AppState::History;
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = svn::log::get_file_history(&path, &policy).map_err(|e| e.to_string());
+            send(AppEvent::History { path, result });
+        });
+    }
+
+    fn handle_history_key_event(&mut self, key_event: KeyEvent) {
+        let Some(screen) = self.history.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let count = screen.entries().len();
+        let selected = screen.list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('V' | 'q') => {
+                self.history = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down if count > 0 => screen
+                .list_state
+                .select(Some((selected + 1).min(count - 1))),
+            KeyCode::Up => screen.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Home => screen.list_state.select(Some(0)),
+            KeyCode::End if count > 0 => screen.list_state.select(Some(count - 1)),
+            KeyCode::Enter if screen.purpose == HistoryPurpose::Restore => {
+                self.restore_selected_revision()
+            }
+            KeyCode::Enter | KeyCode::Char('d') => self.show_revision_diff(),
+            _ => {}
+        }
+    }
+
+    /// Replaces the file's contents with its contents at the revision picked in the history,
+    /// keeping the current contents to undo it
+    fn restore_selected_revision(&mut self) {
+        let Some(screen) = self.history.as_ref() else {
+            return;
+        };
+        let Some(revision) = screen.selected_entry().map(|entry| entry.revision) else {
+            return;
+        };
+        let path = screen.path.clone();
+        let contents = match svn::svn_cat(&path, revision) {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.push_message(format!("could not read {path} at r{revision}: {e}"));
+                return;
+            }
+        };
+        let previous = std::fs::read_to_string(&path).ok();
+        if let Err(e) = std::fs::write(&path, contents) {
+            self.push_message(format!("could not write {path}: {e}"));
+            return;
+        }
+        self.history = None;
+        self.state = AppState::Main;
+        self.push_message(format!("restored {path} to r{revision}"));
+        self.last_undo = Some(Undo::Restore { path, previous });
+        self.update_svn_status();
+    }
+
+    /// Shows what the revision selected in the history changed in its file
+    fn show_revision_diff(&mut self) {
+        let Some(screen) = self.history.as_ref() else {
+            return;
+        };
+        let Some(revision) = screen.selected_entry().map(|entry| entry.revision) else {
+            return;
+        };
+        match svn::get_revision_diff(&screen.path, revision) {
+            Ok(diff) if diff.trim().is_empty() => {
+                self.push_message(format!("r{revision} didn't change the contents"))
+            }
+            Ok(diff) => self.pending_pager = Some(diff),
+            Err(e) => self.push_message(e.to_string()),
+        }
+    }
+
+    /// Blames the selected file in the background, while its blame screen shows it's loading
+    fn open_blame(&mut self) {
+        let path = match self.selected_paths().as_slice() {
+            [path] => path.clone(),
+            _ => {
+                self.push_message("pick a single file to blame".into());
+                return;
+            }
+        };
+        self.close_change_popup();
+        self.blame = Some(BlameScreen::new(&path));
+        self.state = AppState::Blame;
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = svn::blame::get_blame(&path).map_err(|e| e.to_string());
+            send(AppEvent::Blame { path, result });
+        });
+    }
+
+    fn handle_blame_key_event(&mut self, key_event: KeyEvent) {
+        let page = self.page_height() as isize;
+        let Some(screen) = self.blame.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('K' | 'q') => {
+                self.blame = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down => screen.scroll_by(1),
+            KeyCode::Up => screen.scroll_by(-1),
+            KeyCode::PageDown => screen.scroll_by(page),
+            KeyCode::PageUp => screen.scroll_by(-page),
+            KeyCode::Home => screen.scroll = 0,
+            KeyCode::End => screen.scroll_by(isize::MAX),
+            _ => {}
+        }
+    }
+
+    /// Lists the paths that differ between the working copy's branch and the other url in
+    /// the background, to preview what a merge would touch
+    fn open_diff_summary(&mut self, other: &str) {
+        self.diff_summary = Some(DiffSummaryScreen::new(other));
+        self.state = AppState::DiffSummary;
+        let cwd = self.cwd.clone();
+        let other = other.to_string();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = svn::get_url(&cwd)
+                .and_then(|base| svn::get_diff_summary(&base, &other, &policy))
+                .map_err(|e| e.to_string());
+            send(AppEvent::DiffSummary { other, result });
+        });
+    }
+
+    fn handle_diff_summary_key_event(&mut self, key_event: KeyEvent) {
+        let Some(screen) = self.diff_summary.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let count = screen.paths().len();
+        let selected = screen.list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('D' | 'q') => {
+                self.diff_summary = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down if count > 0 => screen
+                .list_state
+                .select(Some((selected + 1).min(count - 1))),
+            KeyCode::Up => screen.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Home => screen.list_state.select(Some(0)),
+            KeyCode::End if count > 0 => screen.list_state.select(Some(count - 1)),
+            _ => {}
+        }
+    }
+
+    /// Shows which revisions of the source are still to be merged into the working copy, and
+    /// which already have been, in the pager once they're read in the background
+    fn show_mergeinfo(&mut self, source: &str) {
+        self.push_message(format!("reading the mergeinfo of {source}"));
+        let source = source.to_string();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            for (revs, heading) in [
+                (svn::MergeinfoRevs::Eligible, "Eligible to merge"),
+                (svn::MergeinfoRevs::Merged, "Already merged"),
+            ] {
+                match svn::get_mergeinfo(&source, revs, &policy) {
+                    Ok(revisions) => text.push_str(&format_mergeinfo(heading, &source, &revisions)),
+                    Err(e) => return send(AppEvent::Mergeinfo(Err(e.to_string()))),
+                }
+            }
+            send(AppEvent::Mergeinfo(Ok(text)));
+        });
+    }
+
+    fn switch_branch(&mut self, url: &str) {
+        self.push_message(format!("switching to {url}"));
+        if self.run_svn_function(&[], |_| svn::svn_switch(url)) {
+            // a switch goes to HEAD of the branch
+            self.updated_revision = None;
+            self.behind = BehindCheck::default();
+            self.last_commit_stale = true;
+        }
+        self.update_branch_name();
+    }
+
+    fn select_workspace(&mut self, index: usize) {
+        if !self.workspaces.is_empty() {
+            let index = index.min(self.workspaces.len() - 1);
+            self.workspaces_list_state.select(Some(index));
+        }
+    }
+
+    /// Shows the working copy at the path, relative to the current one. An unversioned
+    /// directory inside a working copy shows the whole working copy.
+    fn change_directory(&mut self, input: &str) {
+        if input.is_empty() {
+            return;
+        }
+        let path = match self
+            .cwd
+            .join(prompt::expand_home(Path::new(input)))
+            .canonicalize()
+        {
+            Ok(path) if path.is_dir() => path,
+            Ok(path) => return self.push_message(format!("{} isn't a directory", path.display())),
+            Err(e) => return self.push_message(format!("{input}: {e}")),
+        };
+        let Some(root) = vcs::find_working_copy(&path) else {
+            return self.push_message(format!("{} isn't in a working copy", path.display()));
+        };
+        let path = match vcs::detect_backend(&path).branch_name(&path) {
+            Ok(_) => path,
+            Err(_) => root,
+        };
+        if path != self.cwd {
+            self.switch_working_copy(path);
+        }
+    }
+
+    /// Makes the working copy at the path the one shown in the changes list
+    fn switch_working_copy(&mut self, path: PathBuf) {
+        if let Err(e) = std::env::set_current_dir(&path) {
+            self.push_message(format!("{}: {e}", path.display()));
+            return;
+        }
+        // the working copy's .svn-util.toml may differ from the last one's
+        match self.config.reload(&path) {
+            Some(Ok(config)) => self.apply_config(config),
+            Some(Err(e)) => self.push_message(format!("could not reload the config: {e}")),
+            None => {}
+        }
+        self.backend = vcs::detect_backend(&path);
+        self.pinned = config::load_pins(&path);
+        self.cwd = path;
+        self.file_list.replace_changes(svn::FileList::empty());
+        self.multiselection.clear();
+        self.list_state.select(None);
+        self.changes_scrollbar_state = ScrollbarState::default();
+        self.state = AppState::Main;
+        // a different working copy isn't a branch change, so don't run the hook
+        self.known_branch = None;
+        self.updated_revision = None;
+        self.behind = BehindCheck::default();
+        self.last_commit = None;
+        self.last_commit_stale = true;
+        self.drilled_dirs.clear();
+        self.scope = None;
+        self.not_working_copy = None;
+        if self.backend.name() == "svn" && !self.detect_svn_client() {
+            self.current_branch = "svn not found".into();
+            return;
+        }
+        self.update_branch_name();
+        self.update_svn_status();
+    }
+
+    /// Handles any mouse clicks within the UI.
+    fn handle_click(&mut self, button: MouseButton) {
+        let section = self.current_mouse_section();
+        match section {
+            Some(AppSection::Changes) => {
+                if let Some(rect) = self.changes_area {
+                    let offset = self.mouse_loc.0 - rect.y;
+                    let index = (offset as usize + self.list_state.offset()).saturating_sub(1);
+                    if button == MouseButton::Right {
+                        if index <= self.rows().len() {
+                            *self.list_state.selected_mut() = Some(index);
+                            self.change_popup_area = None;
+                            self.state = AppState::ChangePopup;
+                        }
+                    } else {
+                        self.close_change_popup();
+                    }
+                    if button == MouseButton::Left {
+                        if index <= self.rows().len() {
+                            *self.list_state.selected_mut() = Some(index);
+                        } else {
+                            *self.list_state.selected_mut() = None;
+                        }
+                        self.drag_anchor = self.change_index_at_mouse();
+                        self.toggle_row(index);
+                    }
+                }
+            }
+            Some(AppSection::ChangePopup) => {
+                let pos = Position {
+                    // TODO make App.mouse_loc a Position
+                    x: self.mouse_loc.1,
+                    y: self.mouse_loc.0,
+                };
+                if let Some(func) = self.buttons.iter().fold(None, |mut a, (rect, func)| {
+                    if rect.contains(pos) {
+                        a = Some(func);
+                    }
+                    a
+                }) {
+                    func(self);
+                }
+                self.close_change_popup();
+            }
+            _ => {
+                *self.list_state.selected_mut() = None;
+                self.close_change_popup();
+            }
+        }
+    }
+
+    fn close_change_popup(&mut self) {
+        self.state = AppState::Main;
+        self.change_popup_area = None;
+    }
+
+    fn get_selected_changes(&self) -> Option<Vec<&StatusEntry>> {
+        if !self.multiselection.is_empty() {
+            let selected: Vec<_> = self
+                .listed_changes()
+                .into_iter()
+                .filter(|StatusEntry { path, .. }| self.multiselection.contains(path))
+                .collect();
+            return Some(selected);
+        }
+        if let Some(index) = self.list_state.selected() {
+            if let Some(change) = self.change_at(index) {
+                Some(vec![change])
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    /// The rows of the changes list, with the group headers if the changes are grouped
+    fn rows(&self) -> Vec<Row<'_>> {
+        group_rows(
+            self.listed_changes(),
+            self.config.group_by,
+            &self.collapsed_dirs,
+            &self.expanded_dirs,
+            &self.collapsed_states,
+            &self.pinned,
+        )
+    }
+
+    fn selected_row_key(&self) -> Option<RowKey> {
+        let index = self.list_state.selected()?;
+        self.rows().get(index).map(Row::key)
+    }
+
+    /// Selects the row that was selected before the changes were updated, wherever it's moved
+    /// to, or the row now at its index if it's gone
+    fn reselect_row(&mut self, previous: Option<RowKey>) {
+        let Some(previous) = previous else {
+            return;
+        };
+        let index = self
+            .rows()
+            .iter()
+            .position(|row| row.key() == previous)
+            .unwrap_or(self.selected_index());
+        self.select_change(index);
+    }
+
+    /// The change in the row at the index, `None` if the row is a header
+    fn change_at(&self, index: usize) -> Option<&StatusEntry> {
+        match self.rows().get(index) {
+            Some(Row::Change(change)) => Some(change),
+            _ => None,
+        }
+    }
+
+    /// Grows or shrinks the conflicts pane by a percentage of the width, saving the new size
+    fn resize_conflicts_pane(&mut self, by: i16) {
+        let percent = self
+            .config
+            .conflicts_pane_percent
+            .saturating_add_signed(by)
+            .clamp(MIN_PANE_PERCENT, MAX_PANE_PERCENT);
+        if percent == self.config.conflicts_pane_percent {
+            return;
+        }
+        self.config.conflicts_pane_percent = percent;
+        if let Err(e) = self.config.save_layout() {
+            self.push_message(format!("could not save the layout: {e}"));
+        }
+    }
+
+    /// The command bound to the key in the config, if there is one
+    fn custom_command(&self, key_event: &KeyEvent) -> Option<String> {
+        self.config
+            .custom_keys
+            .iter()
+            .find(|(key, _)| key.matches(key_event))
+            .map(|(_, command)| command.clone())
+    }
+
+    /// Runs a command bound to a key once its placeholders are replaced, showing its output
+    fn run_custom_command(&mut self, template: &str) {
+        let path = self
+            .list_state
+            .selected()
+            .and_then(|index| self.change_at(index))
+            .map(|StatusEntry { path, .. }| path.to_string_lossy().to_string());
+        let command = expand_custom_command(
+            template,
+            &self.cwd,
+            &self.current_branch,
+            path.as_deref(),
+            &self.selected_paths(),
+        );
+        self.run_job(Job::Operation(Operation::Custom { command }));
+    }
+
+    /// Moves the conflicts pane beside or under the changes list, saving the choice
+    fn toggle_pane_layout(&mut self) {
+        self.config.pane_layout = self.config.pane_layout.next();
+        self.push_message(format!("{} layout", self.config.pane_layout.label()));
+        if let Err(e) = self.config.save_layout() {
+            self.push_message(format!("could not save the layout: {e}"));
+        }
+    }
+
+    fn toggle_high_contrast(&mut self) {
+        self.config.theme = match self.config.theme {
+            Theme::HighContrast => self.theme_before_high_contrast.take().unwrap_or_default(),
+            theme => {
+                self.theme_before_high_contrast = Some(theme);
+                Theme::HighContrast
+            }
+        };
+        self.push_message(format!("{} theme", self.config.theme.label()));
+    }
+
+    fn cycle_grouping(&mut self) {
+        self.config.group_by = self.config.group_by.next();
+        self.list_state.select(None);
+        self.push_message(format!("grouping by {}", self.config.group_by.label()));
+    }
+
+    /// Expands or collapses the row if it's a directory's header or a state's section
+    fn toggle_row(&mut self, index: usize) {
+        match self.rows().get(index) {
+            Some(Row::Header { dir, .. }) => {
+                let dir = dir.clone();
+                self.toggle_collapsed(dir);
+            }
+            Some(Row::Section { state, .. }) => {
+                let state = *state;
+                self.toggle_section(state);
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_section(&mut self, state: State) {
+        if !self.collapsed_states.remove(&state) {
+            self.collapsed_states.insert(state);
+        }
+        self.select_change(self.selected_index());
+    }
+
+    fn toggle_collapsed(&mut self, dir: PathBuf) {
+        let toggled = match self.config.group_by {
+            GroupBy::Tree => &mut self.expanded_dirs,
+            _ => &mut self.collapsed_dirs,
+        };
+        if !toggled.remove(&dir) {
+            toggled.insert(dir);
+        }
+        // keep the highlight within the list if rows were hidden below it
+        self.select_change(self.selected_index());
+    }
+
+    /// The changelist that every selected change belongs to, if they share one
+    fn selected_changelist(&self) -> Option<String> {
+        let selected = self.get_selected_changes()?;
+        let first = selected.first()?;
+        let name = self.file_list.changelist(&first.path)?;
+        selected
+            .iter()
+            .all(|StatusEntry { path, .. }| self.file_list.changelist(path) == Some(name))
+            .then(|| name.to_string())
+    }
+
+    fn changelist_member_paths(&self, name: &str) -> Vec<String> {
+        self.file_list
+            .changelist_members(name)
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// The actions that can be performed on the selected changes
+    fn selection_actions(&self) -> Vec<Action> {
+        let in_changelist = self.selected_changelist().is_some();
+        match self.get_selected_changes() {
+            Some(selected) => available_actions(&selected, in_changelist, self.backend.read_only()),
+            None => vec![],
+        }
+    }
+
+    /// The last message while it's recent, otherwise hints for the keys that do something
+    /// with the current selection
+    fn message_or_hints(&self) -> (String, bool) {
+        if let (Some(message), Some(at)) = (self.messages.last(), self.last_message_at)
+            && !time_for_update(at, MESSAGE_DURATION)
+        {
+            return (message.clone(), false);
+        }
+        (self.hints().join("  "), true)
+    }
+
+    fn hints(&self) -> Vec<String> {
+        if self.svn_missing || self.not_working_copy.is_some() {
+            return vec![
+                "C: open another directory".into(),
+                "w: workspaces".into(),
+                "q: quit".into(),
+            ];
+        }
+        if let Some(fix) = &self.pending_fix {
+            return vec![
+                format!("f: {} and retry", fix.precondition.label()),
+                "r: refresh".into(),
+            ];
+        }
+        if self.focus == AppSection::Conflicts {
+            return vec![
+                "↑↓: scroll".into(),
+                "←: changes".into(),
+                "<>: resize".into(),
+                "L: layout".into(),
+            ];
+        }
+        if let Some(Row::Header { collapsed, .. } | Row::Section { collapsed, .. }) =
+            self.rows().get(self.selected_index())
+        {
+            let toggle = match collapsed {
+                true => "Enter: expand",
+                false => "Enter: collapse",
+            };
+            let regroup = format!("v: group by {}", self.config.group_by.next().label());
+            return vec![toggle.into(), regroup];
+        }
+        let actions = self.selection_actions();
+        if actions.is_empty() && self.scope.is_some() {
+            return vec![
+                "Backspace: whole working copy".into(),
+                "s: scope".into(),
+                "r: refresh".into(),
+                "q: quit".into(),
+            ];
+        }
+        if actions.is_empty() {
+            return vec![
+                "r: refresh".into(),
+                "w: workspaces".into(),
+                "B: branches".into(),
+                "e: export".into(),
+                "q: quit".into(),
+            ];
+        }
+        actions
+            .iter()
+            .map(|action| format!("{}: {}", action.key(), action.label().to_lowercase()))
+            .collect()
+    }
+
+    /// Performs the action on the selected changes, if it applies to all of them
+    fn perform_action(&mut self, action: Action) {
+        let available = self.selection_actions();
+        let unsupported = action
+            .required_feature()
+            .and_then(|feature| self.unsupported(feature));
+        if let Some(reason) = unsupported.filter(|_| available.contains(&action)) {
+            let label = action.label().to_lowercase();
+            self.push_message(format!("{label} {reason}"));
+        } else if available.contains(&action)
+            && action.writes_working_copy()
+            && self.worker.is_busy()
+        {
+            self.close_change_popup();
+            let paths = self.selected_paths();
+            self.run_job(Job::Action { action, paths });
+        } else if available.contains(&action) {
+            self.close_change_popup();
+            self.pending_fix = None;
+            self.unmet_precondition = None;
+            self.transient_failure = false;
+            let paths = self.selected_paths();
+            action.handler()(self);
+            if let Some(precondition) = self.unmet_precondition.take() {
+                self.pending_fix = Some(PendingFix {
+                    precondition,
+                    action,
+                    paths,
+                });
+            } else if std::mem::take(&mut self.transient_failure) {
+                self.retry_later(Job::Action { action, paths });
+            }
+        }
+    }
+
+    /// Does what the last failed action needed first, e.g. updating out of date paths, then
+    /// performs the action on them again
+    fn fix_and_retry(&mut self) {
+        match self.pending_fix.take() {
+            Some(fix) => self.run_job(Job::Fix(fix)),
+            None => self.push_message("nothing to fix".into()),
+        }
+    }
+
+    fn run_fix(&mut self, fix: PendingFix) {
+        let prerequisite = match fix.precondition {
+            Precondition::Lock => svn::svn_lock,
+            Precondition::Update => svn::svn_update,
+            Precondition::Cleanup => svn::svn_cleanup,
+        };
+        if !self.run_svn_function(&fix.paths, prerequisite) {
+            return;
+        }
+        let retried = fix.paths.iter().map(PathBuf::from).collect();
+        let previous = std::mem::replace(&mut self.multiselection, retried);
+        self.perform_action(fix.action);
+        self.multiselection = previous;
+    }
+
+    /// Selects the change at the index, clamped to the list, keeping the scrollbar in step
+    fn select_change(&mut self, index: usize) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        let index = index.min(len - 1);
+        self.list_state.select(Some(index));
+        self.changes_scrollbar_state = self.changes_scrollbar_state.position(index);
+    }
+
+    fn selected_index(&self) -> usize {
+        self.list_state.selected().unwrap_or(0)
+    }
+
+    /// The number of changes visible at once in the changes list
+    fn page_height(&self) -> usize {
+        self.changes_area
+            .map(|area| area.height.saturating_sub(2) as usize) // minus the borders
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Adds the change at the index to the multiselection, or removes it if it was already there
+    fn toggle_multiselection(&mut self, index: usize) {
+        if let Some(StatusEntry { path, .. }) = self.change_at(index) {
+            match self.multiselection.iter().position(|p| p == path) {
+                Some(i) => {
+                    self.multiselection.remove(i);
+                }
+                None => self.multiselection.push(path.clone()),
+            }
+        }
+    }
+
+    /// Selects every visible change, apart from those in the excluded changelists
+    fn select_all_changes(&mut self) {
+        self.multiselection = self
+            .file_list
+            .renderable()
+            .into_iter()
+            .filter(|StatusEntry { path, .. }| {
+                !self
+                    .file_list
+                    .in_changelist(path, &self.config.excluded_changelists)
+            })
+            .map(|StatusEntry { path, .. }| path.clone())
+            .collect();
+    }
+
+    fn toggle_excluded_changelists(&mut self) {
+        let hidden = match self.file_list.hidden_changelists().is_empty() {
+            true => self.config.excluded_changelists.clone(),
+            false => vec![],
+        };
+        self.file_list.set_hidden_changelists(hidden);
+        let renderable = self.file_list.renderable();
+        self.multiselection.retain(|path| {
+            renderable
+                .iter()
+                .any(|StatusEntry { path: p, .. }| p == path)
+        });
+        *self.list_state.selected_mut() = None;
+    }
+
+    fn toggle_property_only(&mut self) {
+        let hide = !self.file_list.hide_property_only();
+        self.file_list.set_hide_property_only(hide);
+        self.changes_updated();
+        *self.list_state.selected_mut() = None;
+        let msg = match hide {
+            true => "hiding entries with only property changes",
+            false => "showing entries with only property changes",
+        };
+        self.push_message(msg.into());
+    }
+
+    fn invert_multiselection(&mut self) {
+        self.multiselection = self
+            .file_list
+            .renderable()
+            .into_iter()
+            .filter(|StatusEntry { path, .. }| !self.multiselection.contains(path))
+            .map(|StatusEntry { path, .. }| path.clone())
+            .collect();
+    }
+
+    fn clear_multiselection(&mut self) {
+        self.multiselection.clear();
+    }
+
+    fn handle_mouse_scroll(&mut self, dir: Direction) {
+        match self.current_mouse_section() {
+            Some(AppSection::Changes) => {
+                if let Some(selected) = self.list_state.selected_mut() {
+                    handle_scroll(&dir, selected, &mut self.changes_scrollbar_state)
+                }
+            }
+            Some(AppSection::Conflicts) => {
+                let mut offset = self.conflicts_scroll_offset;
+                handle_scroll(&dir, &mut offset, &mut self.conflicts_scrollbar_state);
+                self.scroll_conflicts_to(offset);
+            }
+            _ => {}
+        }
+    }
+
+    /// Scrolls the conflicts pane with the arrow and paging keys
+    fn handle_conflicts_key(&mut self, code: KeyCode) {
+        let page = self
+            .conflicts_area
+            .map(|area| area.height.saturating_sub(2) as usize) // minus the borders
+            .unwrap_or(1)
+            .max(1);
+        let offset = self.conflicts_scroll_offset;
+        match code {
+            KeyCode::Down => self.scroll_conflicts_to(offset.saturating_add(1)),
+            KeyCode::Up => self.scroll_conflicts_to(offset.saturating_sub(1)),
+            KeyCode::PageDown => self.scroll_conflicts_to(offset.saturating_add(page)),
+            KeyCode::PageUp => self.scroll_conflicts_to(offset.saturating_sub(page)),
+            KeyCode::Home => self.scroll_conflicts_to(0),
+            KeyCode::End => self.scroll_conflicts_to(usize::MAX),
+            _ => {}
+        }
+    }
+
+    /// Scrolls the conflicts pane so the line is at the top, clamped to the last line
+    fn scroll_conflicts_to(&mut self, offset: usize) {
+        let offset = offset.min(self.conflicts_line_count.saturating_sub(1));
+        self.conflicts_scroll_offset = offset;
+        self.conflicts_scrollbar_state = self.conflicts_scrollbar_state.position(offset);
+    }
+
+    fn current_mouse_section(&self) -> Option<AppSection> {
+        for (area, app_section) in [
+            // this needs to be in the order that popups/dialogs sit above section in Main,
+            // as the rects for each section are still Some(_) even wh en popups are above them
+            (self.change_popup_area, AppSection::ChangePopup),
+            (self.changes_area, AppSection::Changes),
+            (self.conflicts_area, AppSection::Conflicts),
+        ] {
+            if let Some(area) = area {
+                let pos = Position {
+                    x: self.mouse_loc.1,
+                    y: self.mouse_loc.0,
+                };
+                if area.contains(pos) {
+                    return Some(app_section);
+                }
+            }
+        }
+        None
+    }
+
+    fn handle_mouse_move(&mut self) {}
+
+    /// Multiselects the range of changes between where the drag started and the mouse
+    fn handle_mouse_drag(&mut self) {
+        let Some(anchor) = self.drag_anchor else {
+            return;
+        };
+        let Some(index) = self.change_index_at_mouse() else {
+            return;
+        };
+        if index == anchor {
+            // not a drag yet, the mouse may have just wobbled during a click
+            return;
+        }
+        let (start, end) = (anchor.min(index), anchor.max(index));
+        self.multiselection = self.rows()[start..=end]
+            .iter()
+            .filter_map(|row| match row {
+                Row::Change(StatusEntry { path, .. }) => Some(path.clone()),
+                Row::Favorites { .. } | Row::Header { .. } | Row::Section { .. } => None,
+            })
+            .collect();
+        self.select_change(index);
+    }
+
+    /// The index of the change under the mouse, if it is over one
+    fn change_index_at_mouse(&self) -> Option<usize> {
+        let area = self.changes_area?;
+        let row = self.mouse_loc.0.checked_sub(area.y + 1)?; // below the top border
+        let index = row as usize + self.list_state.offset();
+        (index < self.rows().len()).then_some(index)
+    }
+
+    fn perform_svn_function(&mut self, func: fn(&[&str]) -> svn::error::Result<CmdResult>) {
+        let paths = self.selected_paths();
+        if !paths.is_empty() {
+            self.run_svn_function(&paths, func);
+        }
+    }
+
+    fn selected_paths(&self) -> Vec<String> {
+        self.get_selected_changes()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|StatusEntry { path, .. }| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Runs the svn function on the paths, then refreshes the status or shows the error.
+    /// Returns whether the function succeeded.
+    fn run_svn_function(
+        &mut self,
+        paths: &[String],
+        func: impl Fn(&[&str]) -> svn::error::Result<CmdResult>,
+    ) -> bool {
+        let succeeded = self.try_svn_function(paths, func);
+        if succeeded {
+            self.update_svn_status();
+        }
+        succeeded
+    }
+
+    /// Like [`App::run_svn_function`], but only refreshes the status of the paths, for
+    /// functions that can't change anything else
+    fn run_scoped_svn_function(
+        &mut self,
+        paths: &[String],
+        func: impl Fn(&[&str]) -> svn::error::Result<CmdResult>,
+    ) -> bool {
+        let succeeded = self.try_svn_function(paths, func);
+        if succeeded {
+            self.update_svn_status_of(paths);
+        }
+        succeeded
+    }
+
+    /// Runs the svn function on the paths, showing the error if it fails. It isn't retried
+    /// here, as that would sleep on the event loop; a transient failure sets
+    /// `transient_failure` for the caller to [`App::retry_later`].
+    fn try_svn_function(
+        &mut self,
+        paths: &[String],
+        func: impl Fn(&[&str]) -> svn::error::Result<CmdResult>,
+    ) -> bool {
+        let path_strs: Vec<&str> = paths.iter().map(|s| s.as_ref()).collect();
+        match func(path_strs.as_slice()) {
+            Ok(res) if res.success() => return true,
+            Ok(res) => {
+                self.transient_failure = res.is_transient_failure();
+                self.unmet_precondition = res.unmet_precondition();
+                // TODO delete reaches here when the file has modification, as svn requires --force to be passed, this could be used to have a "are you sure?" dialog
+                let error = svn::error::Error::from(&res);
+                self.events.send(AppEvent::Message(error.to_string()))
+            }
+            Err(e) => self.events.send(AppEvent::Message(e.to_string())),
+        }
+        false
+    }
+
+    /// The local changes to the paths, so they can be reapplied after being reverted or
+    /// deleted: a patch, and a copy of the files whose changes the patch can't have
+    fn local_changes(&mut self, paths: &[String]) -> Option<LocalChanges> {
+        let mut changes = LocalChanges::default();
+        for path in paths {
+            let saved = svn::get_git_diff(Path::new(path))
+                .map_err(|e| e.to_string())
+                .and_then(|diff| {
+                    let backups = svn::files_without_content(&diff)
+                        .into_iter()
+                        .map(|file| match std::fs::read(&file) {
+                            Ok(contents) => Ok((file, contents)),
+                            Err(e) => Err(format!("{file}: {e}")),
+                        })
+                        .collect::<Result<Vec<_>, String>>()?;
+                    Ok((diff, backups))
+                });
+            match saved {
+                Ok((diff, backups)) => {
+                    changes.patch.push_str(&diff);
+                    changes.backups.extend(backups);
+                }
+                Err(e) => {
+                    self.push_message(format!(
+                        "could not save the changes to {path} for undo, nothing was done: {e}"
+                    ));
+                    return None;
+                }
+            }
+        }
+        Some(changes)
+    }
+
+    fn undo_last_action(&mut self) {
+        // kept until it's undone, so a failed undo can be tried again
+        let Some(undo) = self.last_undo.clone() else {
+            self.push_message("nothing to undo".into());
+            return;
+        };
+        let undone = match &undo {
+            Undo::Add(paths) => self.run_scoped_svn_function(paths, svn::svn_revert),
+            Undo::Delete { paths, changes } => {
+                self.run_scoped_svn_function(paths, svn::svn_revert)
+                    && self.reapply_changes(changes)
+            }
+            Undo::Revert { changes, .. } => self.reapply_changes(changes),
+            Undo::Lock(paths) => self.run_svn_function(paths, svn::svn_unlock),
+            Undo::Restore { path, previous } => {
+                let result = match previous {
+                    Some(contents) => std::fs::write(path, contents),
+                    None => std::fs::remove_file(path),
+                };
+                match result {
+                    Ok(()) => {
+                        self.update_svn_status();
+                        true
+                    }
+                    Err(e) => {
+                        self.push_message(format!("could not undo the restore of {path}: {e}"));
+                        false
+                    }
+                }
+            }
+        };
+        if undone {
+            self.last_undo = None;
+            self.push_message(format!("undid the {}", undo.description()));
+        }
+    }
+
+    fn reapply_changes(&mut self, changes: &LocalChanges) -> bool {
+        if !changes.patch.trim().is_empty()
+            && !self.run_svn_function(&[], |_| svn::svn_apply_patch(&changes.patch))
+        {
+            return false;
+        }
+        if changes.backups.is_empty() {
+            return true;
+        }
+        for (file, contents) in &changes.backups {
+            if let Err(e) = std::fs::write(file, contents) {
+                self.push_message(format!("could not put back {file}: {e}"));
+                return false;
+            }
+        }
+        self.update_svn_status();
+        true
+    }
+
+    /// Checks that the selected changes still have the state they were shown with, in case
+    /// another process changed the working copy since. If any have changed the list is
+    /// refreshed and false is returned, so destructive actions aren't run on stale state.
+    fn selection_unchanged(&mut self) -> bool {
+        let Some(selected) = self.get_selected_changes() else {
+            return false;
+        };
+        let selected: Vec<StatusEntry> = selected.into_iter().cloned().collect();
+        let fresh = match self.read_status() {
+            Ok(fresh) => fresh,
+            Err(e) => {
+                self.push_message(format!("could not check the selection is up to date: {e}"));
+                return false;
+            }
+        };
+        let changed = selected.iter().find_map(|StatusEntry { state, path, .. }| {
+            let now = fresh
+                .entry(path)
+                .map(|entry| entry.state)
+                .unwrap_or(State::Clean);
+            (now != *state).then_some((path, now))
+        });
+        match changed {
+            None => true,
+            Some((path, now)) => {
+                let now = match now {
+                    State::Clean => "unchanged".to_string(),
+                    now => format!("'{now}'"),
+                };
+                self.push_message(format!(
+                    "{} changed since you selected it (now {now}), nothing was done",
+                    path.display()
+                ));
+                let selected = self.selected_row_key();
+                self.file_list.replace_changes(fresh);
+                self.reselect_row(selected);
+                false
+            }
         }
     }
 
     fn delete_change_file(&mut self) {
-        self.perform_svn_function(svn::svn_delete);
+        if !self.selection_unchanged() {
+            return;
+        }
+        let paths = self.selected_paths();
+        if let Some(changes) = self.local_changes(&paths)
+            && self.run_scoped_svn_function(&paths, svn::svn_delete)
+        {
+            self.last_undo = Some(Undo::Delete { paths, changes });
+        }
+    }
+
+    fn add_change_file(&mut self) {
+        let paths = self.selected_paths();
+        if !paths.is_empty() && self.run_scoped_svn_function(&paths, svn::svn_add) {
+            self.last_undo = Some(Undo::Add(paths));
+        }
+    }
+
+    fn revert_change_file(&mut self) {
+        if !self.selection_unchanged() {
+            return;
+        }
+        let paths = self.selected_paths();
+        if let Some(changes) = self.local_changes(&paths)
+            && self.run_scoped_svn_function(&paths, svn::svn_revert)
+        {
+            self.last_undo = Some(Undo::Revert { paths, changes });
+        }
+    }
+
+    /// Starts committing the selected changes together: their message is typed, then they're
+    /// reviewed before being committed
+    fn commit_change_file(&mut self) {
+        let changes = self
+            .get_selected_changes()
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+        self.review_commit(changes);
+    }
+
+    /// Opens the commit message prompt for the changes, which are then reviewed
+    fn review_commit(&mut self, changes: Vec<StatusEntry>) {
+        if changes.is_empty() {
+            return;
+        }
+        let count = changes.len();
+        self.commit_review = Some(CommitReview::new(changes, self.config.keep_locks));
+        let draft = self.commit_draft.clone();
+        self.open_prompt(PromptKind::CommitMessage(count), &draft);
+    }
+
+    fn handle_commit_review_key_event(&mut self, key_event: KeyEvent) {
+        let Some(review) = self.commit_review.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let count = review.changes.len();
+        let selected = review.list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.cancel_commit("commit cancelled"),
+            KeyCode::Down if count > 0 => review
+                .list_state
+                .select(Some((selected + 1).min(count - 1))),
+            KeyCode::Up => review.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char('d') | KeyCode::Delete => {
+                review.remove_selected();
+                if review.changes.is_empty() {
+                    self.cancel_commit("commit cancelled, every change was left out");
+                }
+            }
+            KeyCode::Char('k') => review.keep_locks = !review.keep_locks,
+            KeyCode::Char('e') => {
+                let message = review.message.clone();
+                self.open_prompt(PromptKind::CommitMessage(count), &message);
+            }
+            KeyCode::Enter | KeyCode::Char('y') => self.confirm_commit(),
+            _ => {}
+        }
+    }
+
+    /// Drops the reviewed commit, keeping its message for the next one
+    fn cancel_commit(&mut self, message: &str) {
+        if let Some(review) = self.commit_review.take() {
+            self.commit_draft = review.message;
+        }
+        self.state = AppState::Main;
+        self.push_message(message.into());
+    }
+
+    fn confirm_commit(&mut self) {
+        self.state = AppState::Main;
+        let Some(review) = self.commit_review.take() else {
+            return;
+        };
+        let operation = Operation::Commit {
+            paths: review.paths(),
+            message: review.message,
+            keep_locks: review.keep_locks,
+        };
+        self.run_job(Job::Operation(operation));
+    }
+
+    fn resolve_change_file(&mut self) {
+        if self.selection_unchanged() {
+            self.perform_svn_function(svn::svn_resolve);
+        }
+    }
+
+    fn auto_resolve_change_file(&mut self) {
+        let paths: Vec<PathBuf> = self.selected_paths().iter().map(PathBuf::from).collect();
+        if !paths.is_empty() {
+            self.resolve_trivial_conflicts(&paths);
+        }
+    }
+
+    /// Resolves the conflicts among the paths that need no choosing, writing the merge of the
+    /// sides into the file and marking it resolved. The rest are left for the user.
+    fn resolve_trivial_conflicts(&mut self, paths: &[PathBuf]) {
+        let conflicts: Vec<_> = self
+            .file_list
+            .conflicts()
+            .into_iter()
+            .filter(|conflict| paths.iter().any(|path| path == conflict.file()))
+            .collect();
+        let total = conflicts.len();
+        let mut merged = vec![];
+        for conflict in conflicts {
+            let conflict = conflict.clone().locate_parts().unwrap_or(conflict);
+            let file = conflict.file().to_string_lossy().to_string();
+            match conflict.trivial_resolution() {
+                Ok(Some(content)) => match std::fs::write(conflict.file(), content) {
+                    Ok(()) => merged.push(file),
+                    Err(e) => self.push_message(format!("could not write {file}: {e}")),
+                },
+                Ok(None) => {}
+                Err(e) => self.push_message(format!("could not compare the sides of {file}: {e}")),
+            }
+        }
+        if merged.is_empty() {
+            self.push_message(match total {
+                1 => "the conflict isn't trivial, it needs resolving by hand".into(),
+                _ => "none of the conflicts are trivial, they need resolving by hand".into(),
+            });
+            return;
+        }
+        if self.try_svn_function(&merged, svn::svn_resolve) {
+            self.push_message(format!(
+                "resolved {} of {total} conflicts automatically",
+                merged.len()
+            ));
+        }
+        self.update_svn_status_of(&merged);
+    }
+
+    fn lock_change_file(&mut self) {
+        let paths = self.selected_paths();
+        if !paths.is_empty() && self.run_svn_function(&paths, svn::svn_lock) {
+            self.last_undo = Some(Undo::Lock(paths));
+        }
+    }
+
+    fn ignore_change_file(&mut self) {
+        self.perform_svn_function(svn::svn_ignore);
+    }
+
+    /// Pages through the diff of every selected change
+    fn diff_change_file(&mut self) {
+        let Some(selected) = self.get_selected_changes() else {
+            return;
+        };
+        let paths: Vec<PathBuf> = selected
+            .into_iter()
+            .map(|StatusEntry { path, .. }| path.clone())
+            .collect();
+        let mut diff = String::new();
+        for path in paths {
+            match self.backend.diff(&path) {
+                Ok(text) => diff.push_str(&text),
+                Err(e) => {
+                    self.push_message(e.to_string());
+                    return;
+                }
+            }
+        }
+        match diff.trim().is_empty() {
+            true => self.push_message("no differences to show".into()),
+            false => self.pending_pager = Some(diff),
+        }
+    }
+
+    fn diff_change_properties(&mut self) {
+        let paths: Vec<PathBuf> = self.selected_paths().iter().map(PathBuf::from).collect();
+        let mut diff = String::new();
+        for path in paths {
+            match svn::get_property_diff(&path) {
+                Ok(text) => diff.push_str(&text),
+                Err(e) => {
+                    self.push_message(e.to_string());
+                    return;
+                }
+            }
+        }
+        match diff.trim().is_empty() {
+            true => self.push_message("no property changes to show".into()),
+            false => self.pending_pager = Some(diff),
+        }
+    }
+
+    /// Updates the whole working copy to the revision, or HEAD if it's empty, e.g. to bisect
+    fn update_to_revision(&mut self, input: &str) {
+        let revision = match input.trim_start_matches('r') {
+            "" => "HEAD",
+            rev if rev.parse::<u32>().is_ok() => rev,
+            _ => {
+                self.push_message(format!("'{input}' isn't a revision number"));
+                return;
+            }
+        };
+        let operation = Operation::Update {
+            revision: revision.to_string(),
+        };
+        self.run_job(Job::Operation(operation));
+    }
+
+    /// Shows the merge-left, working and merge-right sides of the selected text conflict side by
+    /// side, lined up by the conflict markers in the file
+    fn view_three_way(&mut self) {
+        let paths = self.selected_paths();
+        let [path] = paths.as_slice() else {
+            self.push_message("pick a single conflicted file to view".into());
+            return;
+        };
+        let file = PathBuf::from(path);
+        let Some(conflict) = self
+            .file_list
+            .conflicts()
+            .into_iter()
+            .find(|conflict| conflict.file() == file)
+        else {
+            self.push_message(format!("{path} isn't a text conflict"));
+            return;
+        };
+        let blocks = match std::fs::read_to_string(&file) {
+            Ok(text) => svn::three_way::parse_conflict_markers(&text),
+            Err(e) => {
+                self.push_message(format!("could not read {path}: {e}"));
+                return;
+            }
+        };
+        if !blocks
+            .iter()
+            .any(|block| matches!(block, MergeBlock::Conflict { .. }))
+        {
+            self.push_message(format!("{path} has no conflict markers left"));
+            return;
+        }
+        // the part files' names tell which revision each side is from
+        let conflict = conflict.clone().locate_parts().unwrap_or(conflict);
+        let title = |part: Option<&Path>, side: &str| match part.and_then(Path::file_name) {
+            Some(name) => format!("{side}: {}", name.to_string_lossy()),
+            None => side.to_string(),
+        };
+        let titles = [
+            title(conflict.base(), "merge-left"),
+            title(conflict.mine(), "working"),
+            title(conflict.theirs(), "merge-right"),
+        ];
+        self.three_way = Some(ThreeWayScreen::new(file, titles, &blocks));
+        self.state = AppState::ThreeWay;
+    }
+
+    fn handle_three_way_key_event(&mut self, key_event: KeyEvent) {
+        let page = self.page_height() as isize;
+        let Some(screen) = self.three_way.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.three_way = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down => screen.scroll_by(1),
+            KeyCode::Up => screen.scroll_by(-1),
+            KeyCode::PageDown => screen.scroll_by(page),
+            KeyCode::PageUp => screen.scroll_by(-page),
+            KeyCode::Home => screen.scroll = 0,
+            KeyCode::End => screen.scroll_by(isize::MAX),
+            KeyCode::Char('n') => screen.next_conflict(),
+            KeyCode::Char('N') => screen.prev_conflict(),
+            _ => {}
+        }
+    }
+
+    /// Shows the hunks of the selected file's changes, to pick which of them to commit
+    fn pick_hunks_to_commit(&mut self) {
+        let paths = self.selected_paths();
+        let [path] = paths.as_slice() else {
+            self.push_message("pick a single file to commit hunks of".into());
+            return;
+        };
+        let path = path.clone();
+        let diff = match svn::get_diff(Path::new(&path)) {
+            Ok(diff) => diff,
+            Err(e) => {
+                self.push_message(e.to_string());
+                return;
+            }
+        };
+        match svn::hunks::FileDiff::parse(&diff) {
+            Some(diff) => {
+                self.hunks = Some(HunkScreen::new(&path, diff));
+                self.state = AppState::Hunks;
+            }
+            None => self.push_message(format!("{path} has no hunks to pick from")),
+        }
+    }
+
+    fn handle_hunks_key_event(&mut self, key_event: KeyEvent) {
+        let Some(screen) = self.hunks.as_mut() else {
+            self.state = AppState::Main;
+            return;
+        };
+        let count = screen.picked.len();
+        let selected = screen.list_state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.events.send(AppEvent::Quit)
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.hunks = None;
+                self.state = AppState::Main;
+            }
+            KeyCode::Down => screen
+                .list_state
+                .select(Some((selected + 1).min(count - 1))),
+            KeyCode::Up => screen.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char(' ') => screen.toggle_selected(),
+            KeyCode::Char('a') => screen.toggle_all(),
+            KeyCode::Enter if screen.picked_count() == 0 => {
+                self.push_message("pick at least one hunk to commit".into())
+            }
+            KeyCode::Enter => self.open_prompt(PromptKind::CommitHunks, ""),
+            _ => {}
+        }
+    }
+
+    /// Commits just the picked hunks: the file is reverted, the picked hunks are patched back
+    /// in and committed, then the file is put back how it was so the rest are still changes
+    fn commit_picked_hunks(&mut self, message: &str) {
+        let Some(screen) = self.hunks.take() else {
+            return;
+        };
+        let (picked, total) = (screen.picked_count(), screen.picked.len());
+        let path = screen.path.clone();
+        // kept on disk too, so the changes aren't lost if the file can't be put back
+        let backup_path = std::env::temp_dir().join(format!(
+            "svn-util-{}-{}",
+            std::process::id(),
+            Path::new(&path)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+        ));
+        let backup = match std::fs::read(&path)
+            .and_then(|content| std::fs::write(&backup_path, &content).map(|_| content))
+        {
+            Ok(content) => content,
+            Err(e) => {
+                self.push_message(format!("could not back up {path}, nothing was done: {e}"));
+                return;
+            }
+        };
+        let patch = screen.diff.patch_with(&screen.picked);
+        let paths = vec![path.clone()];
+        let keep_locks = self.config.keep_locks;
+        let output = RefCell::new(String::new());
+        self.transient_failure = false;
+        let committed = self.try_svn_function(&paths, svn::svn_revert)
+            && self.try_svn_function(&paths, |_| svn::svn_apply_patch(&patch))
+            && self.try_svn_function(&paths, |paths| {
+                let result = svn::svn_commit_with_message(message, keep_locks, paths)?;
+                output.replace(result.output().to_string());
+                Ok(result)
+            });
+        match std::fs::write(&path, backup) {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&backup_path);
+                if committed {
+                    self.push_message(format!("committed {picked} of {total} hunks of {path}"));
+                    self.commit_succeeded(&paths, message, &output.into_inner());
+                }
+            }
+            Err(e) => self.push_message(format!(
+                "could not put {path} back, its changes are in {}: {e}",
+                backup_path.display()
+            )),
+        }
+        self.update_svn_status_of(&paths);
+        // the picks are kept for the retry
+        if std::mem::take(&mut self.transient_failure) {
+            self.hunks = Some(screen);
+            self.retry_later(Job::CommitHunks {
+                path,
+                message: message.to_string(),
+            });
+        }
+    }
+
+    fn export_change_file(&mut self) {
+        match self.selected_paths().as_slice() {
+            [path] => self.export_prompt(&path.clone()),
+            _ => self.push_message("pick a single path to export".into()),
+        }
+    }
+
+    /// Copies the repository url of the selected path, e.g. to share a link to it in a web
+    /// viewer
+    fn copy_change_url(&mut self) {
+        let path = match self.selected_paths().as_slice() {
+            [path] => path.clone(),
+            _ => {
+                self.push_message("pick a single path to copy the url of".into());
+                return;
+            }
+        };
+        let message = match svn::get_url(&self.cwd) {
+            Ok(base) => {
+                let url = svn::path_url(&base, Path::new(&path));
+                match copy_to_clipboard(&url) {
+                    Ok(()) => format!("copied {url}"),
+                    Err(e) => format!("could not copy the url: {e}"),
+                }
+            }
+            Err(e) => format!("could not find the url of {path}: {e}"),
+        };
+        self.push_message(message);
+    }
+
+    /// Asks where to export the path to, suggesting a directory next to the working copy
+    fn export_prompt(&mut self, path: &str) {
+        let name = match path {
+            "." => self.cwd.file_name(),
+            path => Path::new(path).file_name(),
+        };
+        let dest = format!("../{}-export", name.unwrap_or_default().to_string_lossy());
+        self.open_prompt(PromptKind::Export(path.to_string()), &dest);
+    }
+
+    fn export(&mut self, path: &str, dest: &str) {
+        if dest.is_empty() {
+            self.push_message("export cancelled, no destination was given".into());
+            return;
+        }
+        let dest = prompt::expand_home(Path::new(dest))
+            .to_string_lossy()
+            .to_string();
+        self.transient_failure = false;
+        if self.try_svn_function(&[path.to_string()], |paths| {
+            svn::svn_export(paths[0], &dest)
+        }) {
+            self.push_message(format!("exported {path} to {dest}"));
+        } else if std::mem::take(&mut self.transient_failure) {
+            self.retry_later(Job::Export {
+                path: path.to_string(),
+                dest,
+            });
+        }
+    }
+
+    /// Unpins the selected changes if they're all pinned, otherwise pins them
+    fn toggle_pinned(&mut self) {
+        let selected: Vec<PathBuf> = self
+            .get_selected_changes()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|StatusEntry { path, .. }| path.clone())
+            .collect();
+        if selected.iter().all(|path| self.pinned.contains(path)) {
+            self.pinned.retain(|path| !selected.contains(path));
+        } else {
+            for path in selected {
+                if !self.pinned.contains(&path) {
+                    self.pinned.push(path);
+                }
+            }
+        }
+        self.save_pins();
+    }
+
+    /// Pins the file to the favorites, so it's listed even while it has no changes
+    fn pin_favorite(&mut self, input: &str) {
+        let path = prompt::expand_home(Path::new(input));
+        // the file is stored relative to the working copy, like the changes' paths
+        let path = path.strip_prefix(&self.cwd).unwrap_or(&path).to_path_buf();
+        if input.is_empty() || !self.cwd.join(&path).is_file() {
+            self.push_message(format!("{input}: no such file"));
+            return;
+        }
+        if !self.pinned.contains(&path) {
+            self.pinned.push(path);
+            self.save_pins();
+        }
+    }
+
+    fn save_pins(&mut self) {
+        self.update_favorites();
+        if let Err(e) = config::save_pins(&self.cwd, &self.pinned) {
+            self.push_message(format!("could not save the pinned files: {e}"));
+        }
+    }
+
+    fn rename_changelist(&mut self) {
+        if let Some(name) = self.selected_changelist() {
+            self.open_prompt(PromptKind::RenameChangelist(name.clone()), &name);
+        }
+    }
+
+    /// Starts committing every change in the selected changelist, reviewed like any commit
+    fn commit_changelist(&mut self) {
+        let Some(name) = self.selected_changelist() else {
+            return;
+        };
+        let members = self.file_list.changelist_members(&name);
+        let changes = self
+            .file_list
+            .list()
+            .iter()
+            .filter(|change| members.contains(&&change.path))
+            .cloned()
+            .collect();
+        self.review_commit(changes);
+    }
+
+    fn open_change_dir(&mut self) {
+        if let Some(selected) = self.get_selected_changes()
+            && let Some(StatusEntry { path, .. }) = selected.first()
+        {
+            let dir = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => self.cwd.clone(),
+            };
+            let command = self.config.file_manager_command();
+            let mut parts = command.split_whitespace();
+            let Some(program) = parts.next() else {
+                self.events
+                    .send(AppEvent::Message("file manager command is empty".into()));
+                return;
+            };
+            let dir = dir.to_string_lossy();
+            let mut args: Vec<&str> = parts.collect();
+            args.push(dir.as_ref());
+            if let Err(e) = spawn_detached(program, &args) {
+                self.events
+                    .send(AppEvent::Message(format!("{program}: {e}")));
+            }
+        }
+    }
+
+    fn open_change_file(&mut self) {
+        if let Some(selected) = self.get_selected_changes()
+            && let Some(StatusEntry { path, .. }) = selected.first()
+        {
+            if self.config.editor.is_some() {
+                self.pending_editor = Some(path.clone());
+                return;
+            }
+            match run_command(
+                "zellij",
+                vec![
+                    "edit",
+                    "-f",
+                    "--height",
+                    "70%",
+                    "--width",
+                    "70%",
+                    "-x",
+                    "15%",
+                    "-y",
+                    "15%",
+                    path.to_string_lossy().as_ref(),
+                ]
+                .as_slice(),
+            ) {
+                Ok(res) => {
+                    if !res.success() {
+                        self.events
+                            .send(AppEvent::Message(res.output().to_string()))
+                    }
+                }
+                Err(e) => self.events.send(AppEvent::Message(e.to_string())),
+            }
+        }
+    }
+}
+
+/// The order to load the status of a working copy in chunks: the root and its files first,
+/// then each top-level directory in alphabetical order. The result is reversed so that it can
+/// be popped from.
+fn initial_status_queue(root: &Path) -> Vec<(PathBuf, &'static str)> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                .filter(|entry| entry.file_name() != ".svn")
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    let mut queue: Vec<_> = dirs.into_iter().map(|dir| (dir, "infinity")).collect();
+    queue.insert(0, (root.to_path_buf(), "files"));
+    queue.reverse();
+    queue
+}
+
+/// The characters on the screen, one line per row with the trailing spaces trimmed
+fn screen_text(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width.max(1))
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .map(|line| line.trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Checks that the working copy can still be reached, describing why if it can't
+fn check_path_available(path: &Path) -> Result<(), String> {
+    use std::io::ErrorKind;
+    match std::fs::metadata(path) {
+        Ok(meta) if meta.is_dir() => Ok(()),
+        Ok(_) => Err("working copy unavailable, it is no longer a directory".into()),
+        Err(e) => Err(match e.kind() {
+            ErrorKind::NotFound => {
+                "working copy unavailable, it was deleted or its drive was unmounted".into()
+            }
+            ErrorKind::StaleNetworkFileHandle => {
+                "working copy unavailable, its network drive has gone away".into()
+            }
+            ErrorKind::PermissionDenied => "working copy unavailable, permission denied".into(),
+            _ => format!("working copy unavailable: {e}"),
+        }),
+    }
+}
+
+/// A heading with how many revisions there are, then the revisions ten to a line
+fn format_mergeinfo(heading: &str, source: &str, revisions: &[u32]) -> String {
+    let mut text = format!("{heading} from {source}: {}\n", revisions.len());
+    for line in revisions.chunks(10) {
+        let revs: Vec<String> = line.iter().map(|rev| format!("r{rev}")).collect();
+        text.push_str(&format!("  {}\n", revs.join(" ")));
+    }
+    text.push('\n');
+    text
+}
+
+/// Whether the branch url is the one checked out, going by the branch name from 'svn info'
+fn is_current_branch(url: &str, current_branch: &str) -> bool {
+    url.rsplit('/').next() == Some(current_branch)
+}
+
+/// Replaces `{cwd}`, `{branch}`, `{path}` and `{paths}` in a custom command, quoting them for
+/// the shell. Without a highlighted change `{path}` is left empty.
+fn expand_custom_command(
+    template: &str,
+    cwd: &Path,
+    branch: &str,
+    path: Option<&str>,
+    paths: &[String],
+) -> String {
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| command::shell_quote(path))
+        .collect();
+    let placeholders = [
+        ("{cwd}", command::shell_quote(&cwd.to_string_lossy())),
+        ("{branch}", command::shell_quote(branch)),
+        ("{paths}", paths.join(" ")),
+        ("{path}", path.map(command::shell_quote).unwrap_or_default()),
+    ];
+    // in one pass, so a placeholder inside a value, e.g. a path with "{cwd}" in its name, is
+    // left as it is
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match placeholders
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                expanded.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Replaces `{branch}` and `{previous}` in the on_branch_change hook
+fn expand_branch_hook(hook: &str, branch: &str, previous: &str) -> String {
+    hook.replace("{branch}", branch)
+        .replace("{previous}", previous)
+}
+
+fn handle_scroll(dir: &Direction, offset: &mut usize, bar_state: &mut ScrollbarState) {
+    let operation = match dir {
+        Direction::Up => usize::saturating_sub,
+        Direction::Down => usize::saturating_add,
+    };
+    *offset = operation(*offset, 1);
+    *bar_state = bar_state.position(*offset);
+}
+
+fn time_for_update(last_updated: DateTime<Utc>, interval: Duration) -> bool {
+    let elapsed = Utc::now().signed_duration_since(last_updated);
+    elapsed.num_milliseconds() > interval.as_millis() as i64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppSection {
+    Changes,
+    Conflicts,
+    ChangePopup,
+}
+
+#[cfg(test)]
+#[allow(clippy::too_many_arguments)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    use chrono::TimeDelta;
+    use ratatui::style::Color;
+    use rstest::*;
+
+    /// Handles the app events queued so far, e.g. the output of a streamed command
+    fn handle_app_events(a: &mut App) {
+        while let Some(event) = a.events.try_next() {
+            if let Event::App(app_event) = event {
+                a.handle_app_event(app_event);
+            }
+        }
+    }
+
+    fn rect(loc: u16) -> Rect {
+        Rect {
+            x: loc,
+            y: loc,
+            width: 1,
+            height: 1,
+        }
+    }
+
+    #[test]
+    fn test_handle_click() {
+        let mut a = App::new();
+        a.changes_area = Some(Rect {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 5,
+        });
+        let file_list = vec![
+            StatusEntry::new(State::Modified, "path1"),
+            StatusEntry::new(State::Modified, "path2"),
+            StatusEntry::new(State::Modified, "path3"),
+        ];
+        *a.file_list.list_mut() = file_list.clone();
+        a.list_state = ListState::default();
+
+        a.mouse_loc = (3, 0);
+        a.handle_click(MouseButton::Left);
+        a.handle_events().unwrap();
+
+        assert_eq!(a.state, AppState::Main);
+        assert_eq!(a.change_popup_area, None);
+        assert_eq!(a.list_state.selected(), Some(2));
+        assert_eq!(a.get_selected_changes(), Some(vec![&file_list[2]]))
+    }
+
+    #[rstest]
+    #[case(Direction::Down, 0, 0, Some(0), 0, 0, Some(1), 1)]
+    #[case(Direction::Down, 1, 0, Some(0), 1, 0, Some(1), 1)]
+    #[case(Direction::Down, 1, 0, Some(1), 0, 0, Some(2), 2)]
+    fn test_handle_mouse_scroll_changes_section(
+        #[case] dir: Direction,
+        #[case] cont_length: usize,
+        #[case] offset: usize,
+        #[case] selected: Option<usize>,
+        #[case] position: usize,
+        #[case] exp_offset: usize,
+        #[case] exp_selected: Option<usize>,
+        #[case] exp_position: usize,
+    ) {
+        let mut a = App::new();
+        a.mouse_loc = (0, 0);
+        a.changes_area = Some(rect(0));
+        a.list_state = a.list_state.with_offset(offset).with_selected(selected);
+        a.changes_scrollbar_state = a
+            .changes_scrollbar_state
+            .content_length(cont_length)
+            .position(position);
+        let exp_list_state = ListState::default()
+            .with_offset(exp_offset)
+            .with_selected(exp_selected);
+        let exp_scroll_state = ScrollbarState::new(cont_length).position(exp_position);
+        a.handle_mouse_scroll(dir);
+        assert_eq!(exp_list_state, a.list_state);
+        assert_eq!(exp_scroll_state, a.changes_scrollbar_state);
+    }
+
+    #[rstest]
+    #[case(Some(rect(0)), None, (0, 0), Some(AppSection::Changes))]
+    #[case(None, Some(rect(2)), (2, 2), Some(AppSection::ChangePopup))]
+    #[case(Some(rect(0)), Some(rect(0)), (0, 0), Some(AppSection::ChangePopup))]
+    #[case(Some(rect(0)), Some(rect(2)), (3, 3), None)]
+    fn test_current_mouse_section(
+        #[case] changes: Option<Rect>,
+        #[case] change_popup: Option<Rect>,
+        #[case] loc: (u16, u16),
+        #[case] expected: Option<AppSection>,
+    ) {
+        let mut a = App::new();
+        a.changes_area = changes;
+        a.change_popup_area = change_popup;
+        a.mouse_loc = loc;
+        assert_eq!(expected, a.current_mouse_section());
+    }
+
+    #[rstest]
+    #[case(-3000, 5000, false)]
+    #[case(-4000, 5000, false)]
+    #[case(-4900, 5000, false)]
+    #[case(-6000, 5000, true)]
+    #[case(-400, 500, false)]
+    #[case(-600, 500, true)]
+    fn test_time_for_update(
+        #[case] last_updated_ms: i64,
+        #[case] interval_ms: u64,
+        #[case] expected: bool,
+    ) {
+        let last_updated = Utc::now().checked_add_signed(TimeDelta::milliseconds(last_updated_ms));
+        let interval = Duration::from_millis(interval_ms);
+        assert_eq!(expected, time_for_update(last_updated.unwrap(), interval));
+    }
+
+    #[rstest]
+    #[case(1, 2, Direction::Up, 1)]
+    #[case(1, 1, Direction::Up, 0)]
+    #[case(1, 0, Direction::Up, 0)]
+    #[case(1, 2, Direction::Down, 3)]
+    #[case(1, 1, Direction::Down, 2)]
+    #[case(1, 0, Direction::Down, 1)]
+    #[case(2, 2, Direction::Down, 3)]
+    #[case(2, 1, Direction::Down, 2)]
+    #[case(2, 0, Direction::Down, 1)]
+    fn test_handle_scroll(
+        #[case] start_pos: usize,
+        #[case] offset: usize,
+        #[case] dir: Direction,
+        #[case] exp_offset: usize,
+    ) {
+        let mut scroll_state = ScrollbarState::new(3).position(start_pos);
+        let mut offset = offset;
+        handle_scroll(&dir, &mut offset, &mut scroll_state);
+        assert_eq!(exp_offset, offset, "offset = {offset:?}");
+        assert_eq!(
+            ScrollbarState::new(3).position(offset),
+            scroll_state,
+            "state = {scroll_state:?}"
+        );
+    }
+
+    fn app_with_changes(paths: &[&str]) -> App {
+        let mut a = App::new();
+        *a.file_list.list_mut() = paths
+            .iter()
+            .map(|p| StatusEntry::new(State::Modified, p))
+            .collect();
+        a
+    }
+
+    #[test]
+    fn test_drill_into_and_scope_to_directory() {
+        let dir = TempDir::new("drill");
+        let root = dir.path().join("deep_wc");
+        std::fs::create_dir_all(root.join("src/app")).unwrap();
+        let mut a = App::new();
+        a.cwd = root.clone();
+        a.config.status_depth = StatusDepth::Immediates;
+        a.update_svn_status();
+        let paths = |a: &App| -> Vec<PathBuf> {
+            a.file_list.list().iter().map(|e| e.path.clone()).collect()
+        };
+        assert_eq!(
+            vec![PathBuf::from("README.md"), PathBuf::from("src")],
+            paths(&a)
+        );
+        a.select_change(1);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('X')))
+            .unwrap();
+        assert_eq!(Some(Prompt::new(PromptKind::DrillIn, "src/")), a.prompt);
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("src"),
+                PathBuf::from("src/app/ui.rs")
+            ],
+            paths(&a)
+        );
+        assert_eq!(Some(&"reading src in full".to_string()), a.messages.last());
+        a.drill_in("src/app");
+        assert_eq!(
+            Some(&"src/app is already read in full".to_string()),
+            a.messages.last()
+        );
+        a.drill_in("nowhere");
+        assert_eq!(
+            Some(&"nowhere: no such directory".to_string()),
+            a.messages.last()
+        );
+        // scoped inside a directory read in full, the scope is too
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('s')))
+            .unwrap();
+        assert_eq!(Some(Prompt::new(PromptKind::Scope, "src/")), a.prompt);
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(Some(PathBuf::from("src")), a.scope);
+        assert_eq!(
+            vec![PathBuf::from("src"), PathBuf::from("src/app/ui.rs")],
+            paths(&a)
+        );
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("Changes in src"), "{text}");
+        a.handle_key_event(KeyEvent::from(KeyCode::Backspace))
+            .unwrap();
+        assert_eq!(None, a.scope);
+        assert_eq!(3, paths(&a).len());
+        a.scope = Some(PathBuf::from("src/app"));
+        assert_eq!(vec!["src", "app"], a.scope_breadcrumbs());
+    }
+
+    #[test]
+    fn test_clean_favorites_are_listed() {
+        let root = TempDir::new("favorites");
+        std::fs::write(root.path().join("CHANGELOG.md"), "").unwrap();
+        let mut a = app_with_changes(&["a.txt"]);
+        a.cwd = root.path().to_path_buf();
+        a.pinned = vec![PathBuf::from("CHANGELOG.md"), PathBuf::from("gone.txt")];
+        a.changes_updated();
+        let favorite = StatusEntry::new(State::Clean, "CHANGELOG.md");
+        assert_eq!(vec![favorite.clone()], a.favorites);
+        assert_eq!(
+            vec![
+                Row::Favorites { count: 1 },
+                Row::Change(&favorite),
+                Row::Change(&a.file_list.list()[0]),
+            ],
+            a.rows()
+        );
+        a.select_change(1);
+        assert_eq!(
+            vec![
+                Action::Open,
+                Action::OpenDir,
+                Action::Lock,
+                Action::Pin,
+                Action::Export,
+                Action::CopyUrl,
+                Action::History,
+                Action::RestoreRevision,
+                Action::Blame
+            ],
+            a.selection_actions()
+        );
+    }
+
+    #[test]
+    fn test_select_all_skips_excluded_changelists() {
+        let mut a = App::new();
+        a.file_list = svn::FileList::from_svn_status(
+            "M       file1.txt\n--- Changelist 'ignore-on-commit':\nM       local.cfg\n",
+        )
+        .unwrap();
+        a.select_all_changes();
+        assert_eq!(vec![PathBuf::from("file1.txt")], a.multiselection);
+
+        a.toggle_excluded_changelists();
+        assert_eq!(1, a.file_list.renderable().len());
+        a.toggle_excluded_changelists();
+        assert_eq!(2, a.file_list.renderable().len());
+    }
+
+    #[rstest]
+    #[case(None, KeyCode::PageDown, Some(5))]
+    #[case(Some(3), KeyCode::PageDown, Some(8))]
+    #[case(Some(17), KeyCode::PageDown, Some(19))]
+    #[case(Some(7), KeyCode::PageUp, Some(2))]
+    #[case(Some(3), KeyCode::PageUp, Some(0))]
+    #[case(Some(10), KeyCode::Home, Some(0))]
+    #[case(None, KeyCode::End, Some(19))]
+    fn test_paging_keys(
+        #[case] selected: Option<usize>,
+        #[case] key: KeyCode,
+        #[case] exp_selected: Option<usize>,
+    ) {
+        let paths: Vec<String> = (0..20).map(|i| format!("path{i}")).collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let mut a = app_with_changes(&paths);
+        a.changes_area = Some(Rect {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 7,
+        });
+        a.list_state.select(selected);
+        a.handle_key_event(KeyEvent::from(key)).unwrap();
+        assert_eq!(exp_selected, a.list_state.selected());
+        assert_eq!(
+            ScrollbarState::default().position(exp_selected.unwrap()),
+            a.changes_scrollbar_state
+        );
+    }
+
+    #[test]
+    fn test_multiselection_keys() {
+        let mut a = app_with_changes(&["path1", "path2", "path3"]);
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        a.handle_key_event(ctrl('a')).unwrap();
+        assert_eq!(3, a.multiselection.len());
+
+        a.toggle_multiselection(1);
+        assert_eq!(
+            vec![PathBuf::from("path1"), PathBuf::from("path3")],
+            a.multiselection
+        );
+
+        a.handle_key_event(ctrl('i')).unwrap();
+        assert_eq!(vec![PathBuf::from("path2")], a.multiselection);
+        // Tab is left for moving focus
+        a.handle_key_event(KeyEvent::from(KeyCode::Tab)).unwrap();
+        assert_eq!(vec![PathBuf::from("path2")], a.multiselection);
+        assert_eq!(
+            Some(vec![&StatusEntry::new(State::Modified, "path2")]),
+            a.get_selected_changes()
+        );
+
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert!(a.multiselection.is_empty());
+    }
+
+    #[test]
+    fn test_update_to_revision() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('U')))
+            .unwrap();
+        for c in "r41".chars() {
+            a.handle_key_event(KeyEvent::from(KeyCode::Char(c)))
+                .unwrap();
+        }
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Output, a.state);
+        handle_app_events(&mut a);
+        assert_eq!(
+            vec!["Updating '.':", "U    a.txt", "Updated to revision 41."],
+            a.output.as_ref().unwrap().output
+        );
+        assert_eq!(Some(41), a.updated_revision);
+        assert_eq!(
+            Some(&"updated to revision 41".to_string()),
+            a.messages.last()
+        );
+        assert_eq!(AppState::UpdateSummary, a.state);
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("Updated to r41"), "{text}");
+        assert!(text.contains("1 updated"), "{text}");
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert!(a.output.is_none());
+        a.update_to_revision("HEAD~1");
+        assert_eq!(
+            Some(&"'HEAD~1' isn't a revision number".to_string()),
+            a.messages.last()
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let mut a = app_with_changes(&["a.txt", "b.txt"]);
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('E')))
+            .unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+        assert_eq!(
+            Some(Prompt::new(
+                PromptKind::Export("a.txt".into()),
+                "../a.txt-export"
+            )),
+            a.prompt
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            Some(&"exported a.txt to ../a.txt-export".to_string()),
+            a.messages.last()
+        );
+
+        // the whole working copy without a selection
+        a.cwd = PathBuf::from("/work/repo");
+        a.list_state.select(None);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('E')))
+            .unwrap();
+        let prompt = a.prompt.as_ref().unwrap();
+        assert_eq!(PromptKind::Export(".".into()), prompt.kind);
+        assert_eq!("../repo-export", prompt.input);
+    }
+
+    #[test]
+    fn test_open_in_configured_editor() {
+        let mut a = app_with_changes(&["src/a.rs", "c.rs"]);
+        a.config.editor = Some("hx".into());
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('o')))
+            .unwrap();
+        assert_eq!(Some(PathBuf::from("src/a.rs")), a.pending_editor);
+    }
+
+    #[test]
+    fn test_copy_url() {
+        let mut a = app_with_changes(&["src/a b.rs", "c.rs"]);
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('y')))
+            .unwrap();
+        assert_eq!(
+            Some(&"copied https://svn.example.com/repo/trunk/src/a%20b.rs".to_string()),
+            a.messages.last()
+        );
+        a.select_all_changes();
+        a.copy_change_url();
+        assert_eq!(
+            Some(&"pick a single path to copy the url of".to_string()),
+            a.messages.last()
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let mut a = app_with_changes(&["a.txt"]);
+        a.history = Some(HistoryScreen::new("a.txt", HistoryPurpose::Browse));
+        a.state = AppState::History;
+        // the history of a file that's no longer shown is dropped
+        a.handle_app_event(AppEvent::History {
+            path: "b.txt".into(),
+            result: Ok(vec![]),
+        });
+        assert_eq!(None, a.history.as_ref().unwrap().result);
+        a.handle_app_event(AppEvent::History {
+            path: "a.txt".into(),
+            result: svn::log::get_file_history("a.txt", &Config::default().retry_policy())
+                .map_err(|e| e.to_string()),
+        });
+        let screen = a.history.as_ref().unwrap();
+        assert_eq!(Some(12), screen.selected_entry().map(|e| e.revision));
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            Some("Index: a.txt\n-old\n+new\n".to_string()),
+            a.pending_pager
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert!(a.history.is_none());
+    }
+
+    #[test]
+    fn test_incoming_pane() {
+        let mut a = app_with_changes(&["a.txt"]);
+        a.incoming = Some(IncomingPane::default());
+        a.handle_app_event(AppEvent::Incoming(
+            svn::incoming::get_incoming(
+                "changed_by_someone_else",
+                &Config::default().retry_policy(),
+            )
+            .map_err(|e| e.to_string()),
+        ));
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("Incoming (2, HEAD r45)"), "{text}");
+        assert!(text.contains("* a.txt (locally modified)"), "{text}");
+        assert!(text.contains("* b.txt"), "{text}");
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('F')))
+            .unwrap();
+        assert!(a.incoming.is_none());
+        // an answer arriving after the pane was hidden doesn't bring it back
+        a.handle_app_event(AppEvent::Incoming(Ok(Default::default())));
+        assert!(a.incoming.is_none());
+    }
+
+    #[test]
+    fn test_update_summary_goes_to_conflicts() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("conflicted_in_subdir");
+        a.show_output(
+            Operation::Update {
+                revision: "HEAD".into(),
+            },
+            Ok(()),
+        );
+        for line in [
+            "Updating '.':",
+            "U    docs/x.md",
+            "C    src/app/ui.rs",
+            "Updated to revision 45.",
+        ] {
+            a.handle_output_progress(CommandProgress::Output(line.into()));
+        }
+        a.handle_output_progress(CommandProgress::Finished(Ok(())));
+        assert_eq!(AppState::UpdateSummary, a.state);
+        assert_eq!(AppSection::Conflicts, a.focus);
+        assert_eq!(vec!["src/app/ui.rs".to_string()], a.selected_paths());
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("1 conflicted, Enter to resolve"), "{text}");
+        // the output can still be looked over before resolving
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('J')))
+            .unwrap();
+        assert_eq!(AppState::Output, a.state);
+    }
+
+    #[test]
+    fn test_last_commit_in_branch_box() {
+        let mut a = app_with_changes(&["a.txt"]);
+        a.current_branch = "trunk".into();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.handle_app_event(AppEvent::LastCommit {
+            path: "changed_by_someone_else".into(),
+            result: get_last_commit("changed_by_someone_else", &Config::default().retry_policy())
+                .map_err(|e| e.to_string()),
+        });
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("│trunk"), "{text}");
+        assert!(text.contains("│r42 alice: Fix the build"), "{text}");
+        assert!(!text.contains("It was broken"), "{text}");
+        assert!(text.contains("│changed_by_someone_else"), "{text}");
+        // an answer for the working copy that was switched away from is dropped
+        a.handle_app_event(AppEvent::LastCommit {
+            path: "elsewhere".into(),
+            result: Ok(None),
+        });
+        assert_eq!(Some(42), a.last_commit.as_ref().map(|entry| entry.revision));
+        // a failed read keeps the last one showing and is tried again on the next refresh
+        a.last_commit_stale = false;
+        a.handle_app_event(AppEvent::LastCommit {
+            path: "changed_by_someone_else".into(),
+            result: Err("could not reach the repository".into()),
+        });
+        assert!(a.last_commit_stale);
+        assert_eq!(Some(42), a.last_commit.as_ref().map(|entry| entry.revision));
+        // a commit made from the app makes it out of date
+        a.last_commit_stale = false;
+        a.show_output(
+            Operation::Commit {
+                paths: vec!["a.txt".into()],
+                message: "fix".into(),
+                keep_locks: false,
+            },
+            Ok(()),
+        );
+        a.handle_output_progress(CommandProgress::Finished(Ok(())));
+        assert!(a.last_commit_stale);
+    }
+
+    #[test]
+    fn test_revisions_behind() {
+        let mut a = app_with_changes(&["a.txt"]);
+        a.current_branch = "trunk".into();
+        a.handle_app_event(AppEvent::Behind(
+            svn::incoming::get_revisions_behind(
+                "changed_by_someone_else",
+                &Config::default().retry_policy(),
+            )
+            .map_err(|e| e.to_string()),
+        ));
+        // a failed check leaves the last answer showing
+        a.handle_app_event(AppEvent::Behind(Err(
+            "could not reach the repository".into()
+        )));
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("trunk · 2 revisions behind"), "{text}");
+        // being up to date isn't worth mentioning
+        a.handle_app_event(AppEvent::Behind(Ok(0)));
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(!text.contains("behind"), "{text}");
+    }
+
+    #[test]
+    fn test_blame() {
+        let mut a = app_with_changes(&["a.txt"]);
+        a.config.blame_gradient = "#000000,#ff0000".parse().unwrap();
+        a.blame = Some(BlameScreen::new("a.txt"));
+        a.state = AppState::Blame;
+        a.handle_app_event(AppEvent::Blame {
+            path: "a.txt".into(),
+            result: svn::blame::get_blame("a.txt").map_err(|e| e.to_string()),
+        });
+        let mut terminal = Terminal::new(TestBackend::new(50, 12)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("     3 alice       old line"), "{text}");
+        assert!(text.contains("    12 bob         new line"), "{text}");
+        let row = text
+            .lines()
+            .position(|line| line.contains("new line"))
+            .unwrap() as u16;
+        let buffer = terminal.backend().buffer();
+        let colours: Vec<Color> = (0..50)
+            .map(|x| buffer[(x, row)].bg)
+            .filter(|bg| *bg != Color::Reset)
+            .collect();
+        assert_eq!(Some(&Color::Rgb(255, 0, 0)), colours.first());
+        a.handle_key_event(KeyEvent::from(KeyCode::Down)).unwrap();
+        assert_eq!(1, a.blame.as_ref().unwrap().scroll);
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+    }
+
+    #[test]
+    fn test_restore_revision() {
+        let dir = TempDir::new("restore");
+        let file = dir.path().join("restored.txt");
+        std::fs::write(&file, "local edits\n").unwrap();
+        let path = file.to_string_lossy().to_string();
+        let mut a = app_with_changes(&[&path]);
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        let mut screen = HistoryScreen::new(&path, HistoryPurpose::Restore);
+        screen.result = Some(
+            svn::log::get_file_history("a.txt", &Config::default().retry_policy())
+                .map_err(|e| e.to_string()),
+        );
+        screen.list_state.select(Some(0));
+        a.history = Some(screen);
+        a.state = AppState::History;
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(
+            Some(format!("restored {path} to r12")),
+            a.messages.last().cloned()
+        );
+        assert_eq!("old contents\n", std::fs::read_to_string(&file).unwrap());
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('z')))
+            .unwrap();
+        assert_eq!("local edits\n", std::fs::read_to_string(&file).unwrap());
+    }
+
+    #[test]
+    fn test_undo_add() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('z')))
+            .unwrap();
+        assert_eq!(Some(&"nothing to undo".to_string()), a.messages.last());
+
+        a.file_list = svn::FileList::from_svn_status("?       new.txt\n").unwrap();
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('a')))
+            .unwrap();
+        assert_eq!(Some(Undo::Add(vec!["new.txt".into()])), a.last_undo);
+        // only the added path's status was refreshed
+        assert_eq!(
+            &[StatusEntry::new(State::Added, "new.txt")],
+            a.file_list.list()
+        );
+
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('z')))
+            .unwrap();
+        assert_eq!(None, a.last_undo);
+        assert_eq!(
+            Some(&"undid the add of new.txt".to_string()),
+            a.messages.last()
+        );
+    }
+
+    #[test]
+    fn test_undo_revert_of_binary_file() {
+        let dir = TempDir::new("undo-binary");
+        let file = dir.path().join("image.png");
+        std::fs::write(&file, [0x89, b'P', b'N', b'G']).unwrap();
+        let path = file.to_string_lossy().to_string();
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        // the diff has nothing of the file's changes, so its contents are kept instead
+        let changes = a.local_changes(std::slice::from_ref(&path)).unwrap();
+        assert_eq!(
+            vec![(path.clone(), vec![0x89, b'P', b'N', b'G'])],
+            changes.backups
+        );
+        std::fs::write(&file, "reverted").unwrap();
+        a.last_undo = Some(Undo::Revert {
+            paths: vec![path],
+            changes: LocalChanges {
+                patch: "".into(),
+                ..changes
+            },
+        });
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('z')))
+            .unwrap();
+        assert_eq!(vec![0x89, b'P', b'N', b'G'], std::fs::read(&file).unwrap());
+        assert_eq!(None, a.last_undo);
+    }
+
+    #[test]
+    fn test_failed_undo_is_kept() {
+        let dir = TempDir::new("undo-failed");
+        let missing = dir.path().join("gone/image.png");
+        let mut a = App::new();
+        let undo = Undo::Revert {
+            paths: vec!["image.png".into()],
+            changes: LocalChanges {
+                patch: "".into(),
+                backups: vec![(missing.to_string_lossy().to_string(), vec![1])],
+            },
+        };
+        a.last_undo = Some(undo.clone());
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('z')))
+            .unwrap();
+        assert!(a.messages.last().unwrap().starts_with("could not put back"));
+        assert_eq!(Some(undo), a.last_undo);
+    }
+
+    #[test]
+    fn test_retry_transient_failure_later() {
+        let mut a = app_with_changes(&["flaky.txt"]);
+        a.config.network_retries = 1;
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('l')))
+            .unwrap();
+        handle_app_events(&mut a);
+        assert_eq!(
+            Some(&"network error, retrying (1/1)".to_string()),
+            a.messages.last()
+        );
+        assert!(a.last_undo.is_none());
+        // sent once the backoff has passed, by then it's the last retry allowed
+        let job = Job::Action {
+            action: Action::Lock,
+            paths: vec!["flaky.txt".into()],
+        };
+        a.handle_app_event(AppEvent::Retry { job, retry: 1 });
+        handle_app_events(&mut a);
+        assert!(a.messages.last().unwrap().contains("Connection reset"));
+        assert_eq!(0, a.retrying);
+    }
+
+    #[test]
+    fn test_fix_and_retry() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.file_list = svn::FileList::from_svn_status("D       a.txt\n").unwrap();
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('c')))
+            .unwrap();
+        for c in "Fix the thing".chars() {
+            a.handle_key_event(KeyEvent::from(KeyCode::Char(c)))
+                .unwrap();
+        }
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::CommitReview, a.state);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('y')))
+            .unwrap();
+        handle_app_events(&mut a);
+        assert_eq!("Fix the thing", a.commit_draft);
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        let fix = PendingFix {
+            precondition: Precondition::Update,
+            action: Action::Commit,
+            paths: vec!["a.txt".into()],
+        };
+        assert_eq!(Some(fix.clone()), a.pending_fix);
+        assert!(a.hints()[0].starts_with("f: update and retry"));
+
+        // the update succeeds, then the commit is retried with the same message
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('f')))
+            .unwrap();
+        assert_eq!(
+            Some(Prompt::new(PromptKind::CommitMessage(1), "Fix the thing")),
+            a.prompt
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('y')))
+            .unwrap();
+        handle_app_events(&mut a);
+        assert_eq!(Some(fix), a.pending_fix);
+        assert!(a.multiselection.is_empty());
+    }
+
+    #[test]
+    fn test_refresh_keeps_selected_path() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.file_list = svn::FileList::from_svn_status("M       0.txt\nD       a.txt\n").unwrap();
+        a.list_state.select(Some(1));
+        // 0.txt was committed elsewhere, moving a.txt up a row
+        a.update_svn_status();
+        assert_eq!(Some(0), a.list_state.selected());
+        assert_eq!(
+            Some(&StatusEntry::new(State::Deleted, "a.txt")),
+            a.change_at(0)
+        );
+    }
+
+    #[test]
+    fn test_commit_review_keys() {
+        let mut a = App::new();
+        a.file_list = svn::FileList::from_svn_status("M       a.txt\nA       b.txt\n").unwrap();
+        a.multiselection = vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")];
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('c')))
+            .unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('x')))
+            .unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::CommitReview, a.state);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('k')))
+            .unwrap();
+        assert!(a.commit_review.as_ref().unwrap().keep_locks);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('d')))
+            .unwrap();
+        assert_eq!(
+            vec!["b.txt".to_string()],
+            a.commit_review.as_ref().unwrap().paths()
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('e')))
+            .unwrap();
+        assert_eq!(
+            Some(Prompt::new(PromptKind::CommitMessage(1), "x")),
+            a.prompt
+        );
+        // the message is kept for the next commit after cancelling
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert!(a.commit_review.is_none());
+        assert_eq!("x", a.commit_draft);
+    }
+
+    #[test]
+    fn test_message_or_hints() {
+        let mut a = App::new();
+        a.file_list = svn::FileList::from_svn_status("?       new.txt\n").unwrap();
+        assert_eq!(
+            (
+                "r: refresh  w: workspaces  B: branches  e: export  q: quit".to_string(),
+                true
+            ),
+            a.message_or_hints()
+        );
+
+        a.list_state.select(Some(0));
+        let (hints, _) = a.message_or_hints();
+        assert!(hints.starts_with("o: open  O: open dir"), "{hints}");
+        assert!(hints.contains("a: add"), "{hints}");
+        assert!(!hints.contains("u: revert"), "{hints}");
+
+        a.push_message("added new.txt".into());
+        assert_eq!(("added new.txt".to_string(), false), a.message_or_hints());
+        a.last_message_at = Some(Utc::now() - MESSAGE_DURATION * 2);
+        assert!(a.message_or_hints().1);
+    }
+
+    #[test]
+    fn test_rename_changelist_prompt() {
+        let mut a = App::new();
+        a.file_list = svn::FileList::from_svn_status(
+            "M       file1.txt\n--- Changelist 'feature':\nM       a.txt\nM       b.txt\n",
+        )
+        .unwrap();
+        a.list_state.select(Some(0));
+        assert!(!a.selection_actions().contains(&Action::RenameChangelist));
+
+        a.list_state.select(Some(1));
+        assert_eq!(vec!["a.txt", "b.txt"], a.changelist_member_paths("feature"));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('n')))
+            .unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+        assert_eq!(
+            Some(Prompt::new(
+                PromptKind::RenameChangelist("feature".into()),
+                "feature"
+            )),
+            a.prompt
+        );
+
+        // typing doesn't trigger the action keys
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('x')))
+            .unwrap();
+        assert_eq!("featurex", a.prompt.as_ref().unwrap().input);
+
+        a.handle_key_event(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+            .unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(None, a.prompt);
+        assert_eq!(
+            Some(&"a changelist name can't be empty".to_string()),
+            a.messages.last()
+        );
+    }
+
+    #[test]
+    fn test_commit_changelist_is_reviewed() {
+        let mut a = App::new();
+        a.file_list = svn::FileList::from_svn_status(
+            "M       file1.txt\n--- Changelist 'feature':\nM       a.txt\nM       b.txt\n",
+        )
+        .unwrap();
+        a.list_state.select(Some(1));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('g')))
+            .unwrap();
+        assert_eq!(
+            Some(Prompt::new(PromptKind::CommitMessage(2), "")),
+            a.prompt
+        );
+        assert_eq!(
+            vec!["a.txt".to_string(), "b.txt".to_string()],
+            a.commit_review.as_ref().unwrap().paths()
+        );
+        for c in "Add the feature".chars() {
+            a.handle_key_event(KeyEvent::from(KeyCode::Char(c)))
+                .unwrap();
+        }
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::CommitReview, a.state);
+    }
+
+    #[test]
+    fn test_workspaces_keys() {
+        let mut a = App::new();
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('w')))
+            .unwrap();
+        assert_eq!(AppState::Main, a.state);
+
+        a.workspaces = ["core", "ui"]
+            .into_iter()
+            .map(|path| WorkspaceSummary {
+                path: PathBuf::from(path),
+                branch: "trunk".into(),
+                counts: vec![],
+                error: None,
+                loading: false,
+            })
+            .collect();
+        a.state = AppState::Workspaces;
+        for (key, exp_selected) in [
+            (KeyCode::Down, Some(1)),
+            (KeyCode::Down, Some(1)),
+            (KeyCode::Up, Some(0)),
+            (KeyCode::End, Some(1)),
+        ] {
+            a.handle_key_event(KeyEvent::from(key)).unwrap();
+            assert_eq!(exp_selected, a.workspaces_list_state.selected());
+        }
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+    }
+
+    #[test]
+    fn test_change_directory_outside_a_working_copy() {
+        let temp = TempDir::new("cd");
+        let dir = temp.path();
+        let mut a = App::new();
+        a.cwd = PathBuf::from("/work/wc");
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('C')))
+            .unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+        for c in dir.to_string_lossy().chars() {
+            a.handle_key_event(KeyEvent::from(KeyCode::Char(c)))
+                .unwrap();
+        }
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        assert_eq!(
+            Some(&format!("{} isn't in a working copy", dir.display())),
+            a.messages.last()
+        );
+        assert_eq!(PathBuf::from("/work/wc"), a.cwd);
+
+        a.change_directory("does/not/exist");
+        assert!(a.messages.last().unwrap().starts_with("does/not/exist: "));
+    }
+
+    #[test]
+    fn test_bookmarks_keys() {
+        let mut a = App::new();
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('b')))
+            .unwrap();
+        assert_eq!(AppState::Main, a.state);
+
+        a.cwd = PathBuf::from("/work/ui");
+        a.config.bookmarks = vec![PathBuf::from("/work/core"), PathBuf::from("/work/ui")];
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('b')))
+            .unwrap();
+        assert_eq!(AppState::Bookmarks, a.state);
+        assert_eq!(Some(1), a.bookmarks_list_state.selected());
+        for (key, exp_selected) in [
+            (KeyCode::Down, Some(1)),
+            (KeyCode::Up, Some(0)),
+            (KeyCode::Up, Some(0)),
+        ] {
+            a.handle_key_event(KeyEvent::from(key)).unwrap();
+            assert_eq!(exp_selected, a.bookmarks_list_state.selected());
+        }
+        // jumping to the working copy that's already open just closes the popup
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('2')))
+            .unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(PathBuf::from("/work/ui"), a.cwd);
+    }
+
+    #[test]
+    fn test_format_mergeinfo() {
+        let revisions: Vec<u32> = (1..=12).collect();
+        assert_eq!(
+            "Eligible to merge from ^/trunk: 12\n  r1 r2 r3 r4 r5 r6 r7 r8 r9 r10\n  r11 r12\n\n",
+            format_mergeinfo("Eligible to merge", "^/trunk", &revisions)
+        );
+        assert_eq!(
+            "Already merged from ^/trunk: 0\n\n",
+            format_mergeinfo("Already merged", "^/trunk", &[])
+        );
+    }
+
+    #[test]
+    fn test_exit_summary() {
+        let mut a = App::new();
+        a.current_branch = "trunk".into();
+        a.cwd = PathBuf::from("/work/repo");
+        assert_eq!("trunk (/work/repo): no changes", a.exit_summary());
+        a.file_list =
+            svn::FileList::from_svn_status("M       a.txt\nC       b.txt\nM       c.txt\n")
+                .unwrap();
+        assert_eq!(
+            "trunk (/work/repo): 1 conflicted, 2 modified",
+            a.exit_summary()
+        );
+    }
+
+    #[test]
+    fn test_custom_command() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        let key = config::KeyBinding::parse("F5").unwrap();
+        a.config.custom_keys.insert(key, "make test".into());
+        a.handle_key_event(KeyEvent::from(KeyCode::F(5))).unwrap();
+        handle_app_events(&mut a);
+        assert_eq!(AppState::Output, a.state);
+        assert_eq!(
+            Some(&"make test: all passed".to_string()),
+            a.messages.last()
+        );
+        // keys that aren't bound still do what they always did
+        a.state = AppState::Main;
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('v')))
+            .unwrap();
+        assert_eq!(
+            Some(&"grouping by directory".to_string()),
+            a.messages.last()
+        );
     }
 
-    fn add_change_file(&mut self) {
-        self.perform_svn_function(svn::svn_add);
+    #[rstest]
+    #[cfg_attr(unix, case("svn update {cwd}", None, &[], "svn update '/work/it'\\''s wc'"))]
+    #[cfg_attr(windows, case("svn update {cwd}", None, &[], "svn update \"/work/it's wc\""))]
+    #[cfg_attr(unix, case("echo {branch}: {path}", Some("a.txt"), &[], "echo 'trunk': 'a.txt'"))]
+    #[cfg_attr(
+        windows,
+        case("echo {branch}: {path}", Some("a.txt"), &[], "echo \"trunk\": \"a.txt\"")
+    )]
+    #[case("echo {path}", None, &[], "echo ")]
+    #[cfg_attr(
+        unix,
+        case("svn add {paths}", None, &["a b.txt", "c.txt"], "svn add 'a b.txt' 'c.txt'")
+    )]
+    #[cfg_attr(
+        windows,
+        case("svn add {paths}", None, &["a b.txt", "c.txt"], "svn add \"a b.txt\" \"c.txt\"")
+    )]
+    #[cfg_attr(
+        unix,
+        case("svn add {paths}", Some("a.txt"), &["{path}.txt"], "svn add '{path}.txt'")
+    )]
+    #[cfg_attr(
+        windows,
+        case("svn add {paths}", Some("a.txt"), &["{path}.txt"], "svn add \"{path}.txt\"")
+    )]
+    #[cfg_attr(unix, case("echo {unknown} {{branch}", None, &[], "echo {unknown} {'trunk'"))]
+    #[cfg_attr(
+        windows,
+        case("echo {unknown} {{branch}", None, &[], "echo {unknown} {\"trunk\"")
+    )]
+    fn test_expand_custom_command(
+        #[case] template: &str,
+        #[case] path: Option<&str>,
+        #[case] paths: &[&str],
+        #[case] exp: &str,
+    ) {
+        let paths: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+        assert_eq!(
+            exp,
+            expand_custom_command(template, Path::new("/work/it's wc"), "trunk", path, &paths)
+        );
     }
 
-    fn revert_change_file(&mut self) {
-        self.perform_svn_function(svn::svn_revert);
+    #[test]
+    fn test_minimal() {
+        let mut a = App::new();
+        a.config.minimal = true;
+        a.current_branch = "trunk".into();
+        let mut terminal = Terminal::new(TestBackend::new(40, 1)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        assert_eq!("trunk clean", screen_text(terminal.backend().buffer()));
+        a.file_list =
+            svn::FileList::from_svn_status("M       a.txt\nC       b.txt\nM       c.txt\n")
+                .unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        assert_eq!(
+            "trunk C1 M2 CONFLICTS",
+            screen_text(terminal.backend().buffer())
+        );
+        a.unavailable = Some("repository unreachable".into());
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        assert_eq!(
+            "trunk repository unreachable",
+            screen_text(terminal.backend().buffer())
+        );
+        // the keys of the views that can't be seen do nothing
+        for c in ['w', 'v', 'B', ':'] {
+            a.handle_key_event(KeyEvent::from(KeyCode::Char(c)))
+                .unwrap();
+        }
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(GroupBy::None, a.config.group_by);
     }
 
-    fn commit_change_file(&mut self) {
-        self.perform_svn_function(svn::svn_commit);
+    #[test]
+    fn test_merge_screen_keys() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        let operation = Operation::Merge {
+            source: "^/branches/feature".into(),
+            revisions: "10:12".into(),
+        };
+        a.show_output(operation, Ok(()));
+        // leaving while the merge runs keeps its output to come back to
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(
+            Some(&"the merge carries on, J to watch it".to_string()),
+            a.messages.last()
+        );
+        a.handle_output_progress(CommandProgress::Output("U    a.txt".into()));
+        a.handle_output_progress(CommandProgress::Finished(Ok(())));
+        assert_eq!(
+            Some(&"merged ^/branches/feature".to_string()),
+            a.messages.last()
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('J')))
+            .unwrap();
+        assert_eq!(AppState::Output, a.state);
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert!(a.output.is_none());
     }
 
-    fn open_change_file(&mut self) {
-        if let Some(selected) = self.get_selected_changes() {
-            if let Some((_, path)) = selected.first() {
-                match run_command(
-                    "zellij",
-                    vec![
-                        "edit",
-                        "-f",
-                        "--height",
-                        "70%",
-                        "--width",
-                        "70%",
-                        "-x",
-                        "15%",
-                        "-y",
-                        "15%",
-                        path.to_string_lossy().as_ref(),
-                    ]
-                    .as_slice(),
-                ) {
-                    Ok(res) => {
-                        if !res.success() {
-                            self.events
-                                .send(AppEvent::Message(res.output().to_string()))
-                        }
-                    }
-                    Err(e) => self.events.send(AppEvent::Message(e.to_string())),
-                }
-            }
-        }
+    #[test]
+    fn test_actions_wait_for_running_command() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.file_list = svn::FileList::from_svn_status("?       new.txt\n").unwrap();
+        a.update_to_revision("41");
+        assert_eq!(Some("running update".to_string()), a.worker.status());
+        // the update carries on in the background while the changes are worked on
+        a.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        a.list_state.select(Some(0));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('a')))
+            .unwrap();
+        assert_eq!(
+            Some(&"queued add new.txt, 1 waiting".to_string()),
+            a.messages.last()
+        );
+        assert_eq!(
+            Some("running update, 1 queued".to_string()),
+            a.worker.status()
+        );
+        assert!(a.last_undo.is_none());
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('J')))
+            .unwrap();
+        assert_eq!(AppState::Output, a.state);
+        handle_app_events(&mut a);
+        assert_eq!(None, a.worker.status());
+        assert_eq!(Some(41), a.updated_revision);
+        // the add runs after the update, once the refresh has shown whether it still applies
+        assert_eq!(
+            Some(&"skipped add new.txt, it no longer applies".to_string()),
+            a.messages.last()
+        );
     }
-}
 
-fn handle_scroll(dir: &Direction, offset: &mut usize, bar_state: &mut ScrollbarState) {
-    let operation = match dir {
-        Direction::Up => usize::saturating_sub,
-        Direction::Down => usize::saturating_add,
-    };
-    *offset = operation(*offset, 1);
-    *bar_state = bar_state.position(*offset);
-}
+    #[test]
+    fn test_actions_gated_by_svn_version() {
+        let mut a = App::new();
+        a.svn_version = Some(SvnVersion::new(1, 6, 17));
+        a.file_list = svn::FileList::from_svn_status("M       a.txt\n").unwrap();
+        a.list_state.select(Some(0));
+        // still offered, so it's clear why it can't be used
+        assert!(a.selection_actions().contains(&Action::CommitHunks));
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('k')))
+            .unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(
+            Some(&"commit hunks needs svn 1.7+, this is 1.6.17".to_string()),
+            a.messages.last()
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('B')))
+            .unwrap();
+        assert_eq!(
+            Some(&"the branch switcher needs svn 1.9+, this is 1.6.17".to_string()),
+            a.messages.last()
+        );
+    }
 
-fn time_for_update(last_updated: DateTime<Utc>, timeout: u8) -> bool {
-    Utc::now().signed_duration_since(last_updated).num_seconds() > timeout.into()
-}
+    #[test]
+    fn test_svn_missing() {
+        let mut a = App::new();
+        a.svn_missing = true;
+        // nothing is checked or run while there's no svn to run
+        a.refresh();
+        assert!(a.unavailable.is_none());
+        assert_eq!("C: open another directory", a.hints()[0]);
+    }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum AppSection {
-    Changes,
-    ChangePopup,
-}
+    #[test]
+    fn test_not_a_working_copy() {
+        let mut a = App::new();
+        a.cwd = PathBuf::from("/home/someone");
+        a.not_working_copy = Some("not a working copy".into());
+        a.refresh();
+        assert!(a.unavailable.is_none());
+        assert_eq!("C: open another directory", a.hints()[0]);
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(
+            text.contains("/home/someone is not a working copy"),
+            "{text}"
+        );
+        // the cd prompt still opens
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('C')))
+            .unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::TimeDelta;
-    use rstest::*;
-    use svn::state::State;
+    #[test]
+    fn test_replay() {
+        let mut a = App::new();
+        a.svn_missing = true;
+        let recording = crate::recording::parse_recording(
+            "0\tresize\t60\t20\n0\tkey\tchar:C\t0\n10\tkey\tchar:s\t0\n10\tkey\tchar:r\t0\n10\tkey\tchar:c\t0\n",
+        )
+        .unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        a.replay_on(&mut terminal, recording).unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+        let screen = screen_text(terminal.backend().buffer());
+        assert_eq!(20, screen.lines().count());
+        assert!(screen.contains("cd (tab completes): src"), "{screen}");
+        let quit = crate::recording::parse_recording("0\tkey\tesc\t0\n0\tkey\tchar:q\t0\n0\ttick")
+            .unwrap();
+        a.replay_on(&mut terminal, quit).unwrap();
+        assert!(!a.running);
+    }
 
-    fn rect(loc: u16) -> Rect {
-        Rect {
-            x: loc,
-            y: loc,
-            width: 1,
-            height: 1,
-        }
+    #[test]
+    fn test_hunk_screen_keys() {
+        let mut a = App::new();
+        let diff = svn::hunks::FileDiff::parse("@@ -1 +1 @@\n-a\n+b\n").unwrap();
+        a.hunks = Some(HunkScreen::new("a.txt", diff));
+        a.state = AppState::Hunks;
+        a.handle_key_event(KeyEvent::from(KeyCode::Char(' ')))
+            .unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(
+            Some(&"pick at least one hunk to commit".to_string()),
+            a.messages.last()
+        );
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('a')))
+            .unwrap();
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+        // an empty message cancels the commit, leaving the file as it was
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert!(a.hunks.is_none());
     }
 
     #[test]
-    fn test_handle_click() {
+    fn test_branch_switcher_keys() {
         let mut a = App::new();
+        a.current_branch = "feature".into();
+        let mut switcher = BranchSwitcher::new(BranchPurpose::Switch, "uuid".into(), None);
+        switcher.set_branches(vec!["^/trunk".into(), "^/branches/feature".into()]);
+        a.branch_switcher = Some(switcher);
+        a.state = AppState::Branches;
+        // branches listed for a repository the popup is no longer showing are ignored
+        a.handle_branches_listed("other-uuid", Ok(vec![]));
+        assert_eq!(2, a.branch_switcher.as_ref().unwrap().branches.len());
+        a.handle_branches_listed("uuid", Err("svn: E170013: Unable to connect".into()));
+        assert!(a.branch_switcher.as_ref().unwrap().error.is_some());
+        for (key, exp_selected) in [
+            (KeyCode::Down, Some(1)),
+            (KeyCode::Down, Some(1)),
+            (KeyCode::Up, Some(0)),
+            (KeyCode::Down, Some(1)),
+        ] {
+            a.handle_key_event(KeyEvent::from(key)).unwrap();
+            let switcher = a.branch_switcher.as_ref().unwrap();
+            assert_eq!(exp_selected, switcher.list_state.selected());
+        }
+        // switching to the branch that's already checked out just closes the popup
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert!(a.branch_switcher.is_none());
+    }
+
+    #[test]
+    fn test_mouse_drag_selects_range() {
+        let mut a = app_with_changes(&["path0", "path1", "path2", "path3", "path4"]);
         a.changes_area = Some(Rect {
             x: 0,
             y: 0,
-            width: 1,
-            height: 5,
+            width: 10,
+            height: 7,
         });
-        let file_list = vec![
-            (State::Modified, PathBuf::from("path1")),
-            (State::Modified, PathBuf::from("path2")),
-            (State::Modified, PathBuf::from("path3")),
-        ];
-        *a.file_list.list_mut() = file_list.clone();
-        a.list_state = ListState::default();
+        let mouse = |kind, row| MouseEvent {
+            kind,
+            column: 1,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        a.handle_mouse_event(mouse(MouseEventKind::Down(MouseButton::Left), 4))
+            .unwrap();
+        a.handle_mouse_event(mouse(MouseEventKind::Drag(MouseButton::Left), 4))
+            .unwrap();
+        assert!(a.multiselection.is_empty());
 
-        a.mouse_loc = (3, 0);
-        a.handle_click(MouseButton::Left);
-        a.handle_events().unwrap();
+        a.handle_mouse_event(mouse(MouseEventKind::Drag(MouseButton::Left), 2))
+            .unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("path1"),
+                PathBuf::from("path2"),
+                PathBuf::from("path3")
+            ],
+            a.multiselection
+        );
+        assert_eq!(Some(1), a.list_state.selected());
 
-        assert_eq!(a.state, AppState::Main);
-        assert_eq!(a.change_popup_area, None);
-        assert_eq!(a.list_state.selected(), Some(2));
-        assert_eq!(a.get_selected_changes(), Some(vec![&file_list[2]]))
+        a.handle_mouse_event(mouse(MouseEventKind::Up(MouseButton::Left), 2))
+            .unwrap();
+        a.handle_mouse_event(mouse(MouseEventKind::Drag(MouseButton::Left), 5))
+            .unwrap();
+        assert_eq!(3, a.multiselection.len());
     }
 
-    #[rstest]
-    #[case(Direction::Down, 0, 0, Some(0), 0, 0, Some(1), 1)]
-    #[case(Direction::Down, 1, 0, Some(0), 1, 0, Some(1), 1)]
-    #[case(Direction::Down, 1, 0, Some(1), 0, 0, Some(2), 2)]
-    fn test_handle_mouse_scroll_changes_section(
-        #[case] dir: Direction,
-        #[case] cont_length: usize,
-        #[case] offset: usize,
-        #[case] selected: Option<usize>,
-        #[case] position: usize,
-        #[case] exp_offset: usize,
-        #[case] exp_selected: Option<usize>,
-        #[case] exp_position: usize,
-    ) {
+    #[test]
+    fn test_checkout_progress() {
         let mut a = App::new();
-        a.mouse_loc = (0, 0);
-        a.changes_area = Some(rect(0));
-        a.list_state = a.list_state.with_offset(offset).with_selected(selected);
-        a.changes_scrollbar_state = a
-            .changes_scrollbar_state
-            .content_length(cont_length)
-            .position(position);
-        let exp_list_state = ListState::default()
-            .with_offset(exp_offset)
-            .with_selected(exp_selected);
-        let exp_scroll_state = ScrollbarState::new(cont_length).position(exp_position);
-        a.handle_mouse_scroll(dir);
-        assert_eq!(exp_list_state, a.list_state);
-        assert_eq!(exp_scroll_state, a.changes_scrollbar_state);
+        a.checkout = Some(CheckoutScreen::new(
+            "file:///repo/trunk",
+            PathBuf::from("trunk"),
+        ));
+        a.state = AppState::Checkout;
+        for (path, bytes) in [("trunk/a.txt", 10), ("trunk/b.txt", 20)] {
+            a.handle_checkout_progress(CheckoutProgress::Added {
+                path: PathBuf::from(path),
+                bytes,
+            });
+        }
+        let screen = a.checkout.as_ref().unwrap();
+        assert_eq!((2, 30), (screen.files, screen.bytes));
+        assert_eq!(Some(PathBuf::from("trunk/b.txt")), screen.last_path);
+
+        a.handle_checkout_progress(CheckoutProgress::Finished(Err("E170013".into())));
+        assert_eq!(AppState::Checkout, a.state);
+        assert_eq!(
+            Some(&"checkout failed: E170013".to_string()),
+            a.messages.last()
+        );
     }
 
-    #[rstest]
-    #[case(Some(rect(0)), None, (0, 0), Some(AppSection::Changes))]
-    #[case(None, Some(rect(2)), (2, 2), Some(AppSection::ChangePopup))]
-    #[case(Some(rect(0)), Some(rect(0)), (0, 0), Some(AppSection::ChangePopup))]
-    #[case(Some(rect(0)), Some(rect(2)), (3, 3), None)]
-    fn test_current_mouse_section(
-        #[case] changes: Option<Rect>,
-        #[case] change_popup: Option<Rect>,
-        #[case] loc: (u16, u16),
-        #[case] expected: Option<AppSection>,
-    ) {
+    #[test]
+    fn test_conflicts_scrolling() {
         let mut a = App::new();
-        a.changes_area = changes;
-        a.change_popup_area = change_popup;
-        a.mouse_loc = loc;
-        assert_eq!(expected, a.current_mouse_section());
+        a.file_list =
+            svn::FileList::from_svn_status("C       a.txt\n?       a.txt.working\nM       b.txt\n")
+                .unwrap();
+        a.conflicts_area = Some(Rect {
+            x: 10,
+            y: 0,
+            width: 10,
+            height: 5,
+        });
+        a.conflicts_line_count = 10;
+        a.list_state.select(Some(0));
+
+        a.handle_key_event(KeyEvent::from(KeyCode::Right)).unwrap();
+        assert_eq!(AppSection::Conflicts, a.focus);
+        for (key, exp_offset) in [
+            (KeyCode::Down, 1),
+            (KeyCode::PageDown, 4),
+            (KeyCode::End, 9),
+            (KeyCode::Down, 9),
+            (KeyCode::PageUp, 6),
+            (KeyCode::Home, 0),
+        ] {
+            a.handle_key_event(KeyEvent::from(key)).unwrap();
+            assert_eq!(exp_offset, a.conflicts_scroll_offset, "{key:?}");
+        }
+        assert_eq!(Some(0), a.list_state.selected());
+
+        a.mouse_loc = (2, 12);
+        a.handle_mouse_scroll(Direction::Down);
+        a.handle_mouse_scroll(Direction::Down);
+        assert_eq!(2, a.conflicts_scroll_offset);
+        assert_eq!(
+            ScrollbarState::default().position(2),
+            a.conflicts_scrollbar_state
+        );
+
+        a.handle_key_event(KeyEvent::from(KeyCode::Left)).unwrap();
+        assert_eq!(AppSection::Changes, a.focus);
     }
 
-    #[rstest]
-    #[case(-3, 5, false)]
-    #[case(-4, 5, false)]
-    #[case(-5, 5, false)]
-    #[case(-6, 5, true)]
-    fn test_time_for_update(
-        #[case] last_updated: i64,
-        #[case] timeout: u8,
-        #[case] expected: bool,
-    ) {
-        let last_updated = Utc::now().checked_add_signed(TimeDelta::seconds(last_updated));
-        assert_eq!(expected, time_for_update(last_updated.unwrap(), timeout));
+    #[test]
+    fn test_group_by_directory() {
+        let mut a = app_with_changes(&["src/a.rs", "README.md", "src/b.rs"]);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('v')))
+            .unwrap();
+        assert_eq!(GroupBy::Directory, a.config.group_by);
+        // ". (1)", "README.md", "src (2)", "src/a.rs", "src/b.rs"
+        assert_eq!(5, a.rows().len());
+
+        a.select_change(3);
+        assert_eq!(
+            Some(vec![&StatusEntry::new(State::Modified, "src/a.rs")]),
+            a.get_selected_changes()
+        );
+        a.select_change(2);
+        assert_eq!(None, a.get_selected_changes());
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(3, a.rows().len());
+        a.handle_key_event(KeyEvent::from(KeyCode::End)).unwrap();
+        assert_eq!(Some(2), a.list_state.selected());
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(5, a.rows().len());
+    }
+
+    #[test]
+    fn test_tree_expands_to_new_conflicts() {
+        let mut a = app_with_changes(&["README.md"]);
+        a.cwd = PathBuf::from("conflicted_in_subdir");
+        a.config.group_by = GroupBy::Tree;
+        a.update_svn_status();
+        assert_eq!(
+            HashSet::from([PathBuf::from("src"), PathBuf::from("src/app")]),
+            a.expanded_dirs
+        );
+        // "docs (1)", "src (1)", "src/app (1)", "src/app/ui.rs", "README.md"
+        assert_eq!(5, a.rows().len());
+        assert_eq!(Some(3), a.list_state.selected());
+        assert_eq!(
+            Some(&StatusEntry::new(State::Conflicting, "src/app/ui.rs")),
+            a.change_at(3)
+        );
+    }
+
+    #[test]
+    fn test_group_as_tree() {
+        let mut a = app_with_changes(&["src/app/ui.rs", "README.md", "src/main.rs"]);
+        a.config.group_by = GroupBy::Directory;
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('v')))
+            .unwrap();
+        assert_eq!(GroupBy::Tree, a.config.group_by);
+        // "src (2)", "README.md"
+        assert_eq!(2, a.rows().len());
+        a.select_change(0);
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        // "src (2)", "src/app (1)", "src/main.rs", "README.md"
+        assert_eq!(4, a.rows().len());
+        a.select_change(1);
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(5, a.rows().len());
+        a.select_change(0);
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(2, a.rows().len());
+    }
+
+    #[test]
+    fn test_group_by_state() {
+        let mut a = app_with_changes(&[]);
+        a.file_list =
+            svn::FileList::from_svn_status("M       src/a.rs\nC       src/b.rs\n?       new.rs\n")
+                .unwrap();
+        a.config.group_by = GroupBy::Tree;
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('v')))
+            .unwrap();
+        assert_eq!(GroupBy::State, a.config.group_by);
+        // "Conflicts (1)", "src/b.rs", "Modified (1)", "src/a.rs", "Unversioned (1)", "new.rs"
+        assert_eq!(6, a.rows().len());
+        a.select_change(0);
+        a.handle_key_event(KeyEvent::from(KeyCode::Enter)).unwrap();
+        assert_eq!(5, a.rows().len());
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(text.contains("▸ Conflicts (1)"), "{text}");
+        assert!(text.contains("▾ Modified (1)"));
+        assert_eq!("Enter: expand", a.hints()[0]);
+    }
+
+    #[test]
+    fn test_count_badges() {
+        let mut a = app_with_changes(&[]);
+        a.file_list = svn::FileList::from_svn_status(
+            "M       a.rs\nC       b.rs\n?       new.rs\n?       scratch.txt\n",
+        )
+        .unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text = screen_text(buffer);
+        assert!(text.contains("Changes [M:1 ?:2 C:1]"), "{text}");
+        let row = text
+            .lines()
+            .position(|line| line.contains("Changes ["))
+            .unwrap();
+        let column = text
+            .lines()
+            .nth(row)
+            .unwrap()
+            .chars()
+            .position(|c| c == 'C');
+        let conflicts = column.unwrap() + "Changes [M:1 ?:2 C".len() - 1;
+        assert_eq!(Color::Red, buffer[(conflicts as u16, row as u16)].fg);
+    }
+
+    #[test]
+    fn test_last_updated_in_status_bar() {
+        let mut a = app_with_changes(&["a.rs"]);
+        a.last_updated = Utc::now() - TimeDelta::seconds(90);
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(
+            text.lines().last().unwrap().ends_with(" updated 1m ago"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn test_destructive_action_keys_ask_first() {
+        let mut a = app_with_changes(&["a.rs", "b.rs"]);
+        a.select_change(0);
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('u')))
+            .unwrap();
+        assert_eq!(AppState::Prompt, a.state);
+        let prompt = a.prompt.as_ref().unwrap();
+        assert_eq!(PromptKind::Confirm(Action::Revert, 1), prompt.kind);
+        assert_eq!("revert 1 change? (y/n) ", prompt.kind.label());
+
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('n')))
+            .unwrap();
+        assert_eq!(AppState::Main, a.state);
+        assert_eq!(None, a.prompt);
+        assert_eq!(Some(&"revert cancelled".to_string()), a.messages.last());
+        assert_eq!(2, a.file_list.list().len());
+    }
+
+    #[test]
+    fn test_focus_behaviour() {
+        let mut a = app_with_changes(&["a.rs", "b.rs"]);
+        a.config.clear_selection_on_blur = false;
+        a.config.refresh_on_focus = false;
+        a.select_change(1);
+        a.handle_event(Event::Crossterm(CtEvent::FocusLost))
+            .unwrap();
+        assert_eq!(Some(1), a.list_state.selected());
+        a.handle_event(Event::Crossterm(CtEvent::FocusGained))
+            .unwrap();
+        // a refresh would have found the working copy missing
+        assert_eq!(None, a.unavailable);
+
+        a.config.clear_selection_on_blur = true;
+        a.handle_event(Event::Crossterm(CtEvent::FocusLost))
+            .unwrap();
+        assert_eq!(None, a.list_state.selected());
+    }
+
+    #[test]
+    fn test_no_auto_refresh() {
+        let mut a = app_with_changes(&["a.rs"]);
+        a.config.incoming_check_interval = Duration::ZERO;
+        a.last_updated = Utc::now() - a.config.refresh_interval * 2;
+        let refresh_requested = |a: &mut App| {
+            a.tick();
+            std::iter::from_fn(|| a.events.try_next())
+                .any(|event| matches!(event, Event::App(AppEvent::UpdateRequest)))
+        };
+        assert!(refresh_requested(&mut a));
+        a.config.auto_refresh = false;
+        assert!(!refresh_requested(&mut a));
+    }
+
+    #[test]
+    fn test_refresh_flashes_in_status_bar() {
+        let mut a = app_with_changes(&["a.rs"]);
+        // as a refresh that found nothing new leaves it
+        a.refreshed_at = Some(Utc::now());
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(
+            text.lines().last().unwrap().ends_with(" refreshed"),
+            "{text}"
+        );
+
+        a.refreshed_at = Some(Utc::now() - REFRESH_FLASH_DURATION * 2);
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let text = screen_text(terminal.backend().buffer());
+        assert!(
+            text.lines().last().unwrap().ends_with(" updated 0s ago"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn test_toggle_high_contrast() {
+        let mut a = app_with_changes(&["a.rs"]);
+        a.config.theme = Theme::Protanopia;
+        // the working copy's path under the branch is grey
+        a.cwd = PathBuf::from("/work/repo");
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('Z')))
+            .unwrap();
+        assert_eq!(Theme::HighContrast, a.config.theme);
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content.iter().all(|cell| cell.fg != Color::DarkGray));
+
+        a.handle_key_event(KeyEvent::from(KeyCode::Char('Z')))
+            .unwrap();
+        assert_eq!(Theme::Protanopia, a.config.theme);
+        terminal.draw(|frame| a.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content.iter().any(|cell| cell.fg == Color::DarkGray));
     }
 
     #[rstest]
-    #[case(1, 2, Direction::Up, 1)]
-    #[case(1, 1, Direction::Up, 0)]
-    #[case(1, 0, Direction::Up, 0)]
-    #[case(1, 2, Direction::Down, 3)]
-    #[case(1, 1, Direction::Down, 2)]
-    #[case(1, 0, Direction::Down, 1)]
-    #[case(2, 2, Direction::Down, 3)]
-    #[case(2, 1, Direction::Down, 2)]
-    #[case(2, 0, Direction::Down, 1)]
-    fn test_handle_scroll(
-        #[case] start_pos: usize,
-        #[case] offset: usize,
-        #[case] dir: Direction,
-        #[case] exp_offset: usize,
-    ) {
-        let mut scroll_state = ScrollbarState::new(3).position(start_pos);
-        let mut offset = offset;
-        handle_scroll(&dir, &mut offset, &mut scroll_state);
-        assert_eq!(exp_offset, offset, "offset = {offset:?}");
+    #[case("notify-send 'now on {branch}'", "notify-send 'now on feature'")]
+    #[case("echo {previous} {branch} {branch}", "echo trunk feature feature")]
+    #[case("true", "true")]
+    fn test_expand_branch_hook(#[case] hook: &str, #[case] exp: &str) {
+        assert_eq!(exp, expand_branch_hook(hook, "feature", "trunk"));
+    }
+
+    #[test]
+    fn test_destructive_actions_check_the_selection_is_up_to_date() {
+        let mut a = app_with_changes(&["a.txt", "b.txt"]);
+        // the mocked status has a.txt deleted and b.txt unchanged by another process
+        a.cwd = PathBuf::from("changed_by_someone_else");
+        a.list_state.select(Some(0));
+        a.revert_change_file();
         assert_eq!(
-            ScrollbarState::new(3).position(offset),
-            scroll_state,
-            "state = {scroll_state:?}"
+            Some(&"a.txt changed since you selected it (now 'D'), nothing was done".to_string()),
+            a.messages.last()
+        );
+        assert_eq!(
+            vec![StatusEntry::new(State::Deleted, "a.txt")],
+            a.file_list.list()
+        );
+    }
+
+    #[test]
+    fn test_refresh_pauses_while_unavailable() {
+        let temp = TempDir::new("unavailable");
+        let dir = temp.path().join("wc");
+        let mut a = App::new();
+        a.cwd = dir.clone();
+        a.refresh();
+        assert_eq!(
+            Some("working copy unavailable, it was deleted or its drive was unmounted".into()),
+            a.unavailable
+        );
+        let messages = a.messages.len();
+        a.refresh();
+        assert_eq!(messages, a.messages.len(), "only reported once");
+
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(Ok(()), check_path_available(&dir));
+    }
+
+    #[test]
+    fn test_initial_status_queue() {
+        let temp = TempDir::new("initial-status-queue");
+        let root = temp.path().to_path_buf();
+        for dir in [".svn", "b", "a"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("file.txt"), "").unwrap();
+        let queue = initial_status_queue(&root);
+        assert_eq!(
+            vec![
+                (root.join("b"), "infinity"),
+                (root.join("a"), "infinity"),
+                (root.clone(), "files"),
+            ],
+            queue
         );
     }
 }
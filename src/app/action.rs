@@ -0,0 +1,333 @@
+use crate::{
+    app::App,
+    command::Precondition,
+    svn::{PropertyStatus, StatusEntry, state::State, version::ClientFeature},
+};
+/// Everything that can be done to a selection of changes. This is the single source of truth
+/// for which actions apply to which states, used by the change popup and the keybindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Open,
+    OpenDir,
+    Diff,
+    Delete,
+    Revert,
+    Commit,
+    Add,
+    Resolve,
+    Lock,
+    Ignore,
+    RenameChangelist,
+    CommitChangelist,
+    Pin,
+    Export,
+    CopyUrl,
+    History,
+    RestoreRevision,
+    Blame,
+    PropertyDiff,
+    CommitHunks,
+    ThreeWay,
+    AutoResolve,
+}
+
+impl Action {
+    /// All actions, in the order they are shown in the change popup
+    pub const ALL: [Action; 22] = [
+        Action::Open,
+        Action::OpenDir,
+        Action::Diff,
+        Action::Delete,
+        Action::Revert,
+        Action::Commit,
+        Action::Add,
+        Action::Resolve,
+        Action::Lock,
+        Action::Ignore,
+        Action::RenameChangelist,
+        Action::CommitChangelist,
+        Action::Pin,
+        Action::Export,
+        Action::CopyUrl,
+        Action::History,
+        Action::RestoreRevision,
+        Action::Blame,
+        Action::PropertyDiff,
+        Action::CommitHunks,
+        Action::ThreeWay,
+        Action::AutoResolve,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Open => "Open",
+            Action::OpenDir => "Open dir",
+            Action::Diff => "Diff",
+            Action::Delete => "Delete",
+            Action::Revert => "Revert",
+            Action::Commit => "Commit",
+            Action::Add => "Add",
+            Action::Resolve => "Resolve",
+            Action::Lock => "Lock",
+            Action::Ignore => "Ignore",
+            Action::RenameChangelist => "Rename changelist",
+            Action::CommitChangelist => "Commit changelist",
+            Action::Pin => "Pin/unpin",
+            Action::Export => "Export",
+            Action::CopyUrl => "Copy url",
+            Action::History => "History",
+            Action::RestoreRevision => "Restore revision",
+            Action::Blame => "Blame",
+            Action::PropertyDiff => "Property diff",
+            Action::CommitHunks => "Commit hunks",
+            Action::ThreeWay => "Three-way view",
+            Action::AutoResolve => "Auto-resolve",
+        }
+    }
+
+    /// The key that triggers the action when the changes list has a selection
+    pub fn key(&self) -> char {
+        match self {
+            Action::Open => 'o',
+            Action::OpenDir => 'O',
+            Action::Diff => 'd',
+            Action::Delete => 'x',
+            Action::Revert => 'u',
+            Action::Commit => 'c',
+            Action::Add => 'a',
+            Action::Resolve => 'm',
+            Action::Lock => 'l',
+            Action::Ignore => 'N',
+            Action::RenameChangelist => 'n',
+            Action::CommitChangelist => 'g',
+            Action::Pin => 'p',
+            Action::Export => 'E',
+            Action::CopyUrl => 'y',
+            Action::History => 'V',
+            Action::RestoreRevision => 'T',
+            Action::Blame => 'K',
+            Action::PropertyDiff => 'S',
+            Action::CommitHunks => 'k',
+            Action::ThreeWay => 't',
+            Action::AutoResolve => 'A',
+        }
+    }
+
+    pub fn from_key(key: char) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.key() == key)
+    }
+
+    /// Whether the action throws away local changes, so is only done from its key once
+    /// confirmed
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self, Action::Delete | Action::Revert | Action::AutoResolve)
+    }
+
+    /// Whether the action works on a whole changelist, so needs the selection to be in one
+    pub fn needs_changelist(&self) -> bool {
+        matches!(self, Action::RenameChangelist | Action::CommitChangelist)
+    }
+
+    /// What the action needs of the svn client that older clients don't have
+    pub fn required_feature(&self) -> Option<ClientFeature> {
+        match self {
+            Action::CommitHunks => Some(ClientFeature::Patch),
+            _ => None,
+        }
+    }
+
+    /// Whether the action runs svn to change the working copy, so has to wait for any other
+    /// svn command changing it to finish
+    pub fn writes_working_copy(&self) -> bool {
+        matches!(
+            self,
+            Action::Delete
+                | Action::Revert
+                | Action::Add
+                | Action::Resolve
+                | Action::Lock
+                | Action::Ignore
+                | Action::AutoResolve
+        )
+    }
+
+    /// Whether the action can be used in a working copy of a read-only backend, e.g. git,
+    /// whose changes are shown but not touched and whose history isn't read
+    pub fn allowed_read_only(&self) -> bool {
+        matches!(
+            self,
+            Action::Open | Action::OpenDir | Action::Diff | Action::Pin | Action::ThreeWay
+        )
+    }
+
+    pub fn applies_to(&self, state: &State) -> bool {
+        match self {
+            Action::Open | Action::OpenDir | Action::Pin => true,
+            Action::Diff => state.is_diffable(),
+            Action::RenameChangelist | Action::CommitChangelist => true,
+            Action::Delete => state.is_deletable(),
+            Action::Revert => state.is_revertable(),
+            Action::Commit => state.is_commitable(),
+            Action::Add => state.is_addable(),
+            Action::Resolve => state.is_resolvable(),
+            Action::Lock => state.is_lockable(),
+            Action::Ignore => state.is_ignorable(),
+            Action::Export => state.is_exportable(),
+            Action::CopyUrl | Action::History => state.is_in_repository(),
+            // blame reads the working file, so it has to be there
+            Action::Blame => {
+                matches!(state, State::Clean | State::Modified | State::Conflicting)
+            }
+            // a file that's scheduled for deletion, replaced or conflicted has more to undo
+            Action::RestoreRevision => {
+                matches!(state, State::Clean | State::Modified | State::Missing)
+            }
+            // only offered for the properties, see applies_to_properties
+            Action::PropertyDiff => false,
+            // only a modified file has hunks to pick between
+            Action::CommitHunks => *state == State::Modified,
+            Action::ThreeWay | Action::AutoResolve => *state == State::Conflicting,
+        }
+    }
+
+    /// Whether the action applies to a change because of its properties, e.g. committing a
+    /// directory whose state is clean but whose svn:ignore was edited
+    pub fn applies_to_properties(&self, status: PropertyStatus) -> bool {
+        match self {
+            Action::Diff | Action::Revert | Action::PropertyDiff => true,
+            Action::Commit => status == PropertyStatus::Modified,
+            Action::Resolve => status == PropertyStatus::Conflicted,
+            _ => false,
+        }
+    }
+
+    /// The [`App`] method that performs the action on the selected changes
+    pub fn handler(&self) -> fn(&mut App) {
+        match self {
+            Action::Open => App::open_change_file,
+            Action::OpenDir => App::open_change_dir,
+            Action::Diff => App::diff_change_file,
+            Action::Delete => App::delete_change_file,
+            Action::Revert => App::revert_change_file,
+            Action::Commit => App::commit_change_file,
+            Action::Add => App::add_change_file,
+            Action::Resolve => App::resolve_change_file,
+            Action::Lock => App::lock_change_file,
+            Action::Ignore => App::ignore_change_file,
+            Action::RenameChangelist => App::rename_changelist,
+            Action::CommitChangelist => App::commit_changelist,
+            Action::Pin => App::toggle_pinned,
+            Action::Export => App::export_change_file,
+            Action::CopyUrl => App::copy_change_url,
+            Action::History => App::open_history,
+            Action::RestoreRevision => App::open_restore_revision,
+            Action::Blame => App::open_blame,
+            Action::PropertyDiff => App::diff_change_properties,
+            Action::CommitHunks => App::pick_hunks_to_commit,
+            Action::ThreeWay => App::view_three_way,
+            Action::AutoResolve => App::auto_resolve_change_file,
+        }
+    }
+}
+
+/// An action that failed because something has to be done first, which 'f' does before
+/// performing the action again on the same paths
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingFix {
+    pub precondition: Precondition,
+    pub action: Action,
+    pub paths: Vec<String>,
+}
+
+/// Returns the actions that apply to every one of the selected changes. `in_changelist` is
+/// whether the selected changes all belong to the same changelist.
+pub fn available_actions(
+    selected: &[&StatusEntry],
+    in_changelist: bool,
+    read_only: bool,
+) -> Vec<Action> {
+    if selected.is_empty() {
+        return vec![];
+    }
+    Action::ALL
+        .into_iter()
+        .filter(|action| in_changelist || !action.needs_changelist())
+        .filter(|action| !read_only || action.allowed_read_only())
+        .filter(|action| {
+            selected.iter().all(
+                |StatusEntry {
+                     state, properties, ..
+                 }| {
+                    action.applies_to(state)
+                        || properties.is_some_and(|status| action.applies_to_properties(status))
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Action::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(vec![], false, vec![])]
+    #[case(vec![State::Modified], false, vec![Open, OpenDir, Diff, Delete, Revert, Commit, Lock, Pin, Export, CopyUrl, History, RestoreRevision, Blame, CommitHunks])]
+    #[case(vec![State::Unversioned], false, vec![Open, OpenDir, Add, Ignore, Pin])]
+    #[case(vec![State::Conflicting], false, vec![Open, OpenDir, Diff, Delete, Revert, Resolve, Pin, Export, CopyUrl, History, Blame, ThreeWay, AutoResolve])]
+    #[case(vec![State::Modified, State::Added], false, vec![Open, OpenDir, Diff, Revert, Commit, Pin, Export])]
+    #[case(vec![State::Modified, State::Unversioned], false, vec![Open, OpenDir, Pin])]
+    #[case(
+        vec![State::Modified, State::Unversioned],
+        true,
+        vec![Open, OpenDir, RenameChangelist, CommitChangelist, Pin]
+    )]
+    fn test_available_actions(
+        #[case] states: Vec<State>,
+        #[case] in_changelist: bool,
+        #[case] exp: Vec<Action>,
+    ) {
+        let changes: Vec<StatusEntry> = states
+            .into_iter()
+            .map(|state| StatusEntry::new(state, "file.txt"))
+            .collect();
+        let selected: Vec<&StatusEntry> = changes.iter().collect();
+        assert_eq!(exp, available_actions(&selected, in_changelist, false));
+    }
+
+    #[rstest]
+    #[case(State::Clean, PropertyStatus::Modified, vec![Open, OpenDir, Diff, Revert, Commit, Lock, Pin, Export, CopyUrl, History, RestoreRevision, Blame, PropertyDiff])]
+    #[case(State::Clean, PropertyStatus::Conflicted, vec![Open, OpenDir, Diff, Revert, Resolve, Lock, Pin, Export, CopyUrl, History, RestoreRevision, Blame, PropertyDiff])]
+    #[case(State::Added, PropertyStatus::Modified, vec![Open, OpenDir, Diff, Revert, Commit, Pin, Export, PropertyDiff])]
+    fn test_available_actions_with_properties(
+        #[case] state: State,
+        #[case] status: PropertyStatus,
+        #[case] exp: Vec<Action>,
+    ) {
+        let change = StatusEntry {
+            properties: Some(status),
+            ..StatusEntry::new(state, "dir")
+        };
+        assert_eq!(exp, available_actions(&[&change], false, false));
+    }
+
+    #[rstest]
+    #[case(State::Modified, vec![Open, OpenDir, Diff, Pin])]
+    #[case(State::Unversioned, vec![Open, OpenDir, Pin])]
+    #[case(State::Conflicting, vec![Open, OpenDir, Diff, Pin, ThreeWay])]
+    fn test_available_actions_read_only(#[case] state: State, #[case] exp: Vec<Action>) {
+        let change = StatusEntry::new(state, "file.txt");
+        assert_eq!(exp, available_actions(&[&change], false, true));
+    }
+
+    #[test]
+    fn test_keys_are_unique() {
+        for action in Action::ALL {
+            assert_eq!(Some(action), Action::from_key(action.key()));
+        }
+        // too easily hit instead of Ctrl+I, which inverts the selection
+        assert_eq!(None, Action::from_key('i'));
+    }
+}
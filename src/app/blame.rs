@@ -0,0 +1,84 @@
+use crate::svn::blame::BlameLine;
+
+/// Who last changed each line of a file, shown in place of the changes list
+#[derive(Debug, Default)]
+pub struct BlameScreen {
+    /// The file, relative to the working copy
+    pub path: String,
+    /// The lines, or why they couldn't be blamed. `None` while loading.
+    pub result: Option<Result<Vec<BlameLine>, String>>,
+    /// The first line shown
+    pub scroll: usize,
+}
+
+impl BlameScreen {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn lines(&self) -> &[BlameLine] {
+        match &self.result {
+            Some(Ok(lines)) => lines,
+            _ => &[],
+        }
+    }
+
+    pub fn scroll_by(&mut self, rows: isize) {
+        let last = self.lines().len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(rows).min(last);
+    }
+}
+
+/// How recent each line's revision is among the file's, from 0.0 for its oldest revision to 1.0
+/// for its newest. Lines that haven't been committed yet are the newest of all.
+pub fn line_ages(lines: &[BlameLine]) -> Vec<f64> {
+    let revisions = lines.iter().filter_map(|line| line.revision);
+    let (oldest, newest) = match (revisions.clone().min(), revisions.max()) {
+        (Some(oldest), Some(newest)) => (oldest, newest),
+        _ => return vec![1.0; lines.len()],
+    };
+    lines
+        .iter()
+        .map(|line| match line.revision {
+            None => 1.0,
+            Some(_) if oldest == newest => 1.0,
+            Some(revision) => (revision - oldest) as f64 / (newest - oldest) as f64,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(revision: Option<u32>) -> BlameLine {
+        BlameLine {
+            revision,
+            author: None,
+            text: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_line_ages() {
+        let lines = [line(Some(10)), line(Some(30)), line(None), line(Some(20))];
+        assert_eq!(vec![0.0, 1.0, 1.0, 0.5], line_ages(&lines));
+        assert_eq!(vec![1.0, 1.0], line_ages(&[line(Some(4)), line(Some(4))]));
+        assert_eq!(vec![1.0], line_ages(&[line(None)]));
+    }
+
+    #[test]
+    fn test_scroll_by() {
+        let mut screen = BlameScreen::new("a.txt");
+        screen.scroll_by(5);
+        assert_eq!(0, screen.scroll);
+        screen.result = Some(Ok(vec![line(Some(1)); 3]));
+        screen.scroll_by(5);
+        assert_eq!(2, screen.scroll);
+        screen.scroll_by(-1);
+        assert_eq!(1, screen.scroll);
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use ratatui::widgets::ListState;
+use std::{collections::BTreeMap, path::Path};
+
+/// How old a cached branch list can get before it's listed again in the background
+const MAX_CACHE_AGE: TimeDelta = TimeDelta::minutes(10);
+
+/// What picking a branch in the branch popup does
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BranchPurpose {
+    #[default]
+    Switch,
+    /// The picked branch is merged into the working copy
+    MergeFrom,
+    /// The revisions of the picked branch still to merge, and already merged, are shown
+    Mergeinfo,
+    /// The paths that differ between the picked branch and this one are listed
+    DiffSummary,
+}
+
+/// The popup listing the repository's branches to switch to or merge from
+#[derive(Debug, Default)]
+pub struct BranchSwitcher {
+    pub purpose: BranchPurpose,
+    /// The repository UUID the branches are cached under
+    pub uuid: String,
+    pub branches: Vec<String>,
+    pub list_state: ListState,
+    /// When the branches were listed, `None` if they never have been
+    pub fetched: Option<DateTime<Utc>>,
+    /// Whether the branches are being listed in the background
+    pub loading: bool,
+    pub error: Option<String>,
+}
+
+impl BranchSwitcher {
+    /// Opens with the cached branches of the repository, if there are any
+    pub fn new(purpose: BranchPurpose, uuid: String, cached: Option<CachedBranches>) -> Self {
+        let mut switcher = Self {
+            purpose,
+            uuid,
+            ..Self::default()
+        };
+        if let Some(cached) = cached {
+            switcher.fetched = DateTime::from_timestamp(cached.fetched, 0);
+            switcher.set_branches(cached.branches);
+        }
+        switcher
+    }
+
+    /// Whether the branches should be listed again, as they're missing or old
+    pub fn is_stale(&self) -> bool {
+        self.fetched
+            .is_none_or(|fetched| Utc::now() - fetched > MAX_CACHE_AGE)
+    }
+
+    /// Replaces the branches, keeping the same branch selected if it's still there
+    pub fn set_branches(&mut self, branches: Vec<String>) {
+        let selected = self
+            .list_state
+            .selected()
+            .and_then(|i| self.branches.get(i))
+            .and_then(|branch| branches.iter().position(|b| b == branch));
+        self.branches = branches;
+        self.list_state
+            .select(selected.or((!self.branches.is_empty()).then_some(0)));
+    }
+
+    pub fn selected(&self) -> Option<&String> {
+        self.branches.get(self.list_state.selected()?)
+    }
+
+    pub fn to_cache(&self) -> CachedBranches {
+        CachedBranches {
+            fetched: self.fetched.unwrap_or_else(Utc::now).timestamp(),
+            branches: self.branches.clone(),
+        }
+    }
+}
+
+/// A repository's branches as they were when last listed
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CachedBranches {
+    /// When they were listed, in seconds since the epoch
+    pub fetched: i64,
+    pub branches: Vec<String>,
+}
+
+type BranchCache = BTreeMap<String, CachedBranches>;
+
+fn read_cache(path: &Path) -> BranchCache {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn load_cached_branches(path: &Path, uuid: &str) -> Option<CachedBranches> {
+    read_cache(path).remove(uuid)
+}
+
+/// Caches the repository's branches, keeping the other repositories' branches
+pub fn save_cached_branches(path: &Path, uuid: &str, cached: CachedBranches) -> Result<(), String> {
+    let mut cache = read_cache(path);
+    cache.insert(uuid.to_string(), cached);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = toml::to_string(&cache).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = TempDir::new("branch-cache");
+        let path = dir.path().join("branches.toml");
+        let cached = CachedBranches {
+            fetched: 1_700_000_000,
+            branches: vec!["^/trunk".into(), "^/branches/feature".into()],
+        };
+        save_cached_branches(&path, "uuid-1", cached.clone()).unwrap();
+        save_cached_branches(&path, "uuid-2", cached.clone()).unwrap();
+        assert_eq!(Some(cached), load_cached_branches(&path, "uuid-1"));
+        assert_eq!(None, load_cached_branches(&path, "uuid-3"));
+    }
+
+    #[test]
+    fn test_set_branches_keeps_selection() {
+        let mut switcher = BranchSwitcher::new(BranchPurpose::Switch, "uuid".into(), None);
+        assert!(switcher.is_stale());
+        switcher.set_branches(vec!["^/trunk".into(), "^/branches/b".into()]);
+        assert_eq!(Some(&"^/trunk".to_string()), switcher.selected());
+        switcher.list_state.select(Some(1));
+        switcher.set_branches(vec![
+            "^/trunk".into(),
+            "^/branches/a".into(),
+            "^/branches/b".into(),
+        ]);
+        assert_eq!(Some(&"^/branches/b".to_string()), switcher.selected());
+        switcher.fetched = Some(Utc::now());
+        assert!(!switcher.is_stale());
+    }
+}
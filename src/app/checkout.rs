@@ -0,0 +1,86 @@
+use crate::svn::checkout::{Checkout, CheckoutProgress};
+use std::{path::PathBuf, time::Instant};
+
+/// The progress of a checkout, shown on the checkout screen
+#[derive(Debug)]
+pub struct CheckoutScreen {
+    pub url: String,
+    pub dest: PathBuf,
+    /// The running checkout, `None` once it has finished
+    pub handle: Option<Checkout>,
+    pub files: usize,
+    pub bytes: u64,
+    pub last_path: Option<PathBuf>,
+    pub started: Instant,
+    /// The checked out revision, or why the checkout failed
+    pub result: Option<Result<u32, String>>,
+}
+
+impl CheckoutScreen {
+    pub fn new(url: &str, dest: PathBuf) -> Self {
+        Self {
+            url: url.to_string(),
+            dest,
+            handle: None,
+            files: 0,
+            bytes: 0,
+            last_path: None,
+            started: Instant::now(),
+            result: None,
+        }
+    }
+
+    pub fn record(&mut self, progress: CheckoutProgress) {
+        match progress {
+            CheckoutProgress::Added { path, bytes } => {
+                self.files += 1;
+                self.bytes += bytes;
+                self.last_path = Some(path);
+            }
+            CheckoutProgress::Finished(result) => {
+                self.handle = None;
+                self.result = Some(result);
+            }
+        }
+    }
+
+    /// The average bytes checked out per second so far
+    pub fn rate(&self) -> u64 {
+        let secs = self.started.elapsed().as_secs_f64();
+        match secs > 0.0 {
+            true => (self.bytes as f64 / secs) as u64,
+            false => 0,
+        }
+    }
+}
+
+/// Formats a number of bytes in the largest unit that keeps it above 1, e.g. "1.5 MiB"
+pub fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if value < 1024.0 {
+            return match unit {
+                "B" => format!("{bytes} B"),
+                _ => format!("{value:.1} {unit}"),
+            };
+        }
+        value /= 1024.0;
+    }
+    format!("{value:.1} GiB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1536, "1.5 KiB")]
+    #[case(5 * 1024 * 1024, "5.0 MiB")]
+    #[case(3 * 1024 * 1024 * 1024, "3.0 GiB")]
+    fn test_format_bytes(#[case] bytes: u64, #[case] exp: &str) {
+        assert_eq!(exp, format_bytes(bytes));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::svn::StatusEntry;
+use ratatui::widgets::ListState;
+
+/// The changes to commit together and their message, reviewed before they're committed
+#[derive(Debug, Default)]
+pub struct CommitReview {
+    pub changes: Vec<StatusEntry>,
+    /// Empty until it's been typed into the commit message prompt
+    pub message: String,
+    /// Whether the changes' locks are kept after committing, rather than released
+    pub keep_locks: bool,
+    pub list_state: ListState,
+}
+
+impl CommitReview {
+    pub fn new(changes: Vec<StatusEntry>, keep_locks: bool) -> Self {
+        Self {
+            changes,
+            keep_locks,
+            list_state: ListState::default().with_selected(Some(0)),
+            ..Self::default()
+        }
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.changes
+            .iter()
+            .map(|StatusEntry { path, .. }| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Leaves the selected change out of the commit, keeping the selection in the list
+    pub fn remove_selected(&mut self) {
+        let Some(index) = self.list_state.selected() else {
+            return;
+        };
+        if index < self.changes.len() {
+            self.changes.remove(index);
+        }
+        let last = self.changes.len().checked_sub(1);
+        self.list_state.select(last.map(|last| index.min(last)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svn::state::State;
+
+    #[test]
+    fn test_remove_selected() {
+        let mut review = CommitReview::new(
+            vec![
+                StatusEntry::new(State::Modified, "a.txt"),
+                StatusEntry::new(State::Added, "b.txt"),
+            ],
+            false,
+        );
+        review.list_state.select(Some(1));
+        review.remove_selected();
+        assert_eq!(vec!["a.txt".to_string()], review.paths());
+        assert_eq!(Some(0), review.list_state.selected());
+        review.remove_selected();
+        assert!(review.changes.is_empty());
+        assert_eq!(None, review.list_state.selected());
+    }
+}
@@ -0,0 +1,129 @@
+use super::{App, output::Operation, worker::Job};
+use crate::{
+    ipc::{Control, ControlRequest, error_json, json_string},
+    svn::StatusEntry,
+};
+
+impl App {
+    /// Answers a request from the control socket with a line of JSON
+    pub(super) fn handle_control(&mut self, control: Control) {
+        let answer = match control.request {
+            ControlRequest::Status => self.status_json(),
+            ControlRequest::Refresh => {
+                self.refresh();
+                self.status_json()
+            }
+            ControlRequest::Commit { message } => self.commit_from_control(message),
+        };
+        // the client may have disconnected while waiting
+        let _ = control.reply.send(answer);
+    }
+
+    /// The branch, the working copy, the changes and the conflicted files, e.g.
+    /// {"ok":true,"branch":"trunk","working_copy":"/wc","changes":[{"state":"modified","path":"a.rs"}],"conflicts":[]}
+    fn status_json(&self) -> String {
+        if self.svn_missing {
+            return error_json("svn not found");
+        }
+        if let Some(reason) = &self.not_working_copy {
+            return error_json(reason);
+        }
+        let changes: Vec<String> = self
+            .file_list
+            .list()
+            .iter()
+            .map(|StatusEntry { state, path, .. }| {
+                format!(
+                    "{{\"state\":{},\"path\":{}}}",
+                    json_string(state.name()),
+                    json_string(&path.to_string_lossy())
+                )
+            })
+            .collect();
+        let conflicts: Vec<String> = self
+            .file_list
+            .conflicted_paths()
+            .into_iter()
+            .map(|path| json_string(&path.to_string_lossy()))
+            .collect();
+        format!(
+            "{{\"ok\":true,\"branch\":{},\"working_copy\":{},\"changes\":[{}],\"conflicts\":[{}]}}",
+            json_string(&self.current_branch),
+            json_string(&self.cwd.to_string_lossy()),
+            changes.join(","),
+            conflicts.join(",")
+        )
+    }
+
+    /// Commits the committable changes, leaving out the excluded changelists like selecting
+    /// all does. The commit runs like one started from the review, so the answer only says it
+    /// started.
+    fn commit_from_control(&mut self, message: String) -> String {
+        if self.file_list.has_conflicts() {
+            return error_json("resolve the conflicts first");
+        }
+        let paths: Vec<String> = self
+            .file_list
+            .list()
+            .iter()
+            .filter(|StatusEntry { state, path, .. }| {
+                state.is_commitable()
+                    && !self
+                        .file_list
+                        .in_changelist(path, &self.config.excluded_changelists)
+            })
+            .map(|StatusEntry { path, .. }| path.to_string_lossy().to_string())
+            .collect();
+        if paths.is_empty() {
+            return error_json("nothing to commit");
+        }
+        let count = paths.len();
+        self.run_job(Job::Operation(Operation::Commit {
+            paths,
+            message,
+            keep_locks: self.config.keep_locks,
+        }));
+        format!("{{\"ok\":true,\"committing\":{count}}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svn::FileList;
+    use std::{path::PathBuf, sync::mpsc};
+
+    fn ask(a: &mut App, request: ControlRequest) -> String {
+        let (reply, answer) = mpsc::channel();
+        a.handle_control(Control { request, reply });
+        answer.try_recv().unwrap()
+    }
+
+    #[test]
+    fn test_status_json() {
+        let mut a = App::new();
+        a.current_branch = "trunk".into();
+        a.cwd = PathBuf::from("/work/repo");
+        a.file_list =
+            FileList::from_svn_status("M       src/a \"b\".rs\nC       src/c.rs\n").unwrap();
+        assert_eq!(
+            "{\"ok\":true,\"branch\":\"trunk\",\"working_copy\":\"/work/repo\",\"changes\":[{\"state\":\"modified\",\"path\":\"src/a \\\"b\\\".rs\"},{\"state\":\"conflicted\",\"path\":\"src/c.rs\"}],\"conflicts\":[\"src/c.rs\"]}",
+            ask(&mut a, ControlRequest::Status)
+        );
+    }
+
+    #[test]
+    fn test_commit_from_control() {
+        let mut a = App::new();
+        a.file_list = FileList::from_svn_status("C       src/c.rs\n").unwrap();
+        let commit = ControlRequest::Commit {
+            message: "fix".into(),
+        };
+        assert_eq!(
+            error_json("resolve the conflicts first"),
+            ask(&mut a, commit.clone())
+        );
+        a.file_list = FileList::from_svn_status("?       new.rs\n").unwrap();
+        assert_eq!(error_json("nothing to commit"), ask(&mut a, commit));
+    }
+}
@@ -0,0 +1,28 @@
+use crate::svn::StatusEntry;
+use ratatui::widgets::ListState;
+
+/// The paths that differ from another branch, shown in place of the changes list
+#[derive(Debug, Default)]
+pub struct DiffSummaryScreen {
+    /// The url the working copy's branch is compared against
+    pub other: String,
+    /// The differing paths, or why they couldn't be listed. `None` while loading.
+    pub result: Option<Result<Vec<StatusEntry>, String>>,
+    pub list_state: ListState,
+}
+
+impl DiffSummaryScreen {
+    pub fn new(other: &str) -> Self {
+        Self {
+            other: other.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn paths(&self) -> &[StatusEntry] {
+        match &self.result {
+            Some(Ok(paths)) => paths,
+            _ => &[],
+        }
+    }
+}
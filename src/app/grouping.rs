@@ -0,0 +1,376 @@
+use crate::{
+    config::GroupBy,
+    svn::{StatusEntry, state::State},
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// A row of the changes list
+#[derive(Debug, PartialEq)]
+pub enum Row<'a> {
+    /// Above the pinned files, the favorites, which come before the rest
+    Favorites {
+        count: usize,
+    },
+    Header {
+        dir: PathBuf,
+        /// How many changes are in the group, including any collapsed ones
+        count: usize,
+        collapsed: bool,
+        /// How many directories it's nested in, always 0 unless grouped as a tree
+        depth: usize,
+    },
+    /// Above the changes in one state, when grouped by state
+    Section {
+        state: State,
+        count: usize,
+        collapsed: bool,
+    },
+    Change(&'a StatusEntry),
+}
+
+/// What a row is of, to find it again after a refresh has moved it
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowKey {
+    Favorites,
+    Header(PathBuf),
+    Section(State),
+    Change(PathBuf),
+}
+
+impl Row<'_> {
+    pub fn key(&self) -> RowKey {
+        match self {
+            Row::Favorites { .. } => RowKey::Favorites,
+            Row::Header { dir, .. } => RowKey::Header(dir.clone()),
+            Row::Section { state, .. } => RowKey::Section(*state),
+            Row::Change(StatusEntry { path, .. }) => RowKey::Change(path.clone()),
+        }
+    }
+}
+
+/// Lays the changes out in rows, with a header before each group. The pinned favorites come
+/// first in the order they were pinned, and the changes in collapsed directories are left out.
+/// Directories are open unless `collapsed`, except in a tree where they're closed unless
+/// `expanded`, and the sections of each state are open unless in `collapsed_states`.
+pub fn group_rows<'a>(
+    changes: Vec<&'a StatusEntry>,
+    group_by: GroupBy,
+    collapsed: &HashSet<PathBuf>,
+    expanded: &HashSet<PathBuf>,
+    collapsed_states: &HashSet<State>,
+    pinned: &[PathBuf],
+) -> Vec<Row<'a>> {
+    let (mut pins, rest): (Vec<_>, Vec<_>) = changes
+        .into_iter()
+        .partition(|StatusEntry { path, .. }| pinned.contains(path));
+    let mut rows = vec![];
+    if !pins.is_empty() {
+        pins.sort_by_key(|StatusEntry { path, .. }| pinned.iter().position(|pin| pin == path));
+        rows.push(Row::Favorites { count: pins.len() });
+        rows.extend(pins.into_iter().map(Row::Change));
+    }
+    rows.extend(group_unpinned(
+        rest,
+        group_by,
+        collapsed,
+        expanded,
+        collapsed_states,
+    ));
+    rows
+}
+
+fn group_unpinned<'a>(
+    changes: Vec<&'a StatusEntry>,
+    group_by: GroupBy,
+    collapsed: &HashSet<PathBuf>,
+    expanded: &HashSet<PathBuf>,
+    collapsed_states: &HashSet<State>,
+) -> Vec<Row<'a>> {
+    match group_by {
+        GroupBy::None => changes.into_iter().map(Row::Change).collect(),
+        GroupBy::Tree => tree_rows(changes, Path::new(""), 0, expanded),
+        GroupBy::State => state_rows(changes, collapsed_states),
+        GroupBy::Directory => {
+            let mut groups: BTreeMap<PathBuf, Vec<&StatusEntry>> = BTreeMap::new();
+            for change in changes {
+                groups
+                    .entry(parent_dir(&change.path))
+                    .or_default()
+                    .push(change);
+            }
+            let mut rows = vec![];
+            for (dir, changes) in groups {
+                let is_collapsed = collapsed.contains(&dir);
+                rows.push(Row::Header {
+                    dir,
+                    count: changes.len(),
+                    collapsed: is_collapsed,
+                    depth: 0,
+                });
+                if !is_collapsed {
+                    rows.extend(changes.into_iter().map(Row::Change));
+                }
+            }
+            rows
+        }
+    }
+}
+
+/// The rows for the changes inside the directory, its subdirectories first and then its own
+/// files. Only the expanded subdirectories are laid out, the collapsed ones are just counted,
+/// so a huge status costs no more than the part of the tree that's open.
+fn tree_rows<'a>(
+    changes: Vec<&'a StatusEntry>,
+    dir: &Path,
+    depth: usize,
+    expanded: &HashSet<PathBuf>,
+) -> Vec<Row<'a>> {
+    let mut files = vec![];
+    let mut subdirs: BTreeMap<PathBuf, Vec<&StatusEntry>> = BTreeMap::new();
+    for change in changes {
+        let mut components = change
+            .path
+            .strip_prefix(dir)
+            .unwrap_or(&change.path)
+            .components();
+        match (components.next(), components.next()) {
+            (Some(subdir), Some(_)) => subdirs.entry(dir.join(subdir)).or_default().push(change),
+            _ => files.push(change),
+        }
+    }
+    let mut rows = vec![];
+    for (subdir, changes) in subdirs {
+        let is_collapsed = !expanded.contains(&subdir);
+        rows.push(Row::Header {
+            dir: subdir.clone(),
+            count: changes.len(),
+            collapsed: is_collapsed,
+            depth,
+        });
+        if !is_collapsed {
+            rows.extend(tree_rows(changes, &subdir, depth + 1, expanded));
+        }
+    }
+    rows.extend(files.into_iter().map(Row::Change));
+    rows
+}
+
+/// The order the states' sections come in, the ones needing attention first
+const SECTION_ORDER: [State; 7] = [
+    State::Conflicting,
+    State::Missing,
+    State::Modified,
+    State::Replaced,
+    State::Added,
+    State::Deleted,
+    State::Unversioned,
+];
+
+/// A section for each state there are changes in, so a mixed status can be worked through a
+/// state at a time
+fn state_rows<'a>(changes: Vec<&'a StatusEntry>, collapsed: &HashSet<State>) -> Vec<Row<'a>> {
+    let mut rows = vec![];
+    for state in SECTION_ORDER {
+        let section: Vec<_> = changes.iter().filter(|c| c.state == state).collect();
+        if section.is_empty() {
+            continue;
+        }
+        let is_collapsed = collapsed.contains(&state);
+        rows.push(Row::Section {
+            state,
+            count: section.len(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            rows.extend(section.into_iter().map(|change| Row::Change(change)));
+        }
+    }
+    rows
+}
+
+/// The heading of the section of changes in the state
+pub fn section_title(state: State) -> &'static str {
+    match state {
+        State::Conflicting => "Conflicts",
+        State::Missing => "Missing",
+        State::Modified => "Modified",
+        State::Replaced => "Replaced",
+        State::Added => "Added",
+        State::Deleted => "Deleted",
+        State::Unversioned => "Unversioned",
+        State::Clean => "Unchanged",
+    }
+}
+
+/// The directory a change is grouped under, "." for the root of the working copy
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svn::state::State;
+
+    #[test]
+    fn test_group_rows_by_directory() {
+        let changes: Vec<StatusEntry> = ["src/b.rs", "README.md", "src/a.rs", "docs/x.md"]
+            .into_iter()
+            .map(|path| StatusEntry::new(State::Modified, path))
+            .collect();
+        let header = |dir: &str, count, collapsed| Row::Header {
+            dir: PathBuf::from(dir),
+            count,
+            collapsed,
+            depth: 0,
+        };
+        let collapsed = HashSet::from([PathBuf::from("docs")]);
+        assert_eq!(
+            vec![
+                header(".", 1, false),
+                Row::Change(&changes[1]),
+                header("docs", 1, true),
+                header("src", 2, false),
+                Row::Change(&changes[0]),
+                Row::Change(&changes[2]),
+            ],
+            group_rows(
+                changes.iter().collect(),
+                GroupBy::Directory,
+                &collapsed,
+                &HashSet::new(),
+                &HashSet::new(),
+                &[]
+            )
+        );
+        assert_eq!(
+            4,
+            group_rows(
+                changes.iter().collect(),
+                GroupBy::None,
+                &collapsed,
+                &HashSet::new(),
+                &HashSet::new(),
+                &[]
+            )
+            .len()
+        );
+    }
+
+    #[test]
+    fn test_group_rows_as_tree() {
+        let changes: Vec<StatusEntry> = ["src/app/ui.rs", "README.md", "src/main.rs", "docs/x.md"]
+            .into_iter()
+            .map(|path| StatusEntry::new(State::Modified, path))
+            .collect();
+        let header = |dir: &str, count, collapsed, depth| Row::Header {
+            dir: PathBuf::from(dir),
+            count,
+            collapsed,
+            depth,
+        };
+        let rows = |expanded: &[&str]| {
+            let expanded = expanded.iter().map(PathBuf::from).collect();
+            group_rows(
+                changes.iter().collect(),
+                GroupBy::Tree,
+                &HashSet::new(),
+                &expanded,
+                &HashSet::new(),
+                &[],
+            )
+        };
+        assert_eq!(
+            vec![
+                header("docs", 1, true, 0),
+                header("src", 2, true, 0),
+                Row::Change(&changes[1]),
+            ],
+            rows(&[])
+        );
+        // a subdirectory stays closed until it's expanded itself
+        assert_eq!(
+            vec![
+                header("docs", 1, true, 0),
+                header("src", 2, false, 0),
+                header("src/app", 1, true, 1),
+                Row::Change(&changes[2]),
+                Row::Change(&changes[1]),
+            ],
+            rows(&["src"])
+        );
+        assert_eq!(7, rows(&["src", "src/app", "docs"]).len());
+        // expanding a directory inside a collapsed one shows nothing more
+        assert_eq!(3, rows(&["src/app"]).len());
+    }
+
+    #[test]
+    fn test_group_rows_by_state() {
+        let changes: Vec<StatusEntry> = [
+            (State::Unversioned, "new.rs"),
+            (State::Modified, "a.rs"),
+            (State::Conflicting, "b.rs"),
+            (State::Modified, "c.rs"),
+        ]
+        .into_iter()
+        .map(|(state, path)| StatusEntry::new(state, path))
+        .collect();
+        let section = |state, count, collapsed| Row::Section {
+            state,
+            count,
+            collapsed,
+        };
+        assert_eq!(
+            vec![
+                section(State::Conflicting, 1, false),
+                Row::Change(&changes[2]),
+                section(State::Modified, 2, true),
+                section(State::Unversioned, 1, false),
+                Row::Change(&changes[0]),
+            ],
+            group_rows(
+                changes.iter().collect(),
+                GroupBy::State,
+                &HashSet::new(),
+                &HashSet::new(),
+                &HashSet::from([State::Modified]),
+                &[]
+            )
+        );
+    }
+
+    #[test]
+    fn test_group_rows_pinned_first() {
+        let changes: Vec<StatusEntry> = ["a.txt", "b.txt", "c.txt"]
+            .into_iter()
+            .map(|path| StatusEntry::new(State::Modified, path))
+            .collect();
+        let pinned = [
+            PathBuf::from("c.txt"),
+            PathBuf::from("gone.txt"),
+            PathBuf::from("a.txt"),
+        ];
+        assert_eq!(
+            vec![
+                Row::Favorites { count: 2 },
+                Row::Change(&changes[2]),
+                Row::Change(&changes[0]),
+                Row::Change(&changes[1]),
+            ],
+            group_rows(
+                changes.iter().collect(),
+                GroupBy::None,
+                &HashSet::new(),
+                &HashSet::new(),
+                &HashSet::new(),
+                &pinned
+            )
+        );
+    }
+}
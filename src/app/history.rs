@@ -0,0 +1,67 @@
+use crate::svn::log::LogEntry;
+use ratatui::widgets::ListState;
+
+/// What picking a revision in the history does
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum HistoryPurpose {
+    /// Shows what the revision changed in the file
+    #[default]
+    Browse,
+    /// Replaces the file's contents with its contents at the revision
+    Restore,
+}
+
+/// The revisions that changed a file, shown in place of the changes list
+#[derive(Debug, Default)]
+pub struct HistoryScreen {
+    /// The file, relative to the working copy
+    pub path: String,
+    pub purpose: HistoryPurpose,
+    /// The revisions, newest first, or why they couldn't be read. `None` while loading.
+    pub result: Option<Result<Vec<LogEntry>, String>>,
+    pub list_state: ListState,
+}
+
+impl HistoryScreen {
+    pub fn new(path: &str, purpose: HistoryPurpose) -> Self {
+        Self {
+            path: path.to_string(),
+            purpose,
+            ..Self::default()
+        }
+    }
+
+    pub fn entries(&self) -> &[LogEntry] {
+        match &self.result {
+            Some(Ok(entries)) => entries,
+            _ => &[],
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<&LogEntry> {
+        self.entries().get(self.list_state.selected()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_entry() {
+        let entry = |revision| LogEntry {
+            revision,
+            author: "alice".into(),
+            date: None,
+            message: String::new(),
+            paths: vec![],
+        };
+        let mut screen = HistoryScreen::new("a.txt", HistoryPurpose::Browse);
+        screen.list_state.select(Some(1));
+        assert_eq!(None, screen.selected_entry());
+        screen.result = Some(Ok(vec![entry(12), entry(10)]));
+        assert_eq!(Some(10), screen.selected_entry().map(|e| e.revision));
+        screen.result = Some(Err("E170013".into()));
+        assert_eq!(None, screen.selected_entry());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::svn::hunks::FileDiff;
+use ratatui::widgets::ListState;
+
+/// The hunks of a file's changes, picked to commit some without the rest
+#[derive(Debug)]
+pub struct HunkScreen {
+    pub path: String,
+    pub diff: FileDiff,
+    /// Whether each hunk is picked, all of them to start with
+    pub picked: Vec<bool>,
+    pub list_state: ListState,
+}
+
+impl HunkScreen {
+    pub fn new(path: &str, diff: FileDiff) -> Self {
+        Self {
+            path: path.to_string(),
+            picked: vec![true; diff.hunks.len()],
+            diff,
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn picked_count(&self) -> usize {
+        self.picked.iter().filter(|picked| **picked).count()
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some(picked) = self
+            .list_state
+            .selected()
+            .and_then(|index| self.picked.get_mut(index))
+        {
+            *picked = !*picked;
+        }
+    }
+
+    /// Picks every hunk, or none if they're all picked already
+    pub fn toggle_all(&mut self) {
+        let pick = self.picked_count() < self.picked.len();
+        self.picked.fill(pick);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggles() {
+        let diff = FileDiff::parse("@@ -1 +1 @@\n-a\n+b\n@@ -5 +5 @@\n-c\n+d\n").unwrap();
+        let mut screen = HunkScreen::new("a.txt", diff);
+        assert_eq!(2, screen.picked_count());
+        screen.toggle_selected();
+        assert_eq!(vec![false, true], screen.picked);
+        screen.toggle_all();
+        assert_eq!(vec![true, true], screen.picked);
+        screen.toggle_all();
+        assert_eq!(0, screen.picked_count());
+    }
+}
@@ -0,0 +1,104 @@
+use super::{App, time_for_update};
+use crate::{
+    event::AppEvent,
+    svn::incoming::{Incoming, IncomingChange, get_incoming, get_revisions_behind},
+};
+use chrono::{DateTime, Utc};
+
+/// What an update would pull in, shown in a pane below the changes while toggled on
+#[derive(Debug, Default)]
+pub struct IncomingPane {
+    /// The out of date paths, or why the repository couldn't be asked. `None` while asking.
+    pub result: Option<Result<Incoming, String>>,
+}
+
+impl IncomingPane {
+    pub fn changes(&self) -> &[IncomingChange] {
+        match &self.result {
+            Some(Ok(incoming)) => &incoming.changes,
+            _ => &[],
+        }
+    }
+}
+
+/// How far the repository has moved on from the working copy, asked on its own slower timer so
+/// that the status refresh never waits on the repository
+#[derive(Debug, Default)]
+pub struct BehindCheck {
+    /// The revisions an update would bring in, `None` until the repository has answered
+    pub revisions: Option<usize>,
+    /// `None` until the first check, which is made on the first tick
+    last_checked: Option<DateTime<Utc>>,
+    checking: bool,
+}
+
+impl App {
+    /// Shows the incoming changes pane, asking the repository what's changed, or hides it
+    pub(super) fn toggle_incoming(&mut self) {
+        if self.incoming.take().is_some() {
+            return;
+        }
+        if self.backend.read_only() {
+            let name = self.backend.name();
+            self.push_message(format!("no incoming changes for a {name} working copy"));
+            return;
+        }
+        self.incoming = Some(IncomingPane::default());
+        self.fetch_incoming();
+    }
+
+    /// Asks the repository again in the background, keeping the old list until it answers
+    pub(super) fn fetch_incoming(&mut self) {
+        let path = self.cwd.to_string_lossy().to_string();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = get_incoming(&path, &policy).map_err(|e| e.to_string());
+            send(AppEvent::Incoming(result));
+        });
+    }
+
+    /// Asks the repository how many revisions the working copy is behind when the check is
+    /// due, unless one is already running
+    pub(super) fn check_behind_if_due(&mut self) {
+        let interval = self.config.incoming_check_interval;
+        if interval.is_zero()
+            || self.behind.checking
+            || self.svn_missing
+            || self.not_working_copy.is_some()
+            || self.backend.read_only()
+        {
+            return;
+        }
+        if let Some(last) = self.behind.last_checked
+            && !time_for_update(last, interval)
+        {
+            return;
+        }
+        self.behind.checking = true;
+        let path = self.cwd.to_string_lossy().to_string();
+        let policy = self.config.retry_policy();
+        let send = self.events.app_sender();
+        std::thread::spawn(move || {
+            let result = get_revisions_behind(&path, &policy).map_err(|e| e.to_string());
+            send(AppEvent::Behind(result));
+        });
+    }
+
+    pub(super) fn handle_behind(&mut self, result: Result<usize, String>) {
+        self.behind.checking = false;
+        self.behind.last_checked = Some(Utc::now());
+        // the repository being unreachable is already said by the refresh if it matters, so a
+        // failed check only leaves the last answer in place
+        if let Ok(revisions) = result {
+            self.behind.revisions = Some(revisions);
+        }
+    }
+
+    pub(super) fn handle_incoming(&mut self, result: Result<Incoming, String>) {
+        // the pane may have been hidden while the repository was being asked
+        if let Some(pane) = self.incoming.as_mut() {
+            pane.result = Some(result);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{command::CommandProgress, svn::parse_updated_revision};
+
+/// The long-running svn command whose output is shown
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Merge {
+        source: String,
+        /// The revisions as typed, empty for every eligible revision
+        revisions: String,
+    },
+    Update {
+        revision: String,
+    },
+    Commit {
+        paths: Vec<String>,
+        message: String,
+        keep_locks: bool,
+    },
+    /// A command bound to a key in the config, run in the shell
+    Custom {
+        command: String,
+    },
+}
+
+impl Operation {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Operation::Merge { .. } => "Merge",
+            Operation::Update { .. } => "Update",
+            Operation::Commit { .. } => "Commit",
+            Operation::Custom { .. } => "Command",
+        }
+    }
+
+    /// What's being done, for the status line while it runs
+    pub fn describe(&self) -> String {
+        match self {
+            Operation::Merge { source, revisions } if revisions.is_empty() => {
+                format!("Merging every eligible revision of {source}")
+            }
+            Operation::Merge { source, revisions } => {
+                format!("Merging revisions {revisions} of {source}")
+            }
+            Operation::Update { revision } => format!("Updating to revision {revision}"),
+            Operation::Commit { paths, .. } if paths.len() == 1 => {
+                format!("Committing {}", paths[0])
+            }
+            Operation::Commit { paths, .. } => format!("Committing {} changes", paths.len()),
+            Operation::Custom { command } => format!("Running {command}"),
+        }
+    }
+}
+
+/// What an update did to the working copy, shown in a popup once it finishes
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UpdateSummary {
+    pub updated: usize,
+    pub added: usize,
+    pub deleted: usize,
+    /// Changed in the repository and locally, and merged without conflicts
+    pub merged: usize,
+    pub conflicted: usize,
+    /// The revision the working copy was left at
+    pub revision: Option<u32>,
+}
+
+impl UpdateSummary {
+    /// Whether the update left the working copy as it was
+    pub fn is_empty(&self) -> bool {
+        self.updated + self.added + self.deleted + self.merged + self.conflicted == 0
+    }
+}
+
+/// The output of a command as it runs, shown on the output screen
+#[derive(Debug)]
+pub struct OutputScreen {
+    pub operation: Operation,
+    pub output: Vec<String>,
+    /// Why the command failed if it did, `None` while it's running
+    pub result: Option<Result<(), String>>,
+    /// The first line in view, `None` to keep following the latest lines
+    pub scroll: Option<usize>,
+}
+
+impl OutputScreen {
+    pub fn new(operation: Operation) -> Self {
+        Self {
+            operation,
+            output: vec![],
+            result: None,
+            scroll: None,
+        }
+    }
+
+    pub fn record(&mut self, progress: CommandProgress) {
+        match progress {
+            CommandProgress::Output(line) => self.output.push(line),
+            CommandProgress::Finished(result) => self.result = Some(result),
+        }
+    }
+
+    /// How many paths were left conflicted, going by svn's "C    path" output lines
+    pub fn conflicts(&self) -> usize {
+        self.output
+            .iter()
+            .filter(|line| line.get(..4).is_some_and(|status| status.contains('C')))
+            .count()
+    }
+
+    /// Counts the paths by what the update did to them, going by the four columns svn writes
+    /// before each path: the contents, the properties, a broken lock and a tree conflict
+    pub fn update_summary(&self) -> UpdateSummary {
+        let mut summary = UpdateSummary {
+            revision: parse_updated_revision(&self.output.join("\n")),
+            ..Default::default()
+        };
+        for columns in self.output.iter().filter_map(|line| update_columns(line)) {
+            let (contents, properties) = (columns[0], columns[1]);
+            let count = if columns.contains(&'C') {
+                &mut summary.conflicted
+            } else if contents == 'A' {
+                &mut summary.added
+            } else if contents == 'D' {
+                &mut summary.deleted
+            } else if contents == 'G' || properties == 'G' {
+                &mut summary.merged
+            } else {
+                &mut summary.updated
+            };
+            *count += 1;
+        }
+        summary
+    }
+
+    /// The first line to show in `height` rows
+    pub fn first_visible(&self, height: usize) -> usize {
+        let last = self.output.len().saturating_sub(height);
+        self.scroll.map_or(last, |scroll| scroll.min(last))
+    }
+
+    /// Scrolls by `delta` lines, following the output again once the end is back in view
+    pub fn scroll_by(&mut self, delta: isize, height: usize) {
+        let last = self.output.len().saturating_sub(height);
+        let scroll = self.first_visible(height).saturating_add_signed(delta);
+        self.scroll = (scroll < last).then_some(scroll);
+    }
+}
+
+/// The status columns of a line of 'svn update' output like "U    src/a.rs", `None` for the
+/// lines around them like "Updating '.':" and "Summary of conflicts:"
+fn update_columns(line: &str) -> Option<[char; 4]> {
+    let mut chars = line.chars();
+    let columns = [chars.next()?, chars.next()?, chars.next()?, chars.next()?];
+    let valid = matches!(columns[0], 'A' | 'D' | 'U' | 'C' | 'G' | 'E' | 'R' | ' ')
+        && matches!(columns[1], 'U' | 'C' | 'G' | ' ')
+        && matches!(columns[2], 'B' | ' ')
+        && matches!(columns[3], 'C' | ' ')
+        && columns != [' '; 4]
+        && chars.next() == Some(' ');
+    valid.then_some(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge() -> Operation {
+        Operation::Merge {
+            source: "^/branches/feature".into(),
+            revisions: "".into(),
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut screen = OutputScreen::new(merge());
+        for line in [
+            "--- Merging r10 through r12 into '.':",
+            "U    src/a.rs",
+            "C    src/b.rs",
+            " C   src",
+            "Summary of conflicts:",
+        ] {
+            screen.record(CommandProgress::Output(line.into()));
+        }
+        assert_eq!(None, screen.result);
+        screen.record(CommandProgress::Finished(Ok(())));
+        assert_eq!(Some(Ok(())), screen.result);
+        assert_eq!(5, screen.output.len());
+        assert_eq!(2, screen.conflicts());
+    }
+
+    #[test]
+    fn test_update_summary() {
+        let mut screen = OutputScreen::new(Operation::Update {
+            revision: "HEAD".into(),
+        });
+        screen.output = [
+            "Updating '.':",
+            "U    src/a.rs",
+            " U   src/b.rs",
+            "A    src/new.rs",
+            "D    src/old.rs",
+            "G    src/c.rs",
+            "C    src/d.rs",
+            "   C src/moved.rs",
+            "Updated to revision 45.",
+            "Summary of conflicts:",
+            "  Text conflicts: 1",
+            "  Tree conflicts: 1",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            UpdateSummary {
+                updated: 2,
+                added: 1,
+                deleted: 1,
+                merged: 1,
+                conflicted: 2,
+                revision: Some(45),
+            },
+            screen.update_summary()
+        );
+    }
+
+    #[test]
+    fn test_scroll_by() {
+        let mut screen = OutputScreen::new(merge());
+        screen.output = (0..10).map(|i| i.to_string()).collect();
+        assert_eq!(6, screen.first_visible(4));
+        screen.scroll_by(-3, 4);
+        assert_eq!(Some(3), screen.scroll);
+        // new output doesn't move what's in view while scrolled back
+        screen.output.push("10".into());
+        assert_eq!(3, screen.first_visible(4));
+        screen.scroll_by(10, 4);
+        assert_eq!(None, screen.scroll);
+        assert_eq!(7, screen.first_visible(4));
+        screen.scroll_by(-100, 4);
+        assert_eq!(Some(0), screen.scroll);
+    }
+}
@@ -0,0 +1,208 @@
+use super::action::Action;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::{Path, PathBuf};
+
+/// A line of text being typed into the message box, e.g. a new changelist name
+#[derive(Debug, PartialEq)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
+/// What the typed text is for, and so what happens when it is submitted
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    /// The new name for the changelist
+    RenameChangelist(String),
+    /// A directory to read at full depth despite the configured status depth
+    DrillIn,
+    /// The directory to list the changes of instead of the whole working copy
+    Scope,
+    /// Where to write the status report, or nothing to copy it to the clipboard
+    ExportReport,
+    /// The directory of the working copy to show instead
+    ChangeDirectory,
+    /// A file to pin to the favorites, whether or not it has changes
+    PinFavorite,
+    /// The revisions of the source url to merge into the working copy
+    MergeRevisions(String),
+    /// Where to export the path to, "." for the whole working copy
+    Export(String),
+    /// The revision to update the working copy to
+    UpdateToRevision,
+    /// The commit message for the hunks picked on the hunks screen
+    CommitHunks,
+    /// The commit message for the number of changes, which are reviewed before committing
+    CommitMessage(usize),
+    /// Whether to do the action to the number of selected changes, answered with y or n
+    Confirm(Action, usize),
+}
+
+impl PromptKind {
+    pub fn label(&self) -> String {
+        match self {
+            PromptKind::RenameChangelist(name) => format!("rename changelist '{name}' to: "),
+            PromptKind::ExportReport => {
+                "export report to a .md, .html, .csv or .json file, or nothing for the clipboard: "
+                    .into()
+            }
+            PromptKind::ChangeDirectory => "cd (tab completes): ".into(),
+            PromptKind::PinFavorite => "pin a file to the favorites (tab completes): ".into(),
+            PromptKind::DrillIn => "read at full depth (tab completes): ".into(),
+            PromptKind::Scope => "only list the changes in (tab completes): ".into(),
+            PromptKind::Export(path) if path == "." => {
+                "export the working copy to (tab completes): ".into()
+            }
+            PromptKind::Export(path) => format!("export {path} to (tab completes): "),
+            PromptKind::UpdateToRevision => "update to revision (nothing for HEAD): ".into(),
+            PromptKind::CommitHunks => "commit message for the picked hunks: ".into(),
+            PromptKind::CommitMessage(1) => "commit message for 1 change: ".into(),
+            PromptKind::CommitMessage(count) => format!("commit message for {count} changes: "),
+            PromptKind::Confirm(action, 1) => {
+                format!("{} 1 change? (y/n) ", action.label().to_lowercase())
+            }
+            PromptKind::Confirm(action, count) => {
+                format!("{} {count} changes? (y/n) ", action.label().to_lowercase())
+            }
+            PromptKind::MergeRevisions(source) => {
+                format!(
+                    "revisions of {source} to merge, e.g. 100:120 or 105,107, or nothing for all eligible: "
+                )
+            }
+        }
+    }
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind, input: &str) -> Self {
+        Self {
+            kind,
+            input: input.to_string(),
+        }
+    }
+
+    /// Applies an editing key to the input, returning whether the key was used
+    pub fn edit(&mut self, key_event: KeyEvent) -> bool {
+        match key_event.code {
+            KeyCode::Char('u') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.input.clear()
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(c)
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Tab
+                if matches!(
+                    self.kind,
+                    PromptKind::ChangeDirectory
+                        | PromptKind::DrillIn
+                        | PromptKind::Scope
+                        | PromptKind::Export(_)
+                ) =>
+            {
+                if let Some(completed) = complete_path(&self.input, true) {
+                    self.input = completed;
+                }
+            }
+            KeyCode::Tab if self.kind == PromptKind::PinFavorite => {
+                if let Some(completed) = complete_path(&self.input, false) {
+                    self.input = completed;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Completes the last component of the path to the directories (or files too, unless
+/// `dirs_only`) it could be, as far as they all agree. A single directory match gets a
+/// trailing slash so the next component can be typed.
+fn complete_path(input: &str, dirs_only: bool) -> Option<String> {
+    let (parent, prefix) = match input.rfind(['/', std::path::MAIN_SEPARATOR]) {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let dir = match parent {
+        "" => Path::new("."),
+        parent => Path::new(parent),
+    };
+    let mut names: Vec<(String, bool)> = std::fs::read_dir(expand_home(dir))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| (entry.file_name(), entry.path().is_dir()))
+        .filter(|(_, is_dir)| *is_dir || !dirs_only)
+        .filter_map(|(name, is_dir)| Some((name.into_string().ok()?, is_dir)))
+        .filter(|(name, _)| {
+            name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .collect();
+    names.sort();
+    let completed = match names.as_slice() {
+        [] => return None,
+        [(name, true)] => format!("{name}/"),
+        [(name, false)] => name.clone(),
+        [(first, _), rest @ ..] => rest.iter().fold(first.clone(), |common, (name, _)| {
+            common
+                .chars()
+                .zip(name.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect()
+        }),
+    };
+    Some(format!("{parent}{completed}"))
+}
+
+/// Replaces a leading `~` with the home directory
+pub fn expand_home(path: &Path) -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_edit() {
+        let mut prompt = Prompt::new(PromptKind::RenameChangelist("old".into()), "old");
+        for c in "er".chars() {
+            assert!(prompt.edit(KeyEvent::from(KeyCode::Char(c))));
+        }
+        assert!(prompt.edit(KeyEvent::from(KeyCode::Backspace)));
+        assert_eq!("olde", prompt.input);
+        assert!(prompt.edit(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL)));
+        assert_eq!("", prompt.input);
+        assert!(!prompt.edit(KeyEvent::from(KeyCode::Enter)));
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let temp = TempDir::new("complete-directory");
+        let root = temp.path();
+        for dir in ["trunk", "tags", "branches/feature", ".svn"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("tree.txt"), "").unwrap();
+        let root_str = format!("{}/", root.display());
+        let complete = |input: &str| complete_path(&format!("{root_str}{input}"), true);
+        let completed = |path: &str| Some(format!("{root_str}{path}"));
+        assert_eq!(completed("t"), complete("t"));
+        assert_eq!(completed("trunk/"), complete("tr"));
+        assert_eq!(completed("branches/feature/"), complete("branches/f"));
+        assert_eq!(completed(""), complete(""));
+        assert_eq!(completed(".svn/"), complete(".s"));
+        assert_eq!(None, complete("x"));
+        assert_eq!(None, complete("tre"));
+        let complete_file = |input: &str| complete_path(&format!("{root_str}{input}"), false);
+        assert_eq!(completed("tree.txt"), complete_file("tre"));
+        assert_eq!(completed("tr"), complete_file("tr"));
+    }
+}
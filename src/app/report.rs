@@ -0,0 +1,265 @@
+use crate::{
+    ipc::json_string,
+    svn::{Conflict, StatusEntry},
+};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+/// The kinds of status report that can be exported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    /// HTML, CSV or JSON for paths ending in .html or .htm, .csv or .json, otherwise Markdown
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                ReportFormat::Html
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ReportFormat::Csv,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ReportFormat::Json,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+/// The path with "{timestamp}" replaced by the time, e.g. "status-{timestamp}.json" becomes
+/// "status-20250304-050607.json", so repeated exports don't overwrite each other
+pub fn timestamped_path(path: &str, now: DateTime<Utc>) -> PathBuf {
+    PathBuf::from(path.replace("{timestamp}", &now.format("%Y%m%d-%H%M%S").to_string()))
+}
+
+/// What goes in a status report
+pub struct Report<'a> {
+    pub branch: &'a str,
+    pub working_copy: &'a Path,
+    pub generated: String,
+    pub changes: Vec<&'a StatusEntry>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Report<'_> {
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.markdown(),
+            ReportFormat::Html => self.html(),
+            ReportFormat::Csv => self.csv(),
+            ReportFormat::Json => self.json(),
+        }
+    }
+
+    /// A row per change, each with the branch and time so snapshots can be concatenated
+    fn csv(&self) -> String {
+        let mut text = "generated,branch,working_copy,state,path,conflicted\n".to_string();
+        let conflicted: Vec<&Path> = self.conflicts.iter().map(|c| c.file()).collect();
+        for StatusEntry { state, path, .. } in &self.changes {
+            let fields = [
+                self.generated.clone(),
+                self.branch.to_string(),
+                self.working_copy.display().to_string(),
+                state.name().to_string(),
+                path.display().to_string(),
+                conflicted.contains(&path.as_path()).to_string(),
+            ];
+            let fields: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+            text.push_str(&fields.join(","));
+            text.push('\n');
+        }
+        text
+    }
+
+    fn json(&self) -> String {
+        let changes: Vec<String> = self
+            .changes
+            .iter()
+            .map(|StatusEntry { state, path, .. }| {
+                format!(
+                    "{{\"state\":{},\"path\":{}}}",
+                    json_string(state.name()),
+                    json_string(&path.display().to_string())
+                )
+            })
+            .collect();
+        let conflicts: Vec<String> = self
+            .conflicts
+            .iter()
+            .map(|conflict| json_string(&conflict.file().display().to_string()))
+            .collect();
+        format!(
+            "{{\"generated\":{},\"branch\":{},\"working_copy\":{},\"changes\":[{}],\"conflicts\":[{}]}}\n",
+            json_string(&self.generated),
+            json_string(self.branch),
+            json_string(&self.working_copy.display().to_string()),
+            changes.join(","),
+            conflicts.join(",")
+        )
+    }
+
+    fn markdown(&self) -> String {
+        let mut text = format!(
+            "# Status of {}\n\nWorking copy: `{}`, generated {}\n\n## Changes ({})\n\n",
+            self.branch,
+            self.working_copy.display(),
+            self.generated,
+            self.changes.len()
+        );
+        for StatusEntry { state, path, .. } in &self.changes {
+            text.push_str(&format!("- {}: `{}`\n", state.name(), path.display()));
+        }
+        if !self.conflicts.is_empty() {
+            text.push_str(&format!("\n## Conflicts ({})\n\n", self.conflicts.len()));
+            for conflict in &self.conflicts {
+                text.push_str(&format!("- `{}`\n", conflict.file().display()));
+            }
+        }
+        text
+    }
+
+    fn html(&self) -> String {
+        let mut text = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Status of {branch}</title></head>\n<body>\n<h1>Status of {branch}</h1>\n<p>Working copy: <code>{}</code>, generated {}</p>\n<h2>Changes ({})</h2>\n<ul>\n",
+            escape_html(&self.working_copy.display().to_string()),
+            escape_html(&self.generated),
+            self.changes.len(),
+            branch = escape_html(self.branch),
+        );
+        for StatusEntry { state, path, .. } in &self.changes {
+            text.push_str(&format!(
+                "<li>{}: <code>{}</code></li>\n",
+                state.name(),
+                escape_html(&path.display().to_string())
+            ));
+        }
+        text.push_str("</ul>\n");
+        if !self.conflicts.is_empty() {
+            text.push_str(&format!(
+                "<h2>Conflicts ({})</h2>\n<ul>\n",
+                self.conflicts.len()
+            ));
+            for conflict in &self.conflicts {
+                text.push_str(&format!(
+                    "<li><code>{}</code></li>\n",
+                    escape_html(&conflict.file().display().to_string())
+                ));
+            }
+            text.push_str("</ul>\n");
+        }
+        text.push_str("</body>\n</html>\n");
+        text
+    }
+}
+
+/// Quotes the field if it has a comma, quote or line break in it
+fn escape_csv(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svn::state::State;
+    use rstest::*;
+    use std::path::PathBuf;
+
+    fn report<'a>(changes: &'a [StatusEntry]) -> Report<'a> {
+        Report {
+            branch: "trunk",
+            working_copy: Path::new("/wc"),
+            generated: "2025-03-04 05:06".into(),
+            changes: changes.iter().collect(),
+            conflicts: vec![Conflict::Text {
+                file: "b<c>.txt".into(),
+                left: None,
+                right: None,
+                working: None,
+            }],
+        }
+    }
+
+    #[rstest]
+    #[case("report.html", ReportFormat::Html)]
+    #[case("report.HTM", ReportFormat::Html)]
+    #[case("status.csv", ReportFormat::Csv)]
+    #[case("status.JSON", ReportFormat::Json)]
+    #[case("report.md", ReportFormat::Markdown)]
+    #[case("report", ReportFormat::Markdown)]
+    fn test_format_from_path(#[case] path: &str, #[case] exp: ReportFormat) {
+        assert_eq!(exp, ReportFormat::from_path(Path::new(path)));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let changes = [
+            StatusEntry::new(State::Modified, "a.txt"),
+            StatusEntry::new(State::Conflicting, "b<c>.txt"),
+        ];
+        assert_eq!(
+            "# Status of trunk\n\nWorking copy: `/wc`, generated 2025-03-04 05:06\n\n\
+             ## Changes (2)\n\n- modified: `a.txt`\n- conflicted: `b<c>.txt`\n\n\
+             ## Conflicts (1)\n\n- `b<c>.txt`\n",
+            report(&changes).render(ReportFormat::Markdown)
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let changes = [
+            StatusEntry::new(State::Modified, "a, \"b\".txt"),
+            StatusEntry::new(State::Conflicting, "b<c>.txt"),
+        ];
+        assert_eq!(
+            "generated,branch,working_copy,state,path,conflicted\n\
+             2025-03-04 05:06,trunk,/wc,modified,\"a, \"\"b\"\".txt\",false\n\
+             2025-03-04 05:06,trunk,/wc,conflicted,b<c>.txt,true\n",
+            report(&changes).render(ReportFormat::Csv)
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let changes = [StatusEntry::new(State::Modified, "a.txt")];
+        assert_eq!(
+            "{\"generated\":\"2025-03-04 05:06\",\"branch\":\"trunk\",\"working_copy\":\"/wc\",\
+             \"changes\":[{\"state\":\"modified\",\"path\":\"a.txt\"}],\"conflicts\":[\"b<c>.txt\"]}\n",
+            report(&changes).render(ReportFormat::Json)
+        );
+    }
+
+    #[test]
+    fn test_timestamped_path() {
+        let now = DateTime::parse_from_rfc3339("2025-03-04T05:06:07Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            PathBuf::from("logs/status-20250304-050607.json"),
+            timestamped_path("logs/status-{timestamp}.json", now)
+        );
+        assert_eq!(
+            PathBuf::from("status.csv"),
+            timestamped_path("status.csv", now)
+        );
+    }
+
+    #[test]
+    fn test_render_html_escapes_paths() {
+        let changes = [StatusEntry::new(State::Conflicting, "b<c>.txt")];
+        let html = report(&changes).render(ReportFormat::Html);
+        assert!(html.contains("<li>conflicted: <code>b&lt;c&gt;.txt</code></li>"));
+        assert!(!html.contains("b<c>"));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::svn::log::{BranchHistory, LogEntry};
+use ratatui::widgets::ListState;
+
+/// The revision graph screen, which replaces the changes list while it's open
+#[derive(Debug, Default)]
+pub struct RevisionGraphScreen {
+    /// The branches, newest first, or why they couldn't be listed. `None` while loading.
+    pub result: Option<Result<Vec<BranchHistory>, String>>,
+    pub list_state: ListState,
+}
+
+/// A row of the revision graph, indented by its branch's lane
+#[derive(Debug, PartialEq)]
+pub enum GraphRow<'a> {
+    Branch(&'a str),
+    Revision(&'a LogEntry),
+    /// Where the branch above was copied from, joining it to the lane of the branch below
+    Copy {
+        from: &'a str,
+        revision: u32,
+    },
+}
+
+/// Lays the branches out one after another, each a lane further in than the one copied from
+/// it, so the history reads down from the newest revision of the current branch
+pub fn graph_rows(branches: &[BranchHistory]) -> Vec<(usize, GraphRow<'_>)> {
+    let mut rows = vec![];
+    for (lane, branch) in branches.iter().enumerate() {
+        rows.push((lane, GraphRow::Branch(&branch.path)));
+        rows.extend(
+            branch
+                .entries
+                .iter()
+                .map(|entry| (lane, GraphRow::Revision(entry))),
+        );
+        if let Some((from, revision)) = &branch.copied_from {
+            rows.push((
+                lane,
+                GraphRow::Copy {
+                    from,
+                    revision: *revision,
+                },
+            ));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_rows() {
+        let entry = |revision| LogEntry {
+            revision,
+            author: "alice".into(),
+            date: None,
+            message: String::new(),
+            paths: vec![],
+        };
+        let branches = vec![
+            BranchHistory {
+                path: "/branches/feature".into(),
+                entries: vec![entry(12), entry(10)],
+                copied_from: Some(("/trunk".into(), 9)),
+            },
+            BranchHistory {
+                path: "/trunk".into(),
+                entries: vec![entry(9)],
+                copied_from: None,
+            },
+        ];
+        assert_eq!(
+            vec![
+                (0, GraphRow::Branch("/branches/feature")),
+                (0, GraphRow::Revision(&branches[0].entries[0])),
+                (0, GraphRow::Revision(&branches[0].entries[1])),
+                (
+                    0,
+                    GraphRow::Copy {
+                        from: "/trunk",
+                        revision: 9
+                    }
+                ),
+                (1, GraphRow::Branch("/trunk")),
+                (1, GraphRow::Revision(&branches[1].entries[0])),
+            ],
+            graph_rows(&branches)
+        );
+    }
+}
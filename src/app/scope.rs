@@ -0,0 +1,154 @@
+use super::{App, PromptKind};
+use crate::{
+    config::StatusDepth,
+    svn::{self, FileList},
+};
+use std::path::{Component, Path, PathBuf};
+
+impl App {
+    /// The status of the scoped directory, or of the whole working copy, at the configured
+    /// depth with each drilled into directory read in full
+    pub(super) fn read_status(&self) -> svn::Result<FileList> {
+        let depth = self.config.status_depth;
+        let root = match &self.scope {
+            Some(dir) => self.cwd.join(dir),
+            None => self.cwd.clone(),
+        };
+        let drilled = self.scope.as_ref().is_some_and(|scope| {
+            self.drilled_dirs
+                .iter()
+                .any(|drilled| scope.starts_with(drilled))
+        });
+        if depth == StatusDepth::Infinity || drilled {
+            return match self.scope {
+                Some(_) => self
+                    .backend
+                    .status_with_depth(&root, StatusDepth::Infinity.label()),
+                None => self.backend.status(&self.cwd),
+            };
+        }
+        let mut status = self.backend.status_with_depth(&root, depth.label())?;
+        let inside_scope = |dir: &&PathBuf| {
+            self.scope
+                .as_ref()
+                .is_none_or(|scope| dir.starts_with(scope))
+        };
+        for dir in self.drilled_dirs.iter().filter(inside_scope) {
+            let subtree = self
+                .backend
+                .status_with_depth(&self.cwd.join(dir), StatusDepth::Infinity.label())?;
+            // the shallow status may already have the directory and what's directly inside it
+            status.patch_changes(std::slice::from_ref(dir), subtree);
+        }
+        Ok(status)
+    }
+
+    /// Asks which directory to read in full, starting with the selected directory or the one
+    /// the selected change is in
+    pub(super) fn drill_in_prompt(&mut self) {
+        if self.config.status_depth == StatusDepth::Infinity {
+            self.push_message(
+                "the whole working copy is already read, status_depth is infinity".into(),
+            );
+            return;
+        }
+        let dir = self.selected_dir_input();
+        self.open_prompt(PromptKind::DrillIn, &dir);
+    }
+
+    /// Reads the directory at full depth from now on, as well as the shallow status
+    pub(super) fn drill_in(&mut self, input: &str) {
+        let Some(dir) = self.dir_from_input(input) else {
+            self.push_message(format!("{input}: no such directory"));
+            return;
+        };
+        if self
+            .drilled_dirs
+            .iter()
+            .any(|drilled| dir.starts_with(drilled))
+        {
+            self.push_message(format!("{} is already read in full", dir.display()));
+            return;
+        }
+        // a directory drilled into before that's inside this one is read with it
+        self.drilled_dirs
+            .retain(|drilled| !drilled.starts_with(&dir));
+        self.push_message(format!("reading {} in full", dir.display()));
+        self.drilled_dirs.push(dir);
+        self.update_svn_status();
+    }
+
+    /// Asks which directory to list the changes of, starting with the selected directory or
+    /// the one the selected change is in
+    pub(super) fn scope_prompt(&mut self) {
+        if !self.backend.supports_depth() {
+            let name = self.backend.name();
+            self.push_message(format!(
+                "a {name} working copy can't be scoped to a directory"
+            ));
+            return;
+        }
+        let dir = self.selected_dir_input();
+        self.open_prompt(PromptKind::Scope, &dir);
+    }
+
+    /// Lists only the changes in the directory until the scope is left with Backspace
+    pub(super) fn scope_to(&mut self, input: &str) {
+        let Some(dir) = self.dir_from_input(input) else {
+            self.push_message(format!("{input}: no such directory"));
+            return;
+        };
+        if dir.as_os_str().is_empty() {
+            return self.leave_scope();
+        }
+        self.scope = Some(dir);
+        self.list_state.select(None);
+        self.update_svn_status();
+    }
+
+    /// Lists the changes in the whole working copy again
+    pub(super) fn leave_scope(&mut self) {
+        if self.scope.take().is_some() {
+            self.list_state.select(None);
+            self.update_svn_status();
+        }
+    }
+
+    /// The scoped directory's components, from the working copy down, e.g. ["src", "app"]
+    pub(super) fn scope_breadcrumbs(&self) -> Vec<String> {
+        self.scope
+            .iter()
+            .flat_map(|dir| dir.components())
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// The selected directory, or the one the selected change is in, to start a prompt for a
+    /// directory with. Empty at the top of the working copy.
+    fn selected_dir_input(&self) -> String {
+        let selected = self
+            .get_selected_changes()
+            .and_then(|changes| changes.first().map(|change| change.path.clone()));
+        let dir = match selected {
+            Some(path) if self.cwd.join(&path).is_dir() => Some(path),
+            Some(path) => path.parent().map(Path::to_path_buf),
+            None => None,
+        };
+        dir.filter(|dir| !dir.as_os_str().is_empty())
+            .map(|dir| format!("{}/", dir.display()))
+            .unwrap_or_default()
+    }
+
+    /// The directory typed into a prompt relative to the working copy, like the changes'
+    /// paths, if it exists
+    fn dir_from_input(&self, input: &str) -> Option<PathBuf> {
+        let dir = Path::new(input);
+        let dir: PathBuf = dir
+            .strip_prefix(&self.cwd)
+            .unwrap_or(dir)
+            .components()
+            .filter(|component| *component != Component::CurDir)
+            .collect();
+        (!input.is_empty() && self.cwd.join(&dir).is_dir()).then_some(dir)
+    }
+}
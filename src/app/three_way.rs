@@ -0,0 +1,138 @@
+use crate::svn::three_way::MergeBlock;
+use std::path::PathBuf;
+
+/// A line across the merge-left, working and merge-right columns, empty where a side has
+/// fewer lines in a conflict region than the others
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreeWayRow {
+    pub cells: [Option<String>; 3],
+    pub conflict: bool,
+}
+
+/// The three sides of a text conflict side by side, shown in place of the changes list
+#[derive(Debug)]
+pub struct ThreeWayScreen {
+    pub file: PathBuf,
+    /// The names of the merge-left, working and merge-right columns
+    pub titles: [String; 3],
+    pub rows: Vec<ThreeWayRow>,
+    /// The first row shown, the same in every column so they scroll together
+    pub scroll: usize,
+}
+
+impl ThreeWayScreen {
+    pub fn new(file: PathBuf, titles: [String; 3], blocks: &[MergeBlock]) -> Self {
+        let mut screen = Self {
+            file,
+            titles,
+            rows: three_way_rows(blocks),
+            scroll: 0,
+        };
+        // it opens on the first conflict
+        screen.scroll = screen.conflict_starts().first().copied().unwrap_or(0);
+        screen
+    }
+
+    /// The rows each conflict region starts at
+    pub fn conflict_starts(&self) -> Vec<usize> {
+        (0..self.rows.len())
+            .filter(|&i| self.rows[i].conflict && (i == 0 || !self.rows[i - 1].conflict))
+            .collect()
+    }
+
+    pub fn scroll_by(&mut self, rows: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(rows).min(last);
+    }
+
+    pub fn next_conflict(&mut self) {
+        if let Some(next) = self
+            .conflict_starts()
+            .into_iter()
+            .find(|&start| start > self.scroll)
+        {
+            self.scroll = next;
+        }
+    }
+
+    pub fn prev_conflict(&mut self) {
+        if let Some(prev) = self
+            .conflict_starts()
+            .into_iter()
+            .rfind(|&start| start < self.scroll)
+        {
+            self.scroll = prev;
+        }
+    }
+}
+
+/// Lines the sides up, each common line across all three and each conflict region as tall as
+/// its longest side
+pub fn three_way_rows(blocks: &[MergeBlock]) -> Vec<ThreeWayRow> {
+    let mut rows = vec![];
+    for block in blocks {
+        match block {
+            MergeBlock::Common(line) => rows.push(ThreeWayRow {
+                cells: [Some(line.clone()), Some(line.clone()), Some(line.clone())],
+                conflict: false,
+            }),
+            MergeBlock::Conflict { mine, base, theirs } => {
+                let base = base.as_deref().unwrap_or_default();
+                let height = mine.len().max(base.len()).max(theirs.len()).max(1);
+                rows.extend((0..height).map(|i| ThreeWayRow {
+                    cells: [
+                        base.get(i).cloned(),
+                        mine.get(i).cloned(),
+                        theirs.get(i).cloned(),
+                    ],
+                    conflict: true,
+                }));
+            }
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svn::three_way::parse_conflict_markers;
+
+    #[test]
+    fn test_rows_and_conflict_jumps() {
+        let text = "\
+a
+<<<<<<< .mine
+b mine
+||||||| .r1
+b
+=======
+b theirs
+c theirs
+>>>>>>> .r2
+d
+<<<<<<< .mine
+=======
+>>>>>>> .r2
+";
+        let titles = ["base".to_string(), "mine".into(), "theirs".into()];
+        let mut screen = ThreeWayScreen::new("a.txt".into(), titles, &parse_conflict_markers(text));
+        assert_eq!(
+            ThreeWayRow {
+                cells: [None, None, Some("c theirs".into())],
+                conflict: true,
+            },
+            screen.rows[2]
+        );
+        assert_eq!(vec![1, 4], screen.conflict_starts());
+        assert_eq!(1, screen.scroll);
+        screen.next_conflict();
+        assert_eq!(4, screen.scroll);
+        screen.next_conflict();
+        assert_eq!(4, screen.scroll);
+        screen.prev_conflict();
+        assert_eq!(1, screen.scroll);
+        screen.scroll_by(-5);
+        assert_eq!(0, screen.scroll);
+    }
+}
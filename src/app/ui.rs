@@ -1,44 +1,340 @@
+use chrono::Utc;
 use ratatui::{
     Frame,
+    buffer::Buffer,
     layout::{Constraint, Layout, Margin, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span, Text},
-    widgets::{Block, Clear, List, Paragraph, Scrollbar, ScrollbarOrientation},
+    widgets::{Block, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation},
+};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
 };
-use std::ffi::OsStr;
 
 use crate::{
-    app::{App, AppState},
-    svn::{self, ParsedStatusLine, state::State},
+    app::{
+        App, AppSection, AppState,
+        action::Action,
+        blame::line_ages,
+        branches::BranchPurpose,
+        checkout::format_bytes,
+        grouping::{Row, section_title},
+        history::HistoryPurpose,
+        is_current_branch,
+        revision_graph::{GraphRow, graph_rows},
+    },
+    config::{self, GroupBy, PaneLayout, Theme},
+    svn::{Conflict, LockStatus, PropertyStatus, StatusEntry, state::State},
 };
 
 const MINIMUM_UI_WIDTH: u16 = 15;
 
+/// The narrowest the changes list and conflicts pane can be when shown side by side
+const MINIMUM_PANE_WIDTH: u16 = 20;
+
+/// The shortest the changes list and conflicts pane can be when stacked, for terminals too
+/// narrow to show them side by side
+const MINIMUM_STACKED_PANE_HEIGHT: u16 = 5;
+
+/// Below this width the changes list drops its spacing and shortens its labels
+const COMPACT_LIST_WIDTH: u16 = 30;
+
+/// The smallest terminal the branch box is shown in, otherwise the branch goes in the title
+/// of the changes list to leave more room for the changes
+const BRANCH_BOX_MIN_WIDTH: u16 = 24;
+const BRANCH_BOX_MIN_HEIGHT: u16 = 16;
+
 impl App {
     pub fn draw(&mut self, frame: &mut Frame) {
+        self.draw_screen(frame);
+        if self.config.theme == Theme::HighContrast {
+            drop_dim_styling(frame.buffer_mut());
+        }
+    }
+
+    fn draw_screen(&mut self, frame: &mut Frame) {
+        if self.config.minimal {
+            frame.render_widget(self.minimal_line(), frame.area());
+            return;
+        }
         if frame.area().width < MINIMUM_UI_WIDTH {
             // guard against the ui being too narrow
             frame.render_widget(Span::raw("too small"), frame.area());
             return;
         }
         let should_render_change_popup = self.state == AppState::ChangePopup;
-        let constraints = vec![
-            Constraint::Length(4),
-            Constraint::Fill(1),
-            Constraint::Length(1),
-        ];
+        let branch_box = shows_branch_box(frame.area());
+        let mut constraints = vec![Constraint::Fill(1), Constraint::Length(1)];
+        if self.unavailable.is_some() {
+            constraints.insert(0, Constraint::Length(1));
+        }
+        if branch_box {
+            // the borders, the branch, the last commit if it's been read and the working copy
+            let height = 4 + self.last_commit.is_some() as u16;
+            constraints.insert(0, Constraint::Length(height));
+        }
         let layout = Layout::vertical(constraints).split(frame.area());
         let mut i = 0;
-        self.render_branch_box(frame, layout[i]);
-        i += 1;
-        self.render_file_list(frame, layout[i]);
+        if branch_box {
+            self.render_branch_box(frame, layout[i]);
+            i += 1;
+        }
+        if let Some(reason) = &self.unavailable {
+            let banner = Line::raw(format!(" {reason}, waiting for it to come back"))
+                .style(Style::new().fg(Color::White).bg(Color::Red));
+            frame.render_widget(banner, layout[i]);
+            i += 1;
+        }
+        // the incoming changes go below the changes and conflicts, when there's room
+        let (lists_area, incoming_area) = match &self.incoming {
+            Some(pane) => split_incoming_pane(layout[i], pane.changes().len()),
+            None => (layout[i], None),
+        };
+        let conflicts_pane = match self.file_list.has_conflicts() {
+            true => split_conflicts_pane(
+                lists_area,
+                self.config.pane_layout,
+                self.config.conflicts_pane_percent,
+            ),
+            false => None,
+        };
+        match (&self.state, conflicts_pane) {
+            (AppState::Main, _) if self.svn_missing => render_svn_missing(frame, layout[i]),
+            (AppState::Main, _) if let Some(reason) = &self.not_working_copy => {
+                render_not_working_copy(frame, layout[i], &self.cwd, reason)
+            }
+            (AppState::Workspaces, _) => self.render_workspaces(frame, layout[i]),
+            (AppState::Checkout, _) => self.render_checkout(frame, layout[i]),
+            (AppState::RevisionGraph, _) => self.render_revision_graph(frame, layout[i]),
+            (AppState::History, _) => self.render_history(frame, layout[i]),
+            (AppState::Blame, _) => self.render_blame(frame, layout[i]),
+            (AppState::Output, _) => self.render_output(frame, layout[i]),
+            (AppState::DiffSummary, _) => self.render_diff_summary(frame, layout[i]),
+            (AppState::Hunks, _) => self.render_hunks(frame, layout[i]),
+            (AppState::ThreeWay, _) => self.render_three_way(frame, layout[i]),
+            (_, Some((changes, conflicts))) => {
+                self.render_file_list(frame, changes);
+                self.render_conflicts(frame, conflicts);
+                self.render_incoming(frame, incoming_area);
+            }
+            _ => {
+                self.render_file_list(frame, lists_area);
+                self.conflicts_area = None;
+                self.render_incoming(frame, incoming_area);
+            }
+        }
         i += 1;
         if should_render_change_popup {
             self.render_change_popup(frame);
         }
+        if self.state == AppState::Bookmarks {
+            self.render_bookmarks(frame, layout[i - 1]);
+        }
+        if self.state == AppState::Branches {
+            self.render_branches(frame, layout[i - 1]);
+        }
+        if self.state == AppState::CommitReview {
+            self.render_commit_review(frame, layout[i - 1]);
+        }
+        if self.state == AppState::UpdateSummary {
+            self.render_update_summary(frame, layout[i - 1]);
+        }
         self.render_message_box(frame, layout[i]);
     }
 
+    /// The bookmarked working copies, numbered for jumping to, centred over the area
+    fn render_bookmarks(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<Line> = self
+            .config
+            .bookmarks
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let number = match i {
+                    0..9 => format!("{} ", i + 1),
+                    _ => "  ".into(),
+                };
+                let line = Line::from(vec![
+                    Span::raw(number).fg(Color::DarkGray),
+                    Span::raw(path.to_string_lossy()),
+                ]);
+                match *path == self.cwd {
+                    true => line.fg(Color::Cyan),
+                    false => line,
+                }
+            })
+            .collect();
+        let width = items.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+        let popup_area = area.clamp(Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + 1,
+            width,
+            height: items.len() as u16 + 2,
+        });
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(Color::from_u32(0x00333355)))
+            .block(Block::bordered().title("Bookmarks"));
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut self.bookmarks_list_state);
+    }
+
+    /// The repository's branches, with when they were listed, centred over the area
+    fn render_branches(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(switcher) = self.branch_switcher.as_mut() else {
+            return;
+        };
+        let mut items: Vec<Line> = switcher
+            .branches
+            .iter()
+            .map(|url| match is_current_branch(url, &self.current_branch) {
+                true => Line::raw(url.as_str()).fg(Color::Cyan),
+                false => Line::raw(url.as_str()),
+            })
+            .collect();
+        if let Some(error) = &switcher.error {
+            items.push(Line::raw(error.as_str()).fg(Color::Red));
+        } else if items.is_empty() {
+            items.push(Line::raw("no branches listed yet").fg(Color::DarkGray));
+        }
+        let (name, footer) = match switcher.purpose {
+            BranchPurpose::Switch => ("Branches", " r: refresh  Enter: switch "),
+            BranchPurpose::MergeFrom => ("Merge from", " r: refresh  Enter: pick revisions "),
+            BranchPurpose::Mergeinfo => ("Mergeinfo from", " r: refresh  Enter: show revisions "),
+            BranchPurpose::DiffSummary => ("Compare with", " r: refresh  Enter: list differences "),
+        };
+        let title = match (switcher.loading, switcher.fetched) {
+            (true, _) => format!("{name} (listing...)"),
+            (false, Some(at)) => format!("{name} (listed {})", self.config.format_timestamp(at)),
+            (false, None) => name.to_string(),
+        };
+        let footer = Line::raw(footer).fg(Color::DarkGray);
+        let width = items
+            .iter()
+            .map(Line::width)
+            .chain([title.len(), footer.width()])
+            .max()
+            .unwrap_or(0) as u16
+            + 4;
+        let popup_area = area.clamp(Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + 1,
+            width,
+            height: items.len() as u16 + 2,
+        });
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(Color::from_u32(0x00333355)))
+            .block(Block::bordered().title(title).title_bottom(footer));
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut switcher.list_state);
+    }
+
+    /// What the last update did, centred over the area
+    fn render_update_summary(&self, frame: &mut Frame, area: Rect) {
+        let Some(summary) = self.update_summary else {
+            return;
+        };
+        let mut lines: Vec<Line> = [
+            (summary.updated, "updated"),
+            (summary.added, "added"),
+            (summary.deleted, "deleted"),
+            (summary.merged, "merged with local changes"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, what)| Line::raw(format!("{count} {what}")))
+        .collect();
+        if summary.conflicted > 0 {
+            lines.push(
+                Line::raw(format!(
+                    "{} conflicted, Enter to resolve",
+                    summary.conflicted
+                ))
+                .fg(Color::LightMagenta),
+            );
+        }
+        if summary.is_empty() {
+            lines.push(Line::raw("already up to date").fg(Color::DarkGray));
+        }
+        let title = match summary.revision {
+            Some(rev) => format!("Updated to r{rev}"),
+            None => "Updated".to_string(),
+        };
+        let footer = Line::raw(" Enter: close  J: output ").fg(Color::DarkGray);
+        let width = lines
+            .iter()
+            .map(Line::width)
+            .chain([title.len(), footer.width()])
+            .max()
+            .unwrap_or(0) as u16
+            + 4;
+        let popup_area = area.clamp(Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + 1,
+            width,
+            height: lines.len() as u16 + 2,
+        });
+        let block = Block::bordered().title(title).title_bottom(footer);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+    }
+
+    /// The message and the changes about to be committed, to check before confirming
+    fn render_commit_review(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(review) = self.commit_review.as_mut() else {
+            return;
+        };
+        let message: Vec<Line> = review
+            .message
+            .lines()
+            .map(|line| Line::raw(line).add_modifier(Modifier::ITALIC))
+            .collect();
+        let mut title = match review.changes.len() {
+            1 => "Commit 1 change".to_string(),
+            count => format!("Commit {count} changes"),
+        };
+        if review.keep_locks {
+            title.push_str(", keeping locks");
+        }
+        let footer =
+            Line::raw(" y: commit  e: edit message  d: leave out  k: keep locks  esc: cancel ")
+                .fg(Color::DarkGray);
+        let popup_area = area.inner(Margin::new(2, 1));
+        let popup_area = Rect {
+            height: popup_area
+                .height
+                .min((message.len() + review.changes.len()) as u16 + 3),
+            ..popup_area
+        };
+        let block = Block::bordered().title(title).title_bottom(footer);
+        let inner = block.inner(popup_area);
+        let [message_area, separator_area, changes_area] = Layout::vertical([
+            Constraint::Length(message.len() as u16),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        let list = List::new(review.changes.iter().map(|psl| {
+            create_file_list_item(
+                psl,
+                u16::MAX, // the full paths, to tell apart files with the same name
+                false,
+                self.config.nerd_font_icons,
+                self.config.theme,
+                false,
+            )
+        }))
+        .highlight_style(Style::new().bg(Color::from_u32(0x00333355)));
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(block, popup_area);
+        frame.render_widget(Paragraph::new(message), message_area);
+        frame.render_widget(
+            Line::raw("─".repeat(separator_area.width as usize)).fg(Color::DarkGray),
+            separator_area,
+        );
+        frame.render_stateful_widget(list, changes_area, &mut review.list_state);
+    }
+
     fn calculate_popup_rect(&self, buttons: &[Text], allowed_area: Rect) -> Rect {
         let (row, mut col) = self.mouse_loc;
         let width = (buttons
@@ -60,30 +356,36 @@ impl App {
     }
 
     fn render_change_popup(&mut self, frame: &mut Frame) {
-        let selected = match self.get_selected_changes() {
-            Some(selected) if !selected.is_empty() => selected,
-            _ => return,
-        };
-        let popup = Block::new().bg(Color::DarkGray);
-        let button = |title: &'static str, color: Color| Text::raw(title).style(color);
-        let mut btn_widgets = vec![button("Open", Color::LightBlue)];
-        let mut btn_funcs = vec![App::open_change_file as fn(&mut App)];
-        if selected.iter().all(|(state, _)| state.is_deletable()) {
-            btn_widgets.push(button("Delete", Color::LightRed));
-            btn_funcs.push(App::delete_change_file);
-        }
-        if selected.iter().all(|(state, _)| state.is_revertable()) {
-            btn_widgets.push(button("Revert", Color::LightYellow));
-            btn_funcs.push(App::revert_change_file);
-        }
-        if selected.iter().all(|(state, _)| state.is_commitable()) {
-            btn_widgets.push(button("Commit", Color::LightGreen));
-            btn_funcs.push(App::commit_change_file);
-        }
-        if selected.iter().all(|(state, _)| state.is_addable()) {
-            btn_widgets.push(button("Add", Color::LightGreen));
-            btn_funcs.push(App::add_change_file);
+        let actions = self.selection_actions();
+        if actions.is_empty() {
+            return;
         }
+        let popup = Block::new().bg(Color::DarkGray);
+        // actions the svn client is too old for are shown disabled, saying why
+        let unsupported: Vec<Option<String>> = actions
+            .iter()
+            .map(|action| {
+                action
+                    .required_feature()
+                    .and_then(|feature| self.unsupported(feature))
+            })
+            .collect();
+        let btn_widgets: Vec<Text> = actions
+            .iter()
+            .zip(&unsupported)
+            .map(|(action, reason)| match reason {
+                Some(reason) => Text::raw(format!("{} ({reason})", action.label())).fg(Color::Gray),
+                None => Text::raw(action.label()).style(action_color(*action)),
+            })
+            .collect();
+        let btn_funcs: Vec<fn(&mut App)> = actions
+            .iter()
+            .zip(&unsupported)
+            .map(|(action, reason)| match reason {
+                Some(_) => App::explain_unsupported_actions,
+                None => action.handler(),
+            })
+            .collect();
         let constraints = vec![Constraint::Length(3); btn_widgets.len()];
         let popup_area = self
             .change_popup_area
@@ -104,26 +406,175 @@ impl App {
         self.change_popup_area = Some(popup_area);
     }
 
+    /// The branch, how many changes there are of each state and a flag when there are
+    /// conflicts, e.g. "trunk C1 M2 A1 CONFLICTS"
+    fn minimal_line(&self) -> Line<'_> {
+        let mut spans = vec![Span::raw(&*self.current_branch).fg(Color::Cyan)];
+        // without svn the branch is "svn not found" and there's nothing more to say, likewise
+        // "not a working copy"
+        if self.svn_missing || self.not_working_copy.is_some() {
+            return Line::from(spans);
+        }
+        if let Some(reason) = &self.unavailable {
+            spans.push(Span::raw(format!(" {reason}")).fg(Color::Red));
+            return Line::from(spans);
+        }
+        if self.loading_status.is_some() {
+            spans.push(Span::raw(" ...").fg(Color::DarkGray));
+        }
+        let counts = self.file_list.state_counts();
+        if counts.is_empty() && self.loading_status.is_none() {
+            spans.push(Span::raw(" clean").fg(Color::DarkGray));
+        }
+        for (state, count) in counts {
+            spans.push(Span::raw(" "));
+            spans.push(
+                Span::raw(format!("{state}{count}")).style(state_style(state, self.config.theme)),
+            );
+        }
+        if self.file_list.has_conflicts() {
+            spans.push(Span::raw(" "));
+            spans.push(
+                Span::raw("CONFLICTS")
+                    .fg(Color::White)
+                    .bg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+        Line::from(spans)
+    }
+
+    /// How many changes there are of each state for the changes' title, e.g. " [M:4 A:2 C:1]",
+    /// with the conflicts last and in red so they stand out
+    fn count_badges(&self) -> Vec<Span<'static>> {
+        let (conflicts, mut counts): (Vec<_>, Vec<_>) = self
+            .file_list
+            .state_counts()
+            .into_iter()
+            .partition(|(state, _)| *state == State::Conflicting);
+        counts.extend(conflicts);
+        if counts.is_empty() {
+            return vec![];
+        }
+        let mut spans = vec![Span::raw(" [")];
+        for (i, (state, count)) in counts.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" "));
+            }
+            let badge = Span::raw(format!("{state}:{count}"));
+            spans.push(match state {
+                State::Conflicting => badge.fg(Color::Red).add_modifier(Modifier::BOLD),
+                _ => badge,
+            });
+        }
+        spans.push(Span::raw("]"));
+        spans
+    }
+
     fn render_branch_box(&self, frame: &mut Frame, area: Rect) {
         let branch_box = Block::bordered().title("Branch");
-        let branch = Paragraph::new(Text::from(vec![
-            Line::raw(&*self.current_branch).style(Color::Cyan),
-            Line::raw(self.cwd.to_str().unwrap()).style(Color::DarkGray),
-        ]))
-        .block(branch_box);
+        let mut branch_line = Line::raw(&*self.current_branch).style(Color::Cyan);
+        if let Some(rev) = self.updated_revision {
+            branch_line.push_span(Span::raw(format!(" @ r{rev}")).fg(Color::DarkGray));
+        }
+        match self.behind.revisions {
+            None | Some(0) => {}
+            Some(1) => branch_line.push_span(Span::raw(" · 1 revision behind").fg(Color::Yellow)),
+            Some(n) => branch_line
+                .push_span(Span::raw(format!(" · {n} revisions behind")).fg(Color::Yellow)),
+        }
+        let mut lines = vec![branch_line];
+        if let Some(entry) = &self.last_commit {
+            lines.push(Line::from(vec![
+                Span::raw(format!("r{} {}: ", entry.revision, entry.author)).fg(Color::DarkGray),
+                Span::raw(entry.summary()).add_modifier(Modifier::ITALIC),
+            ]));
+        }
+        lines.push(Line::raw(self.cwd.to_str().unwrap()).style(Color::DarkGray));
+        let branch = Paragraph::new(Text::from(lines)).block(branch_box);
         frame.render_widget(branch, area);
     }
 
     fn render_file_list(&mut self, frame: &mut Frame, area: Rect) {
         let max_width = area.width - 3; // 1 each side for block borders, 1 for scrollbar
-        let block = Block::bordered().title("Changes");
-        let list = List::new(
-            self.file_list
-                .list()
-                .iter()
-                .filter(|(_, path)| !svn::is_conflict_part(path.to_str().expect("bad path")))
-                .map(|psl| create_file_list_item(psl, max_width)),
-        )
+        let compact = area.width < COMPACT_LIST_WIDTH;
+        let mut notes = vec![];
+        match (&self.loading_status, self.multiselection.len()) {
+            (Some(_), _) => notes.push("loading...".to_string()),
+            (None, 0) => {}
+            (None, n) => notes.push(format!("{n} selected")),
+        }
+        match self.file_list.hidden_property_only_count() {
+            0 => {}
+            n => notes.push(format!("{n} property-only hidden")),
+        }
+        match self.file_list.hidden_by_pattern_count() {
+            0 => {}
+            n => notes.push(format!("{n} hidden by pattern")),
+        }
+        let mut title = Line::raw(match self.scope_breadcrumbs().as_slice() {
+            [] => "Changes".to_string(),
+            crumbs => format!("Changes in {}", crumbs.join(" › ")),
+        });
+        title.spans.extend(self.count_badges());
+        if !notes.is_empty() {
+            title.push_span(Span::raw(format!(" ({})", notes.join(", "))));
+        }
+        let mut block = Block::bordered().title(title);
+        if !shows_branch_box(frame.area()) {
+            block = block.title(
+                Line::raw(&*self.current_branch)
+                    .fg(Color::Cyan)
+                    .right_aligned(),
+            );
+        }
+        let grouped = self.config.group_by != GroupBy::None;
+        // taken for the render so the rows can borrow the rest of the app
+        let mut list_state = std::mem::take(&mut self.list_state);
+        let rows = self.rows();
+        let tree = self.config.group_by == GroupBy::Tree;
+        let list = List::new(rows.into_iter().map(|row| {
+            let psl = match row {
+                Row::Favorites { count } => return create_favorites_header(count),
+                Row::Header {
+                    dir,
+                    count,
+                    collapsed,
+                    depth,
+                } => return create_group_header(&dir, count, collapsed, depth, tree),
+                Row::Section {
+                    state,
+                    count,
+                    collapsed,
+                } => return create_section_header(state, count, collapsed, self.config.theme),
+                Row::Change(psl) => psl,
+            };
+            let mut item = create_file_list_item(
+                psl,
+                max_width,
+                self.config.indent_by_depth || tree,
+                self.config.nerd_font_icons,
+                self.config.theme,
+                compact,
+            );
+            if grouped || self.pinned.contains(&psl.path) {
+                item.spans.insert(0, Span::raw("  "));
+            }
+            if let Some(changelist) = self.file_list.changelist(&psl.path) {
+                let changelist = match compact {
+                    true => abbreviate(changelist, 6),
+                    false => changelist.to_string(),
+                };
+                item.push_span(Span::raw(format!(" [{changelist}]")).fg(Color::DarkGray));
+            }
+            if let Some(lock) = psl.lock {
+                item.push_span(lock_span(lock, self.config.nerd_font_icons, compact));
+            }
+            match self.multiselection.contains(&psl.path) {
+                true => item.bg(Color::from_u32(0x00333355)),
+                false => item,
+            }
+        }))
         .highlight_style(
             Style::new()
                 .fg(Color::from_u32(0x00222222))
@@ -132,10 +583,11 @@ impl App {
         )
         .scroll_padding(1)
         .block(block);
-        self.changes_scrollbar_state = self.changes_scrollbar_state.content_length(list.len());
-        let list_length = list.len() as u16;
-        frame.render_stateful_widget(list, area, &mut self.list_state);
-        if area.height - 2 < list_length as u16 {
+        let list_length = list.len();
+        frame.render_stateful_widget(list, area, &mut list_state);
+        self.list_state = list_state;
+        self.changes_scrollbar_state = self.changes_scrollbar_state.content_length(list_length);
+        if usize::from(area.height - 2) < list_length {
             let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
             frame.render_stateful_widget(
                 scrollbar,
@@ -149,87 +601,1037 @@ impl App {
         self.changes_area = Some(area);
     }
 
+    fn render_workspaces(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title("Workspaces (enter to open, esc to go back)");
+        let list = List::new(self.workspaces.iter().map(|workspace| {
+            let mut spans = vec![
+                Span::raw(workspace.path.to_string_lossy()),
+                Span::raw("   "),
+            ];
+            match &workspace.error {
+                Some(error) => spans.push(Span::raw(error.trim()).fg(Color::LightRed)),
+                None if workspace.loading => {
+                    spans.push(Span::raw("loading...").fg(Color::DarkGray))
+                }
+                None => {
+                    spans.push(Span::raw(&*workspace.branch).fg(Color::Cyan));
+                    if workspace.total_changes() == 0 {
+                        spans.push(Span::raw("   no changes").fg(Color::DarkGray));
+                    }
+                    for (state, count) in &workspace.counts {
+                        spans.push(Span::raw("   "));
+                        spans.push(
+                            Span::raw(format!("{state}{count}"))
+                                .style(state_style(*state, self.config.theme)),
+                        );
+                    }
+                }
+            }
+            Line::from(spans)
+        }))
+        .highlight_style(
+            Style::new()
+                .bg(Color::from_u32(0x00333355))
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(block);
+        frame.render_stateful_widget(list, area, &mut self.workspaces_list_state);
+    }
+
+    /// Each branch's revisions in a lane, a lane further in than the branch copied from it
+    fn render_revision_graph(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(screen) = self.revision_graph.as_mut() else {
+            return;
+        };
+        let block = Block::bordered().title("Revision graph (esc to go back)");
+        let branches = match &screen.result {
+            None => {
+                let text = Line::raw("Reading the history...").fg(Color::Cyan);
+                frame.render_widget(Paragraph::new(text).block(block), area);
+                return;
+            }
+            Some(Err(e)) => {
+                let text = Line::raw(format!("Could not read the history: {}", e.trim()));
+                frame.render_widget(Paragraph::new(text.fg(Color::LightRed)).block(block), area);
+                return;
+            }
+            Some(Ok(branches)) => branches,
+        };
+        let items = graph_rows(branches).into_iter().map(|(lane, row)| {
+            let indent = Span::raw("│ ".repeat(lane)).fg(Color::DarkGray);
+            match row {
+                GraphRow::Branch(path) => Line::from(vec![
+                    indent,
+                    Span::raw(path.to_string())
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ]),
+                GraphRow::Revision(entry) => {
+                    let date = entry
+                        .date
+                        .map(|date| self.config.format_timestamp(date))
+                        .unwrap_or_default();
+                    Line::from(vec![
+                        indent,
+                        Span::raw("● "),
+                        Span::raw(format!("r{:<6} ", entry.revision)).fg(Color::Yellow),
+                        Span::raw(format!("{:<12} ", entry.author)),
+                        Span::raw(format!("{date}  ")).fg(Color::DarkGray),
+                        Span::raw(entry.summary().to_string()),
+                    ])
+                }
+                GraphRow::Copy { from, revision } => Line::from(vec![
+                    indent,
+                    Span::raw(format!("╰─┐ copied from {from}@{revision}")).fg(Color::DarkGray),
+                ]),
+            }
+        });
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(Color::from_u32(0x00333355)))
+            .block(block);
+        frame.render_stateful_widget(list, area, &mut screen.list_state);
+    }
+
+    fn render_history(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(screen) = self.history.as_mut() else {
+            return;
+        };
+        let hints = match screen.purpose {
+            HistoryPurpose::Browse => "enter: diff",
+            HistoryPurpose::Restore => "enter: restore, d: diff",
+        };
+        let block = Block::bordered().title(format!(
+            "History of {} ({hints}, esc to go back)",
+            screen.path
+        ));
+        let entries = match &screen.result {
+            None => {
+                let text = Line::raw("Reading the history...").fg(Color::Cyan);
+                frame.render_widget(Paragraph::new(text).block(block), area);
+                return;
+            }
+            Some(Err(e)) => {
+                let text = Line::raw(format!("Could not read the history: {}", e.trim()));
+                frame.render_widget(Paragraph::new(text.fg(Color::LightRed)).block(block), area);
+                return;
+            }
+            Some(Ok(entries)) if entries.is_empty() => {
+                let text = Line::raw("No revisions changed it").fg(Color::DarkGray);
+                frame.render_widget(Paragraph::new(text).block(block), area);
+                return;
+            }
+            Some(Ok(entries)) => entries,
+        };
+        let items = entries.iter().map(|entry| {
+            let date = entry
+                .date
+                .map(|date| self.config.format_timestamp(date))
+                .unwrap_or_default();
+            Line::from(vec![
+                Span::raw(format!("r{:<6} ", entry.revision)).fg(Color::Yellow),
+                Span::raw(format!("{:<12} ", entry.author)),
+                Span::raw(format!("{date}  ")).fg(Color::DarkGray),
+                Span::raw(entry.summary().to_string()),
+            ])
+        });
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(Color::from_u32(0x00333355)))
+            .block(block);
+        frame.render_stateful_widget(list, area, &mut screen.list_state);
+    }
+
+    /// The file's lines after who last changed them, tinted by how recently
+    fn render_blame(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(screen) = self.blame.as_ref() else {
+            return;
+        };
+        let block = Block::bordered().title(format!("Blame of {} (esc to go back)", screen.path));
+        let lines = match &screen.result {
+            None => {
+                let text = Line::raw("Reading who changed each line...").fg(Color::Cyan);
+                frame.render_widget(Paragraph::new(text).block(block), area);
+                return;
+            }
+            Some(Err(e)) => {
+                let text = Line::raw(format!("Could not blame it: {}", e.trim()));
+                frame.render_widget(Paragraph::new(text.fg(Color::LightRed)).block(block), area);
+                return;
+            }
+            Some(Ok(lines)) => lines,
+        };
+        let ages = line_ages(lines);
+        let text: Vec<Line> = lines
+            .iter()
+            .zip(ages)
+            .map(|(line, age)| {
+                let revision = line
+                    .revision
+                    .map_or("-".to_string(), |revision| revision.to_string());
+                let author = abbreviate(line.author.as_deref().unwrap_or("-"), 10);
+                let gutter = format!("{revision:>6} {author:<10} ");
+                Line::from(vec![
+                    Span::raw(gutter)
+                        .fg(Color::White)
+                        .bg(self.config.blame_gradient.colour_at(age)),
+                    Span::raw(" "),
+                    Span::raw(line.text.clone()),
+                ])
+            })
+            .collect();
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .scroll((screen.scroll.min(u16::MAX as usize) as u16, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    /// The three sides of the conflict in columns that scroll together, the conflict regions
+    /// highlighted
+    fn render_three_way(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(screen) = self.three_way.as_ref() else {
+            return;
+        };
+        let starts = screen.conflict_starts();
+        let current = starts
+            .iter()
+            .rposition(|&start| start <= screen.scroll)
+            .map_or(0, |i| i + 1);
+        let columns = Layout::horizontal([Constraint::Fill(1); 3]).split(area);
+        for (column, title) in screen.titles.iter().enumerate() {
+            let lines: Vec<Line> = screen
+                .rows
+                .iter()
+                .map(|row| {
+                    let line = Line::raw(row.cells[column].as_deref().unwrap_or_default());
+                    match row.conflict {
+                        true => line.bg(Color::from_u32(0x00442233)),
+                        false => line,
+                    }
+                })
+                .collect();
+            let mut block = Block::bordered().title(title.as_str());
+            if column == 0 {
+                block = block.title_bottom(
+                    Line::raw(format!(
+                        " {}: conflict {current}/{} ",
+                        screen.file.display(),
+                        starts.len()
+                    ))
+                    .fg(Color::LightMagenta),
+                );
+            } else if column == 1 {
+                block = block.title_bottom(
+                    Line::raw(" n/N: next/previous conflict  esc: back ").fg(Color::DarkGray),
+                );
+            }
+            let paragraph = Paragraph::new(lines)
+                .scroll((screen.scroll as u16, 0))
+                .block(block);
+            frame.render_widget(paragraph, columns[column]);
+        }
+    }
+
+    /// Each hunk of the file with whether it's picked to commit, its lines coloured like a diff
+    fn render_hunks(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(screen) = self.hunks.as_mut() else {
+            return;
+        };
+        let title = format!(
+            "Commit hunks of {} ({}/{} picked)",
+            screen.path,
+            screen.picked_count(),
+            screen.picked.len()
+        );
+        let footer = " space: pick  a: pick all  enter: commit  esc: cancel ";
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::raw(footer).fg(Color::DarkGray));
+        let items = screen
+            .diff
+            .hunks
+            .iter()
+            .zip(&screen.picked)
+            .map(|(hunk, picked)| {
+                let check = match picked {
+                    true => "[x] ",
+                    false => "[ ] ",
+                };
+                let mut lines = vec![Line::from(vec![
+                    Span::raw(check).add_modifier(Modifier::BOLD),
+                    Span::raw(hunk.range.as_str()).fg(Color::Cyan),
+                ])];
+                lines.extend(hunk.lines.iter().map(|line| {
+                    let color = match line.chars().next() {
+                        Some('+') => Color::Green,
+                        Some('-') => Color::Red,
+                        _ => Color::Gray,
+                    };
+                    Line::raw(format!("    {line}")).fg(color)
+                }));
+                ListItem::new(Text::from(lines))
+            });
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(Color::from_u32(0x00333355)))
+            .block(block);
+        frame.render_stateful_widget(list, area, &mut screen.list_state);
+    }
+
+    /// The paths that differ from the other branch, styled like the changes they'd become
+    fn render_diff_summary(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(screen) = self.diff_summary.as_mut() else {
+            return;
+        };
+        let max_width = area.width.saturating_sub(2);
+        let title = match &screen.result {
+            Some(Ok(paths)) => format!("Differences from {} ({})", screen.other, paths.len()),
+            _ => format!("Differences from {}", screen.other),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::raw(" esc to go back ").fg(Color::DarkGray));
+        let paths = match &screen.result {
+            None => {
+                let text = Line::raw("Comparing...").fg(Color::Cyan);
+                frame.render_widget(Paragraph::new(text).block(block), area);
+                return;
+            }
+            Some(Err(e)) => {
+                let text = Line::raw(format!("Could not compare: {}", e.trim()));
+                frame.render_widget(Paragraph::new(text.fg(Color::LightRed)).block(block), area);
+                return;
+            }
+            Some(Ok(paths)) if paths.is_empty() => {
+                let text = Line::raw("No differences").fg(Color::DarkGray);
+                frame.render_widget(Paragraph::new(text).block(block), area);
+                return;
+            }
+            Some(Ok(paths)) => paths,
+        };
+        let list = List::new(paths.iter().map(|psl| {
+            create_file_list_item(
+                psl,
+                max_width,
+                self.config.indent_by_depth,
+                self.config.nerd_font_icons,
+                self.config.theme,
+                max_width < COMPACT_LIST_WIDTH,
+            )
+        }))
+        .highlight_style(
+            Style::new()
+                .fg(Color::from_u32(0x00222222))
+                .bg(Color::Gray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(block);
+        frame.render_stateful_widget(list, area, &mut screen.list_state);
+    }
+
+    /// svn's output as the command goes, keeping the latest lines in view unless scrolled back
+    fn render_output(&self, frame: &mut Frame, area: Rect) {
+        let Some(output) = &self.output else {
+            return;
+        };
+        let title = output.operation.title();
+        let status = match &output.result {
+            None => Line::raw(format!("{}...", output.operation.describe())).fg(Color::Cyan),
+            Some(Ok(())) => match output.conflicts() {
+                0 => Line::raw("Done, esc to go back").fg(Color::Green),
+                n => Line::raw(format!("Done with {n} conflicts, esc to resolve them"))
+                    .fg(Color::Yellow),
+            },
+            Some(Err(e)) => Line::raw(format!("{title} failed: {}, esc to go back", e.trim()))
+                .fg(Color::LightRed),
+        };
+        // the block's borders and the status line leave the rest for the output
+        let visible = area.height.saturating_sub(3) as usize;
+        let skip = output.first_visible(visible);
+        let mut lines = vec![status];
+        lines.extend(
+            output.output[skip..]
+                .iter()
+                .take(visible)
+                .map(|line| Line::raw(line.as_str())),
+        );
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(" ↑/↓ PgUp/PgDn: scroll  End: follow ");
+        frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+    }
+
+    fn render_checkout(&self, frame: &mut Frame, area: Rect) {
+        let Some(checkout) = &self.checkout else {
+            return;
+        };
+        let (status, hint) = match &checkout.result {
+            None => (
+                Line::raw("Checking out...").fg(Color::Cyan),
+                "esc to cancel",
+            ),
+            Some(Ok(revision)) => (
+                Line::raw(format!("Checked out revision {revision}")).fg(Color::Green),
+                "",
+            ),
+            Some(Err(e)) => (
+                Line::raw(format!("Checkout failed: {}", e.trim())).fg(Color::LightRed),
+                "esc to quit",
+            ),
+        };
+        let last_path = checkout
+            .last_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let text = Text::from(vec![
+            status,
+            Line::raw(format!("from {}", checkout.url)),
+            Line::raw(format!("into {}", checkout.dest.display())),
+            Line::raw(""),
+            Line::raw(format!(
+                "{} files, {} ({}/s)",
+                checkout.files,
+                format_bytes(checkout.bytes),
+                format_bytes(checkout.rate())
+            )),
+            Line::raw(last_path).fg(Color::DarkGray),
+            Line::raw(""),
+            Line::raw(hint).fg(Color::DarkGray),
+        ]);
+        let block = Block::bordered().title("Checkout");
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn render_conflicts(&mut self, frame: &mut Frame, area: Rect) {
+        let max_width = area.width.saturating_sub(3); // 1 each side for block borders, 1 for scrollbar
+        let conflicts = self.file_list.conflicts();
+        let lines: Vec<Line> = conflicts
+            .iter()
+            .flat_map(|conflict| transform_conflict(conflict, max_width))
+            .collect();
+        self.conflicts_line_count = lines.len();
+        if self.conflicts_scroll_offset >= lines.len() {
+            self.conflicts_scroll_offset = lines.len().saturating_sub(1);
+        }
+        let border = match self.focus {
+            AppSection::Conflicts => Color::LightMagenta,
+            _ => Color::Reset,
+        };
+        let block = Block::bordered()
+            .title(format!("Conflicts ({})", conflicts.len()))
+            .border_style(border);
+        let paragraph = Paragraph::new(lines)
+            .scroll((self.conflicts_scroll_offset as u16, 0))
+            .block(block);
+        frame.render_widget(paragraph, area);
+        self.conflicts_scrollbar_state = self
+            .conflicts_scrollbar_state
+            .content_length(self.conflicts_line_count)
+            .position(self.conflicts_scroll_offset);
+        if (area.height.saturating_sub(2) as usize) < self.conflicts_line_count {
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
+            frame.render_stateful_widget(
+                scrollbar,
+                area.inner(Margin {
+                    horizontal: 0,
+                    vertical: 1,
+                }),
+                &mut self.conflicts_scrollbar_state,
+            );
+        }
+        self.conflicts_area = Some(area);
+    }
+
+    /// The paths an update would change, with what's changed locally in the same place
+    fn render_incoming(&self, frame: &mut Frame, area: Option<Rect>) {
+        let (Some(pane), Some(area)) = (&self.incoming, area) else {
+            return;
+        };
+        let title = match &pane.result {
+            Some(Ok(incoming)) => match incoming.head {
+                Some(head) => format!("Incoming ({}, HEAD r{head})", incoming.changes.len()),
+                None => format!("Incoming ({})", incoming.changes.len()),
+            },
+            _ => "Incoming".to_string(),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::raw(" F to hide, r to check again ").fg(Color::DarkGray));
+        let lines: Vec<Line> = match &pane.result {
+            None => vec![Line::raw("Asking the repository...").fg(Color::Cyan)],
+            Some(Err(e)) => {
+                vec![
+                    Line::raw(format!("Could not check for updates: {}", e.trim()))
+                        .fg(Color::LightRed),
+                ]
+            }
+            Some(Ok(incoming)) if incoming.changes.is_empty() => {
+                vec![Line::raw("Up to date").fg(Color::DarkGray)]
+            }
+            Some(Ok(incoming)) => incoming
+                .changes
+                .iter()
+                .map(|change| {
+                    let mut spans = vec![
+                        Span::raw("* ").fg(Color::Cyan),
+                        Span::raw(change.path.display().to_string()),
+                    ];
+                    if change.local != State::Clean {
+                        spans.push(
+                            Span::raw(format!(" (locally {})", change.local.name()))
+                                .style(state_style(change.local, self.config.theme)),
+                        );
+                    }
+                    Line::from(spans)
+                })
+                .collect(),
+        };
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
     fn render_message_box(&self, frame: &mut Frame, area: Rect) {
-        let help = Line::from(vec![Span::raw(&self.last_message)]).style(Color::Gray);
-        frame.render_widget(help, area);
+        if let Some(prompt) = &self.prompt {
+            let line = Line::from(vec![
+                Span::raw(prompt.kind.label()).fg(Color::Cyan),
+                Span::raw(&*prompt.input),
+                Span::raw(" ").bg(Color::Gray), // the cursor
+            ]);
+            frame.render_widget(line, area);
+            return;
+        }
+        let (text, is_hint) = self.message_or_hints();
+        let color = match is_hint {
+            true => Color::DarkGray,
+            false => Color::Gray,
+        };
+        // what svn is doing in the background stays in view on the right
+        let area = match self.worker.status() {
+            Some(status) => {
+                let status = Line::raw(format!(" {status} "))
+                    .fg(Color::Black)
+                    .bg(Color::Cyan);
+                let [rest, right] = Layout::horizontal([
+                    Constraint::Fill(1),
+                    Constraint::Length(status.width() as u16),
+                ])
+                .areas(area);
+                frame.render_widget(status, right);
+                rest
+            }
+            None => area,
+        };
+        // and how fresh the changes are, left of it
+        let area = match self.svn_missing || self.not_working_copy.is_some() {
+            true => area,
+            false => {
+                let updated = match self.refresh_flashing() {
+                    true => Line::raw(" refreshed ").add_modifier(Modifier::REVERSED),
+                    false => {
+                        let ago = self
+                            .config
+                            .format_last_updated(self.last_updated, Utc::now());
+                        Line::raw(format!(" updated {ago} ")).fg(Color::DarkGray)
+                    }
+                };
+                let [rest, right] = Layout::horizontal([
+                    Constraint::Fill(1),
+                    Constraint::Length(updated.width() as u16),
+                ])
+                .areas(area);
+                frame.render_widget(updated, right);
+                rest
+            }
+        };
+        frame.render_widget(Line::raw(text).style(color), area);
+    }
+}
+
+/// How to install svn, for the platform the app is running on
+fn svn_install_hints() -> Vec<&'static str> {
+    if cfg!(target_os = "macos") {
+        vec![
+            "brew install subversion",
+            "or: sudo port install subversion",
+        ]
+    } else if cfg!(target_os = "windows") {
+        vec![
+            "install TortoiseSVN with its \"command line client tools\" ticked",
+            "or: winget install Slik.Subversion",
+        ]
+    } else {
+        vec![
+            "Debian/Ubuntu: sudo apt install subversion",
+            "Fedora: sudo dnf install subversion",
+            "Arch: sudo pacman -S subversion",
+        ]
+    }
+}
+
+/// Shown instead of the changes when the svn client can't be found
+fn render_svn_missing(frame: &mut Frame, area: Rect) {
+    let mut lines = vec![
+        Line::raw("svn was not found on the PATH").fg(Color::LightRed),
+        Line::raw(""),
+        Line::raw("Install the svn command line client, then restart:"),
+    ];
+    lines.extend(
+        svn_install_hints()
+            .into_iter()
+            .map(|hint| Line::raw(format!("  {hint}"))),
+    );
+    lines.push(Line::raw(""));
+    if let Some(path) = config::global_config_path() {
+        lines.push(Line::raw(format!("Config: {}", path.display())).fg(Color::DarkGray));
+    }
+    lines.push(Line::raw("svn-util --help lists the options").fg(Color::DarkGray));
+    let block = Block::bordered().title("svn not found");
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+/// Shown instead of the changes when it was started outside any working copy
+fn render_not_working_copy(frame: &mut Frame, area: Rect, cwd: &Path, reason: &str) {
+    let mut lines = vec![
+        Line::raw(format!("{} is not a working copy", cwd.display())).fg(Color::LightRed),
+        Line::raw(reason.to_string()).fg(Color::DarkGray),
+        Line::raw(""),
+        Line::raw("Press C to open a working copy, or start svn-util in one"),
+        Line::raw(""),
+    ];
+    if let Some(path) = config::global_config_path() {
+        lines.push(Line::raw(format!("Config: {}", path.display())).fg(Color::DarkGray));
+    }
+    lines.push(Line::raw("svn-util --help lists the options").fg(Color::DarkGray));
+    let block = Block::bordered().title("not a working copy");
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+/// The conflicted file followed by its conflict parts, then a blank line to separate it from
+/// the next conflict
+fn transform_conflict(conflict: &Conflict, max_width: u16) -> Vec<Line<'static>> {
+    let make_line = |p: &PathBuf, color: Color| {
+        let mut text = p.to_string_lossy().to_string();
+        if text.len() as u16 > max_width {
+            let keep = (max_width as usize).saturating_sub(3);
+            text = text
+                .split_at_checked(keep)
+                .unwrap_or((&text, ""))
+                .0
+                .to_string();
+            text.push_str("...");
+        }
+        Line::raw(text).style(color)
+    };
+    match conflict {
+        Conflict::Text {
+            file,
+            left,
+            right,
+            working,
+        } => {
+            let mut lines = vec![make_line(file, Color::LightMagenta)];
+            lines.extend(
+                [left, working, right]
+                    .into_iter()
+                    .flatten()
+                    .map(|part| make_line(part, Color::DarkGray)),
+            );
+            lines.push(Line::raw(""));
+            lines
+        }
+    }
+}
+
+/// The width of the conflicts pane, keeping both panes usable, or `None` if there's only room
+/// for the changes list
+fn conflicts_pane_width(total: u16, percent: u16) -> Option<u16> {
+    if total < MINIMUM_PANE_WIDTH * 2 {
+        return None;
+    }
+    let width = (total as u32 * percent as u32 / 100) as u16;
+    Some(width.clamp(MINIMUM_PANE_WIDTH, total - MINIMUM_PANE_WIDTH))
+}
+
+/// The height of the conflicts pane when stacked under the changes list, or `None` if there's
+/// only room for the changes list
+fn conflicts_pane_height(total: u16, percent: u16) -> Option<u16> {
+    if total < MINIMUM_STACKED_PANE_HEIGHT * 2 {
+        return None;
     }
+    let height = (total as u32 * percent as u32 / 100) as u16;
+    Some(height.clamp(
+        MINIMUM_STACKED_PANE_HEIGHT,
+        total - MINIMUM_STACKED_PANE_HEIGHT,
+    ))
 }
 
-// fn transform_conflict<'a>(conflict: &'a Conflict, max_width: u16) -> Vec<Line<'a>> {
-//     let make_line = |p: &'a PathBuf, color: Color| {
-//         let mut text = p.to_str().expect("bad path").to_string();
-//         if text.len() as u16 > max_width {
-//             text = text.split_at(max_width as usize - 3).0.to_string();
-//             text.push_str("...");
-//         }
-//         Line::raw(text).style(color)
-//     };
-//     match conflict {
-//         Conflict::Text {
-//             file,
-//             left,
-//             right,
-//             working,
-//         } => match (left, right, working) {
-//             (Some(l), Some(r), Some(w)) => vec![
-//                 make_line(file, Color::Magenta),
-//                 make_line(l, Color::DarkGray),
-//                 make_line(w, Color::DarkGray),
-//                 make_line(r, Color::DarkGray),
-//                 Line::raw(""),
-//             ],
-//             _ => panic!("can there even be a conflict without all 3 parts?"),
-//         },
-//     }
-// }
+/// The areas of the changes list and conflicts pane: side by side for the horizontal layout
+/// when there's the width, otherwise one above the other, or `None` if there's only room for
+/// the changes list
+fn split_conflicts_pane(area: Rect, layout: PaneLayout, percent: u16) -> Option<(Rect, Rect)> {
+    let width = match layout {
+        PaneLayout::Horizontal => conflicts_pane_width(area.width, percent),
+        PaneLayout::Vertical => None,
+    };
+    let [changes, conflicts] = match width {
+        Some(width) => {
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(width)]).areas(area)
+        }
+        None => {
+            let height = conflicts_pane_height(area.height, percent)?;
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(height)]).areas(area)
+        }
+    };
+    Some((changes, conflicts))
+}
+
+/// Whether the terminal has room for the branch box above the changes
+/// Splits the incoming changes pane off the bottom of the area, tall enough for the paths
+/// but taking at most a third of it. `None` for the pane when there isn't room.
+fn split_incoming_pane(area: Rect, paths: usize) -> (Rect, Option<Rect>) {
+    let height = (paths.max(1) as u16 + 2).min(area.height / 3);
+    if height < 3 {
+        return (area, None);
+    }
+    let [rest, pane] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(height)]).areas(area);
+    (rest, Some(pane))
+}
+
+fn shows_branch_box(area: Rect) -> bool {
+    area.width >= BRANCH_BOX_MIN_WIDTH && area.height >= BRANCH_BOX_MIN_HEIGHT
+}
+
+/// The start of the text, marked with an ellipsis if it's longer than `max` characters
+fn abbreviate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some(_) => {
+            let kept: String = text.chars().take(max.saturating_sub(1)).collect();
+            format!("{kept}…")
+        }
+        None => text.to_string(),
+    }
+}
+
+fn action_color(action: Action) -> Color {
+    match action {
+        Action::Open | Action::OpenDir | Action::Diff => Color::LightBlue,
+        Action::Delete => Color::LightRed,
+        Action::Revert | Action::RestoreRevision => Color::LightYellow,
+        Action::Commit | Action::Add => Color::LightGreen,
+        Action::Resolve => Color::LightMagenta,
+        Action::Lock => Color::LightCyan,
+        Action::Ignore => Color::Gray,
+        Action::Pin => Color::LightYellow,
+        Action::Export | Action::CopyUrl | Action::History | Action::Blame => Color::LightBlue,
+        Action::PropertyDiff => Color::LightBlue,
+        Action::CommitHunks => Color::LightGreen,
+        Action::ThreeWay | Action::AutoResolve => Color::LightMagenta,
+        Action::RenameChangelist => Color::LightCyan,
+        Action::CommitChangelist => Color::LightGreen,
+    }
+}
+
+pub fn state_style(state: State, theme: Theme) -> Style {
+    // Okabe-Ito colours, which stay distinct with red-green colour blindness
+    let orange = Color::Rgb(230, 159, 0);
+    let sky_blue = Color::Rgb(86, 180, 233);
+    let blue = Color::Rgb(0, 114, 178);
+    let yellow = Color::Rgb(240, 228, 66);
+    let vermillion = Color::Rgb(213, 94, 0);
+    let purple = Color::Rgb(204, 121, 167);
+    // conflicts and missing files stand out by shape too, not only by colour
+    let conflict = Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED);
+    let missing = Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    match (theme, state) {
+        (Theme::Default, _) => default_state_style(state),
+        (Theme::Monochrome, _) => monochrome_state_style(state),
+        (Theme::HighContrast, _) => high_contrast_state_style(state),
+        (_, State::Modified) => yellow.into(),
+        (_, State::Added) => sky_blue.into(),
+        (Theme::Deuteranopia, State::Deleted) => vermillion.into(),
+        (Theme::Protanopia, State::Deleted) => orange.into(),
+        (Theme::Deuteranopia, State::Missing) => missing.fg(vermillion),
+        (Theme::Protanopia, State::Missing) => missing.fg(orange),
+        (_, State::Replaced) => blue.into(),
+        (_, State::Unversioned) => Color::White.into(),
+        (_, State::Conflicting) => conflict.fg(purple),
+        (_, State::Clean) => Color::DarkGray.into(),
+    }
+}
+
+fn monochrome_state_style(state: State) -> Style {
+    let style = Style::new();
+    match state {
+        State::Modified => style,
+        State::Added => style.add_modifier(Modifier::BOLD),
+        State::Deleted => style.add_modifier(Modifier::CROSSED_OUT),
+        State::Missing => style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        State::Replaced => style.add_modifier(Modifier::UNDERLINED),
+        State::Unversioned => style.add_modifier(Modifier::ITALIC),
+        State::Conflicting => style.add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        State::Clean => style.add_modifier(Modifier::DIM),
+    }
+}
+
+fn high_contrast_state_style(state: State) -> Style {
+    let bold = Style::new().add_modifier(Modifier::BOLD);
+    match state {
+        State::Modified => bold.fg(Color::LightYellow),
+        State::Added => bold.fg(Color::LightGreen),
+        State::Deleted => bold.fg(Color::LightRed),
+        State::Missing => bold.fg(Color::LightRed).add_modifier(Modifier::UNDERLINED),
+        State::Replaced => bold.fg(Color::LightCyan),
+        State::Unversioned => bold.fg(Color::White),
+        State::Conflicting => bold
+            .fg(Color::LightMagenta)
+            .add_modifier(Modifier::REVERSED),
+        State::Clean => Color::White.into(),
+    }
+}
+
+/// Shows the grey hints, counts and paths in the terminal's own foreground instead, which the
+/// high contrast theme needs more than the rest of the screen needs the grey to stand back
+fn drop_dim_styling(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if cell.fg == Color::DarkGray {
+            cell.fg = Color::Reset;
+        }
+        cell.modifier.remove(Modifier::DIM);
+    }
+}
+
+fn default_state_style(state: State) -> Style {
+    match state {
+        State::Modified => Color::Yellow.into(),
+        State::Added => Color::Green.into(),
+        State::Deleted => Color::Red.into(),
+        State::Missing => Style::new()
+            .fg(Color::Red)
+            .add_modifier(Modifier::RAPID_BLINK),
+        State::Replaced => Color::Cyan.into(),
+        State::Unversioned => Color::White.into(),
+        State::Conflicting => Color::LightMagenta.into(),
+        State::Clean => Color::DarkGray.into(),
+    }
+}
+
+fn create_favorites_header(count: usize) -> Line<'static> {
+    Line::from(vec![
+        Span::raw("favorites").add_modifier(Modifier::BOLD),
+        Span::raw(format!(" ({count})")).fg(Color::DarkGray),
+    ])
+}
+
+/// The header of a directory's changes, just its name indented under its parent in a tree
+fn create_group_header(
+    dir: &Path,
+    count: usize,
+    collapsed: bool,
+    depth: usize,
+    tree: bool,
+) -> Line<'static> {
+    let marker = match collapsed {
+        true => "▸",
+        false => "▾",
+    };
+    let name = match tree {
+        true => dir.file_name().unwrap_or(dir.as_os_str()).to_string_lossy(),
+        false => dir.to_string_lossy(),
+    };
+    Line::from(vec![
+        Span::raw(format!("{}{marker} {name}/", "  ".repeat(depth))).add_modifier(Modifier::BOLD),
+        Span::raw(format!(" ({count})")).fg(Color::DarkGray),
+    ])
+}
+
+/// The header of a state's changes, in the state's colour
+fn create_section_header(
+    state: State,
+    count: usize,
+    collapsed: bool,
+    theme: Theme,
+) -> Line<'static> {
+    let marker = match collapsed {
+        true => "▸",
+        false => "▾",
+    };
+    Line::from(vec![
+        Span::raw(format!("{marker} {}", section_title(state)))
+            .style(state_style(state, theme))
+            .add_modifier(Modifier::BOLD),
+        Span::raw(format!(" ({count})")).fg(Color::DarkGray),
+    ])
+}
 
 /// Errors from PathBuf transformations are shown inline in the list view
-fn create_file_list_item<'a>((state, path): &'a ParsedStatusLine, max_width: u16) -> Line<'a> {
-    let state_span = match state {
-        State::Modified => Span::from(state.to_string()).style(Color::Yellow),
-        State::Added => Span::from(state.to_string()).style(Color::Green),
-        State::Deleted => Span::from(state.to_string()).style(Color::Red),
-        State::Missing => Span::from(state.to_string()).style(
-            Style::new()
-                .fg(Color::Red)
-                .add_modifier(Modifier::RAPID_BLINK),
-        ),
-        State::Replaced => Span::from(state.to_string()).style(Color::Cyan),
-        State::Unversioned => Span::from(state.to_string()).style(Color::White),
-        State::Conflicting => Span::from(state.to_string()).style(Color::LightMagenta),
-        State::Clean => Span::from(state.to_string()).style(Color::DarkGray),
+fn create_file_list_item<'a>(
+    StatusEntry {
+        state,
+        path,
+        properties,
+        ..
+    }: &'a StatusEntry,
+    max_width: u16,
+    indent_by_depth: bool,
+    icons: bool,
+    theme: Theme,
+    compact: bool,
+) -> Line<'a> {
+    let state_span = match icons {
+        true => Span::from(state_glyph(*state)),
+        false => Span::from(state.to_string()),
+    }
+    .style(state_style(*state, theme));
+    // the properties' column follows the state's like in 'svn status', e.g. "MM" or " M"
+    let property_span = properties.map(|status| {
+        let state = match status {
+            PropertyStatus::Modified => State::Modified,
+            PropertyStatus::Conflicted => State::Conflicting,
+        };
+        Span::raw(status.letter()).style(state_style(state, theme))
+    });
+    let spacer = match (compact, property_span.is_some()) {
+        (true, _) => " ",
+        (false, true) => "  ",
+        (false, false) => "   ",
+    };
+    let property_width = property_span.as_ref().map_or(0, Span::width);
+    let icon = match icons {
+        true => format!("{} ", file_icon(path)),
+        false => String::new(),
+    };
+    let icon_width = Span::raw(&icon).width();
+    let indent = match indent_by_depth {
+        true => "  ".repeat(path_depth(path)),
+        false => String::new(),
     };
-    let mut filename = path
-        .to_str()
-        .unwrap_or(&format!("ui.create_list_item issue: {path:?}"))
-        .to_string();
-    let spacer = "   ";
-    if max_width < 100 {
-        // with a really wide terminal space we can just show the whole paths!
-        filename = path
+    // with a really wide terminal space we can just show the whole paths!
+    let show_full_path = max_width >= 100;
+    let mut filename = match (show_full_path, indent_by_depth) {
+        (true, false) => path
+            .to_str()
+            .unwrap_or(&format!("ui.create_list_item issue: {path:?}"))
+            .to_string(),
+        _ => path
             .file_name()
             .unwrap_or(OsStr::new(".")) // TODO this isn't necessarily always true
             .to_str()
             .unwrap_or(&format!("ui.create_list_item issue: {path:?}"))
+            .to_string(),
+    };
+    // the directory part is dimmed so the filename stands out
+    let dir_prefix = match (show_full_path, indent_by_depth) {
+        (true, true) => directory_prefix(path),
+        _ => String::new(),
+    };
+    if !show_full_path
+        && (state_span.width()
+            + property_width
+            + spacer.len()
+            + indent.len()
+            + icon_width
+            + filename.len()) as u16
+            >= max_width
+    {
+        let available = (max_width as usize).saturating_sub(3 + indent.len() + icon_width);
+        filename = filename
+            .split_at_checked(available)
+            .unwrap_or(("", ""))
+            .0
             .to_string();
-        if (state_span.width() + spacer.len() + filename.len()) as u16 >= max_width {
-            filename = filename
-                .split_at_checked((max_width - 3) as usize)
-                .unwrap_or(("", ""))
-                .0
-                .to_string();
-            filename.push_str("...");
-        }
+        filename.push_str("...");
     }
     let path_color = match state {
         State::Clean => Color::DarkGray,
         _ => Color::Reset,
     };
-    Line::from(vec![
-        state_span,
-        Span::raw(spacer),
-        Span::raw(filename).fg(path_color),
-    ])
+    let mut spans = vec![state_span];
+    spans.extend(property_span);
+    spans.push(Span::raw(spacer));
+    if !indent.is_empty() {
+        spans.push(Span::raw(indent));
+    }
+    if !icon.is_empty() {
+        spans.push(Span::raw(icon).fg(Color::Gray));
+    }
+    if !dir_prefix.is_empty() {
+        spans.push(Span::raw(dir_prefix).fg(Color::DarkGray));
+    }
+    spans.push(Span::raw(filename).fg(path_color));
+    Line::from(spans)
+}
+
+/// Who holds the lock on a file, green when it's this working copy's to commit with
+fn lock_span(lock: LockStatus, icons: bool, compact: bool) -> Span<'static> {
+    let text = match (icons, compact) {
+        (true, true) => " \u{f023}".to_string(),
+        (true, false) => format!(" \u{f023} {}", lock.label()), // nf-fa-lock
+        (false, true) => format!(" ({})", lock.short_label()),
+        (false, false) => format!(" ({})", lock.label()),
+    };
+    let color = match lock {
+        LockStatus::Mine => Color::Green,
+        LockStatus::Other => Color::LightRed,
+        LockStatus::Stolen | LockStatus::Broken => Color::Yellow,
+    };
+    Span::raw(text).fg(color)
+}
+
+/// The Nerd Font glyph shown instead of the state's letter
+fn state_glyph(state: State) -> &'static str {
+    match state {
+        State::Modified => "\u{f459}",    // nf-oct-diff_modified
+        State::Added => "\u{f457}",       // nf-oct-diff_added
+        State::Deleted => "\u{f458}",     // nf-oct-diff_removed
+        State::Replaced => "\u{f45a}",    // nf-oct-diff_renamed
+        State::Unversioned => "\u{f420}", // nf-oct-question
+        State::Conflicting => "\u{f421}", // nf-oct-alert
+        State::Missing => "\u{f12a}",     // nf-fa-exclamation
+        State::Clean => " ",
+    }
+}
+
+/// The Nerd Font icon for the type of file, from its extension
+fn file_icon(path: &Path) -> &'static str {
+    if path.is_dir() {
+        return "\u{f07b}"; // nf-fa-folder
+    }
+    let extension = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "rs" => "\u{e7a8}",
+        "c" | "h" => "\u{e61e}",
+        "cpp" | "cc" | "hpp" => "\u{e61d}",
+        "py" => "\u{e606}",
+        "js" => "\u{e74e}",
+        "ts" => "\u{e628}",
+        "java" => "\u{e738}",
+        "go" => "\u{e626}",
+        "sh" | "bash" => "\u{f489}",
+        "md" => "\u{f48a}",
+        "json" => "\u{e60b}",
+        "toml" | "yaml" | "yml" | "ini" | "cfg" | "conf" => "\u{e615}",
+        "xml" | "html" => "\u{f121}",
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "bmp" => "\u{f1c5}",
+        "zip" | "gz" | "tar" | "7z" => "\u{f1c6}",
+        "txt" | "log" => "\u{f15c}",
+        _ => "\u{f15b}", // nf-fa-file
+    }
+}
+
+/// The number of directories above the path, e.g. "dir1/nested1/file.txt" is 2 deep
+fn path_depth(path: &Path) -> usize {
+    path.components().count().saturating_sub(1)
+}
+
+/// The parent directory of the path with a trailing separator, or an empty string for
+/// paths at the root of the working copy
+fn directory_prefix(path: &Path) -> String {
+    match path.parent().and_then(|p| p.to_str()) {
+        Some(parent) if !parent.is_empty() => format!("{parent}/"),
+        _ => String::new(),
+    }
 }
 
 #[cfg(test)]
@@ -237,6 +1639,7 @@ mod tests {
     use super::*;
     use State::*;
     use rstest::*;
+    use std::collections::HashSet;
 
     #[rstest]
     #[case(Modified, "path/to/file.txt", 20, "M", "file.txt", Color::Yellow)]
@@ -271,8 +1674,8 @@ mod tests {
         #[case] exp_path: &str,
         #[case] exp_color: Color,
     ) {
-        let psl = (state, path.into());
-        let actual = create_file_list_item(&psl, max_width);
+        let psl = StatusEntry::new(state, path);
+        let actual = create_file_list_item(&psl, max_width, false, false, Theme::Default, false);
         let expected = Line {
             style: Style::new(),
             alignment: None,
@@ -284,4 +1687,188 @@ mod tests {
         };
         assert_eq!(expected, actual);
     }
+
+    #[rstest]
+    #[case("file.txt", 20, vec![Span::from("file.txt").fg(Color::Reset)])]
+    #[case("path/to/file.txt", 20, vec![
+        Span::from("    "),
+        Span::from("file.txt").fg(Color::Reset),
+    ])]
+    #[case("path/to/file.txt", 100, vec![
+        Span::from("    "),
+        Span::from("path/to/").fg(Color::DarkGray),
+        Span::from("file.txt").fg(Color::Reset),
+    ])]
+    fn test_create_file_list_item_indented(
+        #[case] path: &str,
+        #[case] max_width: u16,
+        #[case] exp_spans: Vec<Span>,
+    ) {
+        let psl = StatusEntry::new(Modified, path);
+        let actual = create_file_list_item(&psl, max_width, true, false, Theme::Default, false);
+        let mut spans = vec![Span::from("M").style(Color::Yellow), Span::from("   ")];
+        spans.extend(exp_spans);
+        assert_eq!(Line::from(spans), actual);
+    }
+
+    #[test]
+    fn test_create_file_list_item_with_icons() {
+        let psl = StatusEntry::new(Modified, "src/main.rs");
+        assert_eq!(
+            Line::from(vec![
+                Span::from("\u{f459}").style(Color::Yellow),
+                Span::from("   "),
+                Span::from("\u{e7a8} ").fg(Color::Gray),
+                Span::from("main.rs").fg(Color::Reset),
+            ]),
+            create_file_list_item(&psl, 20, false, true, Theme::Default, false)
+        );
+    }
+
+    #[test]
+    fn test_create_file_list_item_with_properties() {
+        let psl = StatusEntry {
+            properties: Some(PropertyStatus::Modified),
+            ..StatusEntry::new(Clean, "dir")
+        };
+        assert_eq!(
+            Line::from(vec![
+                Span::from(" ").style(state_style(Clean, Theme::Default)),
+                Span::from("M").style(state_style(Modified, Theme::Default)),
+                Span::from("  "),
+                Span::from("dir").fg(Color::DarkGray),
+            ]),
+            create_file_list_item(&psl, 20, false, false, Theme::Default, false)
+        );
+    }
+
+    #[rstest]
+    fn test_state_styles_are_distinct(
+        #[values(
+            Theme::Default,
+            Theme::Deuteranopia,
+            Theme::Protanopia,
+            Theme::Monochrome,
+            Theme::HighContrast
+        )]
+        theme: Theme,
+    ) {
+        let states = [
+            Modified,
+            Added,
+            Deleted,
+            Missing,
+            Replaced,
+            Unversioned,
+            Conflicting,
+            Clean,
+        ];
+        let styles: Vec<Style> = states.iter().map(|s| state_style(*s, theme)).collect();
+        for (i, style) in styles.iter().enumerate() {
+            assert!(!styles[i + 1..].contains(style), "{:?} reused", states[i]);
+        }
+        let glyphs: HashSet<String> = states.iter().map(|s| s.to_string()).collect();
+        assert_eq!(states.len(), glyphs.len());
+    }
+
+    #[rstest]
+    #[case(100, 40, Some(40))]
+    #[case(100, 90, Some(80))]
+    #[case(50, 10, Some(20))]
+    #[case(39, 50, None)]
+    fn test_conflicts_pane_width(
+        #[case] total: u16,
+        #[case] percent: u16,
+        #[case] exp: Option<u16>,
+    ) {
+        assert_eq!(exp, conflicts_pane_width(total, percent));
+    }
+
+    #[rstest]
+    #[case(30, 40, Some(12))]
+    #[case(30, 90, Some(25))]
+    #[case(9, 50, None)]
+    fn test_conflicts_pane_height(
+        #[case] total: u16,
+        #[case] percent: u16,
+        #[case] exp: Option<u16>,
+    ) {
+        assert_eq!(exp, conflicts_pane_height(total, percent));
+    }
+
+    #[test]
+    fn test_split_conflicts_pane() {
+        let horizontal = PaneLayout::Horizontal;
+        let (changes, conflicts) =
+            split_conflicts_pane(Rect::new(0, 0, 100, 20), horizontal, 40).unwrap();
+        assert_eq!((60, 20), (changes.width, changes.height));
+        assert_eq!((60, 40), (conflicts.x, conflicts.width));
+        // too narrow for side by side
+        let (changes, conflicts) =
+            split_conflicts_pane(Rect::new(0, 0, 30, 20), horizontal, 40).unwrap();
+        assert_eq!((30, 12), (changes.width, changes.height));
+        assert_eq!((12, 8), (conflicts.y, conflicts.height));
+        assert_eq!(
+            None,
+            split_conflicts_pane(Rect::new(0, 0, 30, 8), horizontal, 40)
+        );
+        let (changes, conflicts) =
+            split_conflicts_pane(Rect::new(0, 0, 100, 20), PaneLayout::Vertical, 40).unwrap();
+        assert_eq!((100, 12), (changes.width, changes.height));
+        assert_eq!((100, 8), (conflicts.width, conflicts.height));
+    }
+
+    #[test]
+    fn test_split_incoming_pane() {
+        let (rest, pane) = split_incoming_pane(Rect::new(0, 0, 80, 30), 4);
+        assert_eq!((24, Some(Rect::new(0, 24, 80, 6))), (rest.height, pane));
+        // at most a third of the height
+        let (rest, pane) = split_incoming_pane(Rect::new(0, 0, 80, 30), 40);
+        assert_eq!((20, Some(10)), (rest.height, pane.map(|p| p.height)));
+        assert_eq!(
+            (Rect::new(0, 0, 80, 8), None),
+            split_incoming_pane(Rect::new(0, 0, 80, 8), 4)
+        );
+    }
+
+    #[test]
+    fn test_create_file_list_item_compact() {
+        let psl = StatusEntry::new(Modified, "path/to/file.txt");
+        assert_eq!(
+            Line::from(vec![
+                Span::from("M").style(Color::Yellow),
+                Span::from(" "),
+                Span::from("file.txt").fg(Color::Reset),
+            ]),
+            create_file_list_item(&psl, 20, false, false, Theme::Default, true)
+        );
+        assert_eq!(" (lk!)", lock_span(LockStatus::Other, false, true).content);
+    }
+
+    #[rstest]
+    #[case("feature", 6, "featu…")]
+    #[case("fix", 6, "fix")]
+    #[case("bugfix", 6, "bugfix")]
+    fn test_abbreviate(#[case] text: &str, #[case] max: usize, #[case] exp: &str) {
+        assert_eq!(exp, abbreviate(text, max));
+    }
+
+    #[test]
+    fn test_transform_conflict() {
+        let conflict = Conflict::Text {
+            file: "dir/file.txt".into(),
+            left: Some("dir/file.txt.merge-left.r1".into()),
+            right: None,
+            working: Some("dir/file.txt.working".into()),
+        };
+        assert_eq!(
+            vec![
+                Line::raw("dir/file.txt").style(Color::LightMagenta),
+                Line::raw("dir/file....").style(Color::DarkGray),
+                Line::raw("dir/file....").style(Color::DarkGray),
+                Line::raw(""),
+            ],
+            transform_conflict(&conflict, 12)
+        );
+    }
 }
@@ -0,0 +1,63 @@
+/// The last change made to the working copy, with what's needed to reverse it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Undo {
+    /// Reversed by reverting, which unschedules the additions but keeps the files
+    Add(Vec<String>),
+    /// Reversed by reverting to bring the files back, then reapplying their local changes
+    Delete {
+        paths: Vec<String>,
+        changes: LocalChanges,
+    },
+    /// Reversed by reapplying the local changes that were reverted
+    Revert {
+        paths: Vec<String>,
+        changes: LocalChanges,
+    },
+    /// Reversed by unlocking
+    Lock(Vec<String>),
+    /// Reversed by writing back the file's contents, or removing it if it was missing
+    Restore {
+        path: String,
+        previous: Option<String>,
+    },
+}
+
+/// The local changes to paths, saved before they're deleted or reverted
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalChanges {
+    /// Their diff, for 'svn patch' to reapply
+    pub patch: String,
+    /// The contents of the files the diff can't put back, e.g. binary files
+    pub backups: Vec<(String, Vec<u8>)>,
+}
+
+impl Undo {
+    /// What was done, e.g. "revert of 3 files"
+    pub fn description(&self) -> String {
+        let (action, paths) = match self {
+            Undo::Add(paths) => ("add", paths),
+            Undo::Delete { paths, .. } => ("delete", paths),
+            Undo::Revert { paths, .. } => ("revert", paths),
+            Undo::Lock(paths) => ("lock", paths),
+            Undo::Restore { path, .. } => return format!("restore of {path}"),
+        };
+        match paths.as_slice() {
+            [path] => format!("{action} of {path}"),
+            paths => format!("{action} of {} files", paths.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(Undo::Add(vec!["a.txt".into()]), "add of a.txt")]
+    #[case(Undo::Revert { paths: vec!["a.txt".into(), "b.txt".into()], changes: LocalChanges::default() }, "revert of 2 files")]
+    #[case(Undo::Restore { path: "a.txt".into(), previous: None }, "restore of a.txt")]
+    fn test_description(#[case] undo: Undo, #[case] exp: &str) {
+        assert_eq!(exp, undo.description());
+    }
+}
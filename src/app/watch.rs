@@ -0,0 +1,179 @@
+use super::{
+    App, AppState,
+    grouping::{Row, section_title},
+    ui::state_style,
+};
+use crate::{
+    config::GroupBy,
+    event::{AppEvent, Event, EventHandler},
+    svn::{Conflict, StatusEntry},
+};
+use crossterm::{
+    ExecutableCommand,
+    cursor::MoveTo,
+    style::{Attribute, ContentStyle},
+    terminal::{Clear, ClearType},
+};
+use ratatui::style::{Color, Modifier, Style};
+use std::io::Write;
+
+impl App {
+    /// Prints the status to stdout and reprints it whenever it changes, refreshing as often
+    /// as the TUI does, until interrupted
+    pub fn watch(mut self) -> color_eyre::Result<()> {
+        self.start_headless()?;
+        let mut shown = String::new();
+        loop {
+            self.update_svn_status();
+            let mut errors = vec![];
+            while let Some(event) = self.events.try_next() {
+                if let Event::App(AppEvent::Message(message)) = event {
+                    errors.push(message);
+                }
+            }
+            let text = self.watch_text(&errors, true);
+            if text != shown {
+                let mut stdout = std::io::stdout();
+                stdout.execute(MoveTo(0, 0))?;
+                stdout.execute(Clear(ClearType::All))?;
+                stdout.write_all(text.as_bytes())?;
+                stdout.flush()?;
+                shown = text;
+            }
+            std::thread::sleep(self.config.refresh_interval);
+        }
+    }
+
+    /// Opens the working copy without a terminal, for printing its status instead
+    pub(super) fn start_headless(&mut self) -> color_eyre::Result<()> {
+        self.events = EventHandler::headless();
+        self.start();
+        if self.svn_missing {
+            return Err(color_eyre::eyre::eyre!("svn not found, is it on the PATH?"));
+        }
+        if let Some(reason) = &self.not_working_copy {
+            return Err(color_eyre::eyre::eyre!("not a working copy: {reason}"));
+        }
+        if self.state == AppState::Workspaces {
+            return Err(color_eyre::eyre::eyre!(
+                "found nested working copies, run it in the one to show"
+            ));
+        }
+        Ok(())
+    }
+
+    /// The branch, the errors from the refresh, the changes grouped like the changes list and
+    /// the files of each conflict, coloured with ANSI escapes if `colour`
+    fn watch_text(&self, errors: &[String], colour: bool) -> String {
+        let paint = |text: &str, style: Style| match colour {
+            true => ansi_style(style).apply(text).to_string(),
+            false => text.to_string(),
+        };
+        let mut lines = vec![format!(
+            "{} ({})",
+            paint(&self.current_branch, Color::Cyan.into()),
+            self.cwd.display()
+        )];
+        for error in errors {
+            lines.push(paint(error, Color::Red.into()));
+        }
+        lines.push(String::new());
+        let indent = match self.config.group_by {
+            GroupBy::None => "",
+            GroupBy::Directory | GroupBy::Tree | GroupBy::State => "  ",
+        };
+        let rows = self.rows();
+        if rows.is_empty() {
+            lines.push(paint("no changes", Color::DarkGray.into()));
+        }
+        for row in rows {
+            lines.push(match row {
+                Row::Favorites { count } => format!("favorites ({count})"),
+                Row::Header {
+                    dir, count, depth, ..
+                } => format!("{}{}/ ({count})", "  ".repeat(depth), dir.display()),
+                Row::Section { state, count, .. } => {
+                    format!("{} ({count})", section_title(state))
+                }
+                Row::Change(StatusEntry { state, path, .. }) => format!(
+                    "{indent}{}  {}",
+                    paint(&state.to_string(), state_style(*state, self.config.theme)),
+                    path.display()
+                ),
+            });
+        }
+        let conflicts = self.file_list.conflicts();
+        if !conflicts.is_empty() {
+            lines.push(String::new());
+            lines.push(paint(
+                &format!("Conflicts ({})", conflicts.len()),
+                Color::LightMagenta.into(),
+            ));
+        }
+        for Conflict::Text {
+            file,
+            left,
+            right,
+            working,
+        } in &conflicts
+        {
+            lines.push(format!("  {}", file.display()));
+            for part in [left, working, right].into_iter().flatten() {
+                lines.push(paint(
+                    &format!("    {}", part.display()),
+                    Color::DarkGray.into(),
+                ));
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+/// The ratatui style as the terminal's escape codes
+fn ansi_style(style: Style) -> ContentStyle {
+    let mut ansi = ContentStyle {
+        foreground_color: style.fg.map(Into::into),
+        background_color: style.bg.map(Into::into),
+        ..ContentStyle::default()
+    };
+    for (modifier, attribute) in [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::RAPID_BLINK, Attribute::RapidBlink),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            ansi.attributes.set(attribute);
+        }
+    }
+    ansi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svn::FileList;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_watch_text() {
+        let mut a = App::new();
+        a.current_branch = "trunk".into();
+        a.cwd = PathBuf::from("/work/repo");
+        a.file_list = FileList::from_svn_status("M       src/a.rs\nC       src/b.rs\n").unwrap();
+        a.config.group_by = GroupBy::Directory;
+        assert_eq!(
+            "trunk (/work/repo)\n\nsrc/ (2)\n  M  src/a.rs\n  C  src/b.rs\n\nConflicts (1)\n  src/b.rs\n",
+            a.watch_text(&[], false)
+        );
+        let coloured = a.watch_text(&[], true);
+        assert!(coloured.contains("\x1b["), "{coloured:?}");
+        a.file_list = FileList::empty();
+        let errors = ["svn: E170013: Unable to connect".to_string()];
+        assert_eq!(
+            "trunk (/work/repo)\nsvn: E170013: Unable to connect\n\nno changes\n",
+            a.watch_text(&errors, false)
+        );
+    }
+}
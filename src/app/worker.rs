@@ -0,0 +1,114 @@
+use super::{
+    action::{Action, PendingFix},
+    output::Operation,
+};
+use std::collections::VecDeque;
+
+/// Something that runs svn, waiting its turn if another command is already running
+#[derive(Debug, Clone, PartialEq)]
+pub enum Job {
+    /// A long-running command whose output is shown as it goes
+    Operation(Operation),
+    /// An action on the paths, performed as though they were selected
+    Action { action: Action, paths: Vec<String> },
+    /// Doing what a failed action needed first, then performing it again
+    Fix(PendingFix),
+    /// Exporting the path to the destination
+    Export { path: String, dest: String },
+    /// Committing the hunks picked on the hunks screen of the path
+    CommitHunks { path: String, message: String },
+}
+
+impl Job {
+    pub fn describe(&self) -> String {
+        match self {
+            Job::Operation(operation) => operation.title().to_lowercase(),
+            Job::Action { action, paths } => {
+                let label = action.label().to_lowercase();
+                match paths.as_slice() {
+                    [path] => format!("{label} {path}"),
+                    _ => format!("{label} {} paths", paths.len()),
+                }
+            }
+            Job::Fix(fix) => format!("{} and retry", fix.precondition.label()),
+            Job::Export { path, .. } => format!("export {path}"),
+            Job::CommitHunks { path, .. } => format!("commit hunks of {path}"),
+        }
+    }
+}
+
+/// Runs the jobs one at a time, so that svn doesn't find the working copy locked by another
+/// of its commands. Only operations run in the background; while one does, the jobs asked for
+/// are queued to run after it instead of blocking.
+#[derive(Debug, Default)]
+pub struct Worker {
+    running: Option<Job>,
+    queue: VecDeque<Job>,
+}
+
+impl Worker {
+    pub fn is_busy(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Records the job as running in the background until [`Worker::finished`] is called
+    pub fn started(&mut self, job: Job) {
+        self.running = Some(job);
+    }
+
+    /// Queues the job behind the running one, returning how many are waiting with it
+    pub fn queue(&mut self, job: Job) -> usize {
+        self.queue.push_back(job);
+        self.queue.len()
+    }
+
+    /// Marks the running job finished, returning the next one to run
+    pub fn finished(&mut self) -> Option<Job> {
+        self.running = None;
+        self.queue.pop_front()
+    }
+
+    /// What's running and how many jobs are waiting, for the status bar
+    pub fn status(&self) -> Option<String> {
+        let running = self.running.as_ref()?.describe();
+        Some(match self.queue.len() {
+            0 => format!("running {running}"),
+            n => format!("running {running}, {n} queued"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue() {
+        let mut worker = Worker::default();
+        assert_eq!(None, worker.status());
+        worker.started(Job::Operation(Operation::Update {
+            revision: "HEAD".into(),
+        }));
+        assert!(worker.is_busy());
+        assert_eq!(Some("running update".to_string()), worker.status());
+        let add = Job::Action {
+            action: Action::Add,
+            paths: vec!["a.txt".into()],
+        };
+        let revert = Job::Action {
+            action: Action::Revert,
+            paths: vec!["b.txt".into(), "c.txt".into()],
+        };
+        assert_eq!(1, worker.queue(add.clone()));
+        assert_eq!(2, worker.queue(revert.clone()));
+        assert_eq!(
+            Some("running update, 2 queued".to_string()),
+            worker.status()
+        );
+        assert_eq!("revert 2 paths", revert.describe());
+        assert_eq!(Some(add), worker.finished());
+        assert!(!worker.is_busy());
+        assert_eq!(Some(revert), worker.finished());
+        assert_eq!(None, worker.finished());
+    }
+}
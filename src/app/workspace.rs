@@ -0,0 +1,73 @@
+use crate::{svn::state::State, vcs};
+use std::path::{Path, PathBuf};
+
+/// A working copy from the `workspaces` setting, as shown on the workspaces dashboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceSummary {
+    pub path: PathBuf,
+    pub branch: String,
+    /// How many changes there are of each state, see [`crate::svn::FileList::state_counts`]
+    pub counts: Vec<(State, usize)>,
+    /// Why the branch or status couldn't be read, if they couldn't
+    pub error: Option<String>,
+    /// Whether the branch and status are still being read in the background
+    pub loading: bool,
+}
+
+impl WorkspaceSummary {
+    /// A placeholder for the working copy at the path until it's been loaded
+    pub fn loading(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            branch: String::new(),
+            counts: vec![],
+            error: None,
+            loading: true,
+        }
+    }
+
+    /// Reads the branch and status of the working copy at the path
+    pub fn load(path: &Path) -> Self {
+        let backend = vcs::detect_backend(path);
+        let summary = backend
+            .branch_name(path)
+            .and_then(|branch| Ok((branch, backend.status(path)?.state_counts())));
+        match summary {
+            Ok((branch, counts)) => Self {
+                path: path.to_path_buf(),
+                branch,
+                counts,
+                error: None,
+                loading: false,
+            },
+            Err(e) => Self {
+                path: path.to_path_buf(),
+                branch: String::new(),
+                counts: vec![],
+                error: Some(e.to_string()),
+                loading: false,
+            },
+        }
+    }
+
+    pub fn total_changes(&self) -> usize {
+        self.counts.iter().map(|(_, count)| count).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_changes() {
+        let summary = WorkspaceSummary {
+            path: PathBuf::from("component"),
+            branch: "trunk".into(),
+            counts: vec![(State::Modified, 3), (State::Unversioned, 2)],
+            error: None,
+            loading: false,
+        };
+        assert_eq!(5, summary.total_changes());
+    }
+}
@@ -1,18 +1,196 @@
-use std::process::Command;
+use std::{path::Path, time::Duration};
 
 pub struct CmdResult(bool, String, String);
 
+/// An svn error code such as `E155007`, as printed at the start of svn's error messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SvnErrorCode(pub u32);
+
+impl std::fmt::Display for SvnErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "E{:06}", self.0)
+    }
+}
+
 impl CmdResult {
     pub fn success(&self) -> bool {
         self.0
     }
 
+    /// The svn error codes found in the stderr, in the order they were printed
+    pub fn error_codes(&self) -> Vec<SvnErrorCode> {
+        parse_error_codes(&self.2)
+    }
+
+    pub fn has_error_code(&self, code: SvnErrorCode) -> bool {
+        self.error_codes().contains(&code)
+    }
+
+    /// Whether the command failed in a way that might succeed if tried again, e.g. a dropped
+    /// connection or a timeout talking to the repository
+    pub fn is_transient_failure(&self) -> bool {
+        if self.success() {
+            return false;
+        }
+        is_transient_error(&self.2)
+    }
+
+    /// What needs doing before the command can succeed, if it failed for a fixable reason
+    pub fn unmet_precondition(&self) -> Option<Precondition> {
+        match self.success() {
+            true => None,
+            false => unmet_precondition(&self.2),
+        }
+    }
+
     /// Returns the stdout if the command was succesful, else the stderr
     pub fn output(&self) -> &str {
         if self.success() { &self.1 } else { &self.2 }
     }
 }
 
+/// Network errors that are usually caused by a flaky connection rather than the request itself
+const TRANSIENT_ERROR_CODES: [SvnErrorCode; 4] = [
+    SvnErrorCode(170013), // unable to connect to a repository
+    SvnErrorCode(175002), // connection failed or was reset
+    SvnErrorCode(175012), // connection timed out
+    SvnErrorCode(210002), // network connection closed unexpectedly
+];
+
+/// Whether a command that failed with the stderr might succeed if tried again
+pub fn is_transient_error(stderr: &str) -> bool {
+    let codes = parse_error_codes(stderr);
+    if TRANSIENT_ERROR_CODES
+        .iter()
+        .any(|code| codes.contains(code))
+    {
+        return true;
+    }
+    let stderr = stderr.to_lowercase();
+    ["connection reset", "timed out", "connection refused"]
+        .iter()
+        .any(|msg| stderr.contains(msg))
+}
+
+/// Something that has to be done before a failed command is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precondition {
+    /// The paths need locking, e.g. they have svn:needs-lock
+    Lock,
+    /// The paths are out of date with the repository
+    Update,
+    /// The working copy is locked by an interrupted operation
+    Cleanup,
+}
+
+impl Precondition {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Precondition::Lock => "lock",
+            Precondition::Update => "update",
+            Precondition::Cleanup => "clean up",
+        }
+    }
+}
+
+/// Errors that are fixed by doing something first, then trying again
+const PRECONDITION_ERROR_CODES: [(SvnErrorCode, Precondition); 6] = [
+    (SvnErrorCode(160038), Precondition::Lock), // no lock token for a locked path
+    (SvnErrorCode(155011), Precondition::Update), // out of date, try updating
+    (SvnErrorCode(160028), Precondition::Update), // transaction out of date
+    (SvnErrorCode(170004), Precondition::Update), // item out of date
+    (SvnErrorCode(155004), Precondition::Cleanup), // working copy locked
+    (SvnErrorCode(155037), Precondition::Cleanup), // previous operation not finished
+];
+
+/// What needs doing before a command that failed with the stderr can succeed, if anything
+pub fn unmet_precondition(stderr: &str) -> Option<Precondition> {
+    let codes = parse_error_codes(stderr);
+    PRECONDITION_ERROR_CODES
+        .iter()
+        .find(|(code, _)| codes.contains(code))
+        .map(|(_, precondition)| *precondition)
+}
+
+/// What a command running in the background has done, sent as each line of its output is read
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandProgress {
+    Output(String),
+    /// The command ended, with its stderr if it failed
+    Finished(std::result::Result<(), String>),
+}
+
+/// How many times to retry an operation that failed transiently, and how long to wait first
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub retries: u8,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// The delay before the given retry, doubling each time
+    pub fn delay(&self, retry: u8) -> Duration {
+        self.backoff * 2u32.saturating_pow(retry.saturating_sub(1).into())
+    }
+
+    /// What's shown before the given retry
+    pub fn retry_message(&self, retry: u8) -> String {
+        format!("network error, retrying ({retry}/{})", self.retries)
+    }
+}
+
+/// Runs the command like [`run_command`], retrying it with backoff while it fails transiently,
+/// for commands that talk to the repository. It sleeps between tries, so it's for threads
+/// other than the event loop's.
+pub fn run_command_retrying(
+    cmd: &str,
+    args: &[&str],
+    policy: &RetryPolicy,
+) -> std::result::Result<CmdResult, std::io::Error> {
+    with_retries(
+        policy,
+        || run_command(cmd, args),
+        |res| matches!(res, Ok(res) if res.is_transient_failure()),
+        |_| {},
+    )
+}
+
+/// Runs `op`, retrying it with backoff while `is_transient` says the result is worth retrying.
+/// `on_retry` is called with the retry number before each retry.
+pub fn with_retries<T>(
+    policy: &RetryPolicy,
+    mut op: impl FnMut() -> T,
+    is_transient: impl Fn(&T) -> bool,
+    mut on_retry: impl FnMut(u8),
+) -> T {
+    let mut result = op();
+    for retry in 1..=policy.retries {
+        if !is_transient(&result) {
+            break;
+        }
+        on_retry(retry);
+        std::thread::sleep(policy.delay(retry));
+        result = op();
+    }
+    result
+}
+
+/// Finds every `E` followed by exactly 6 digits that isn't part of a larger word
+pub fn parse_error_codes(stderr: &str) -> Vec<SvnErrorCode> {
+    let bytes = stderr.as_bytes();
+    let mut codes = vec![];
+    for (i, _) in stderr.match_indices('E') {
+        let starts_word = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        let digits = &bytes[i + 1..bytes.len().min(i + 7)];
+        let ends_word = bytes.get(i + 7).is_none_or(|b| !b.is_ascii_alphanumeric());
+        if starts_word && digits.len() == 6 && digits.iter().all(u8::is_ascii_digit) && ends_word {
+            let code = stderr[i + 1..i + 7].parse().expect("checked all digits");
+            codes.push(SvnErrorCode(code));
+        }
+    }
+    codes
+}
+
 impl From<std::process::Output> for CmdResult {
     fn from(value: std::process::Output) -> Self {
         Self(
@@ -23,16 +201,268 @@ impl From<std::process::Output> for CmdResult {
     }
 }
 
+fn execute(cmd: &str, args: &[&str]) -> std::result::Result<CmdResult, std::io::Error> {
+    let mut cmd = std::process::Command::new(cmd);
+    Ok(cmd.args(args).output()?.into())
+}
+
+/// Pipes the text into the pager command, e.g. "less -R", which takes over the terminal until
+/// the user quits it
+pub fn run_pager(pager: &str, text: &str) -> std::result::Result<(), std::io::Error> {
+    use std::io::Write;
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or("less");
+    let mut child = std::process::Command::new(program)
+        .args(parts)
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may quit before reading everything, which isn't an error
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Runs the editor command, e.g. "vim" or "code --wait", on the file, which takes over the
+/// terminal until the user quits it
+pub fn run_editor(editor: &str, path: &Path) -> std::result::Result<(), std::io::Error> {
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    std::process::Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()?;
+    Ok(())
+}
+
+/// The platform's shell and the flag it runs a command line with, e.g. `sh -c`
+pub fn shell() -> (&'static str, &'static str) {
+    match cfg!(target_os = "windows") {
+        true => ("cmd", "/C"),
+        false => ("sh", "-c"),
+    }
+}
+
+/// Quotes the text as a single word for [`shell`]
+pub fn shell_quote(text: &str) -> String {
+    match cfg!(target_os = "windows") {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => format!("'{}'", text.replace('\'', "'\\''")),
+    }
+}
+
+/// Starts the command in the platform's shell without waiting for it, e.g. for user hooks
+pub fn spawn_shell(command: &str, env: &[(&str, &str)]) -> std::result::Result<(), std::io::Error> {
+    let (shell, flag) = shell();
+    let child = std::process::Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .envs(env.iter().copied())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    wait_in_background(child);
+    Ok(())
+}
+
+/// Starts the program without waiting for it, for GUI programs like a file manager whose exit
+/// code says nothing about whether they opened (Windows' explorer exits with 1 when it did)
+pub fn spawn_detached(cmd: &str, args: &[&str]) -> std::result::Result<(), std::io::Error> {
+    let child = std::process::Command::new(cmd)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    wait_in_background(child);
+    Ok(())
+}
+
+/// Waits for the child on a thread of its own so it's reaped when it exits instead of being
+/// left a zombie until svn-util quits
+fn wait_in_background(mut child: std::process::Child) {
+    std::thread::spawn(move || child.wait());
+}
+
+/// Shows a desktop notification with the platform's notifier, without waiting for it
+pub fn notify(title: &str, body: &str) -> std::result::Result<(), std::io::Error> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        let mut cmd = std::process::Command::new("osascript");
+        cmd.args(["-e", &script]);
+        cmd
+    } else if cfg!(target_os = "windows") {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "desktop notifications aren't supported on Windows",
+        ));
+    } else {
+        let mut cmd = std::process::Command::new("notify-send");
+        cmd.args(["--app-name", "svn-util", title, body]);
+        cmd
+    };
+    let child = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    wait_in_background(child);
+    Ok(())
+}
+
+/// Copies the text to the clipboard with the OSC 52 escape sequence, which terminals pass on
+/// to the system clipboard, including over ssh
+pub fn copy_to_clipboard(text: &str) -> std::result::Result<(), std::io::Error> {
+    use std::io::Write;
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => encoded.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+/// Quotes the text as an AppleScript string literal
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn spawn_piped(cmd: &str, args: &[String]) -> std::io::Result<std::process::Child> {
+    std::process::Command::new(cmd)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+}
+
+/// Forwards each line the child prints until it ends, returning its stderr if it failed
+fn read_streaming(
+    mut child: std::process::Child,
+    on_progress: &impl Fn(CommandProgress),
+) -> std::result::Result<(), String> {
+    use std::io::{BufRead, BufReader, Read};
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+            on_progress(CommandProgress::Output(line));
+        }
+    }
+    let mut error = String::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let _ = stderr.read_to_string(&mut error);
+    }
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => Err(error.trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Starts the command without waiting for it, calling `on_progress` from another thread for
+/// every line it prints and once more when it ends. It's run again, after saying so in the
+/// output, while it fails transiently.
+fn spawn_streaming(
+    cmd: &str,
+    args: &[&str],
+    policy: &RetryPolicy,
+    on_progress: impl Fn(CommandProgress) + Send + 'static,
+) -> std::result::Result<(), std::io::Error> {
+    let cmd = cmd.to_string();
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let mut first = Some(spawn_piped(&cmd, &args)?);
+    let policy = *policy;
+    std::thread::spawn(move || {
+        let result = with_retries(
+            &policy,
+            || match first.take().map_or_else(|| spawn_piped(&cmd, &args), Ok) {
+                Ok(child) => read_streaming(child, &on_progress),
+                Err(e) => Err(e.to_string()),
+            },
+            |res| matches!(res, Err(e) if is_transient_error(e)),
+            |retry| on_progress(CommandProgress::Output(policy.retry_message(retry))),
+        );
+        on_progress(CommandProgress::Finished(result));
+    });
+    Ok(())
+}
+
 // The below code allows run_command to be mocked based on the arguments passed to it
 // TODO this could be good practice for a macro
 #[cfg(not(test))]
 pub fn run_command(cmd: &str, args: &[&str]) -> std::result::Result<CmdResult, std::io::Error> {
-    let mut cmd = Command::new(cmd);
-    Ok(cmd.args(args).output()?.into())
+    execute(cmd, args)
+}
+
+#[cfg(not(test))]
+pub fn stream_command(
+    cmd: &str,
+    args: &[&str],
+    policy: &RetryPolicy,
+    on_progress: impl Fn(CommandProgress) + Send + 'static,
+) -> std::result::Result<(), std::io::Error> {
+    spawn_streaming(cmd, args, policy, on_progress)
+}
+
+/// Sends the mocked output of [`run_command`] all at once, from this thread
+#[cfg(test)]
+pub fn stream_command(
+    cmd: &str,
+    args: &[&str],
+    policy: &RetryPolicy,
+    on_progress: impl Fn(CommandProgress) + Send + 'static,
+) -> std::result::Result<(), std::io::Error> {
+    if USE_REAL_COMMANDS.with(|real| real.get()) {
+        return spawn_streaming(cmd, args, policy, on_progress);
+    }
+    let res = with_retries(
+        policy,
+        || run_command(cmd, args),
+        |res| matches!(res, Ok(res) if res.is_transient_failure()),
+        |retry| on_progress(CommandProgress::Output(policy.retry_message(retry))),
+    )?;
+    if res.success() {
+        for line in res.1.lines() {
+            on_progress(CommandProgress::Output(line.to_string()));
+        }
+        on_progress(CommandProgress::Finished(Ok(())));
+    } else {
+        on_progress(CommandProgress::Finished(Err(res.2.trim().to_string())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Makes run_command run the real commands on this thread, for the svn integration tests
+    pub static USE_REAL_COMMANDS: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
 }
 
 #[cfg(test)]
 pub fn run_command(cmd: &str, args: &[&str]) -> std::result::Result<CmdResult, std::io::Error> {
+    if USE_REAL_COMMANDS.with(|real| real.get()) {
+        return execute(cmd, args);
+    }
     match (cmd, args) {
         ("svn", args) => match args {
             ["info", "output_missing_URL"] => Ok(CmdResult(true, "info".into(), "".into())),
@@ -40,11 +470,256 @@ pub fn run_command(cmd: &str, args: &[&str]) -> std::result::Result<CmdResult, s
             ["info", "nested/branch_name"] => {
                 Ok(CmdResult(true, "URL: nested/branch_name".into(), "".into()))
             }
+            ["info", "not_a_working_copy"] => Ok(CmdResult(
+                false,
+                "".into(),
+                "svn: E155007: '/not_a_working_copy' is not a working copy\n".into(),
+            )),
+            ["status", "--depth", "infinity", "changed_by_someone_else"] => {
+                Ok(CmdResult(true, "D       a.txt\n".into(), "".into()))
+            }
+            ["status", "--show-updates", "--non-interactive", "changed_by_someone_else"] => {
+                Ok(CmdResult(
+                    true,
+                    "M       *       42   a.txt\n        *       42   b.txt\nStatus against revision:     45\n"
+                        .into(),
+                    "".into(),
+                ))
+            }
+            ["status", "--depth", "infinity", "conflicted_in_subdir"] => Ok(CmdResult(
+                true,
+                "M       docs/x.md\nC       src/app/ui.rs\nM       README.md\n".into(),
+                "".into(),
+            )),
+            ["commit", "-m", "Fix the thing", "--keep-locks", "a.txt"] => {
+                Ok(CmdResult(true, "Committed revision 46.\n".into(), "".into()))
+            }
+            ["commit", "-m", "Fix the thing", "--targets", _] => Ok(CmdResult(
+                false,
+                "".into(),
+                "svn: E155011: File 'a.txt' is out of date\n".into(),
+            )),
+            ["status", "--depth", "immediates", path] if path.ends_with("deep_wc") => Ok(CmdResult(
+                true,
+                "M       README.md\n M      src\n".into(),
+                "".into(),
+            )),
+            ["status", "--depth", "infinity", path] if path.ends_with("deep_wc/src") => Ok(CmdResult(
+                true,
+                " M      src\nM       src/app/ui.rs\n".into(),
+                "".into(),
+            )),
+            ["diff", "--git", path] if path.ends_with("image.png") => Ok(CmdResult(
+                true,
+                format!(
+                    "Index: {path}\n===================================================================\nCannot display: file marked as a binary type.\nsvn:mime-type = image/png\n"
+                ),
+                "".into(),
+            )),
+            ["status", "new.txt"] => Ok(CmdResult(true, "A       new.txt\n".into(), "".into())),
+            ["add", "new.txt"]
+            | ["revert", "new.txt"]
+            | ["update", "--non-interactive", "a.txt"]
+            | ["export", "--non-interactive", "a.txt", "../a.txt-export"] => {
+                Ok(CmdResult(true, "".into(), "".into()))
+            }
+            ["lock", "flaky.txt"] => Ok(CmdResult(
+                false,
+                "".into(),
+                "svn: E175002: Connection reset\n".into(),
+            )),
+            ["update", "--non-interactive", "-r", "42"] => Ok(CmdResult(
+                false,
+                "".into(),
+                "svn: E175002: Connection reset\n".into(),
+            )),
+            ["update", "--non-interactive", "-r", "41"] => Ok(CmdResult(
+                true,
+                "Updating '.':\nU    a.txt\nUpdated to revision 41.\n".into(),
+                "".into(),
+            )),
+            ["--version", "--quiet"] => Ok(CmdResult(true, "1.14.3\n".into(), "".into())),
+            ["info", "--show-item", "url", "changed_by_someone_else"] => Ok(CmdResult(
+                true,
+                "https://svn.example.com/repo/trunk\n".into(),
+                "".into(),
+            )),
+            ["info", "--show-item", "revision", "changed_by_someone_else"] => {
+                Ok(CmdResult(true, "42\n".into(), "".into()))
+            }
+            [
+                "log",
+                "--xml",
+                "--non-interactive",
+                "--quiet",
+                "-r",
+                "BASE:HEAD",
+                "changed_by_someone_else",
+            ] => Ok(CmdResult(
+                true,
+                "<log>\n<logentry revision=\"42\">\n<author>alice</author>\n</logentry>\n<logentry revision=\"44\">\n<author>bob</author>\n</logentry>\n<logentry revision=\"45\">\n<author>alice</author>\n</logentry>\n</log>\n".into(),
+                "".into(),
+            )),
+            ["log", "--xml", "--non-interactive", "--limit", "1", "changed_by_someone_else"] => Ok(CmdResult(
+                true,
+                "<log>\n<logentry revision=\"42\">\n<author>alice</author>\n<msg>Fix the build\n\nIt was broken.</msg>\n</logentry>\n</log>\n".into(),
+                "".into(),
+            )),
+            ["log", "--xml", "--non-interactive", "--limit", "100", "a.txt"] => Ok(CmdResult(
+                true,
+                "<log>\n<logentry revision=\"12\">\n<author>alice</author>\n<msg>Fix a</msg>\n</logentry>\n</log>\n".into(),
+                "".into(),
+            )),
+            ["cat", "-r", "12", path] if path.ends_with("restored.txt") => {
+                Ok(CmdResult(true, "old contents\n".into(), "".into()))
+            }
+            ["blame", "--non-interactive", "a.txt"] => Ok(CmdResult(
+                true,
+                "     3      alice old line\n    12        bob new line\n".into(),
+                "".into(),
+            )),
+            ["diff", "-c", "12", "a.txt"] => Ok(CmdResult(
+                true,
+                "Index: a.txt\n-old\n+new\n".into(),
+                "".into(),
+            )),
             ["info", "something_bad_happened"] => {
                 Ok(CmdResult(false, "".into(), "unknown issue with svn".into()))
             }
             _ => panic!("invalid case: {cmd} {args:?}"),
         },
+        (shell, [flag, "make test"]) if (shell, *flag) == self::shell() => {
+            Ok(CmdResult(true, "all passed\n".into(), "".into()))
+        }
         _ => panic!("not a valid case"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("plain", "\"plain\"")]
+    #[case("say \"hi\"", "\"say \\\"hi\\\"\"")]
+    #[case("C:\\dir", "\"C:\\\\dir\"")]
+    fn test_applescript_string(#[case] text: &str, #[case] exp: &str) {
+        assert_eq!(exp, applescript_string(text));
+    }
+
+    #[rstest]
+    #[case("", "")]
+    #[case("f", "Zg==")]
+    #[case("fo", "Zm8=")]
+    #[case("foo", "Zm9v")]
+    #[case("foobar", "Zm9vYmFy")]
+    fn test_base64(#[case] text: &str, #[case] exp: &str) {
+        assert_eq!(exp, base64(text.as_bytes()));
+    }
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("svn: E155007: '/tmp' is not a working copy", vec![SvnErrorCode(155007)])]
+    #[case(
+        "svn: E170013: Unable to connect\nsvn: E175002: Connection refused",
+        vec![SvnErrorCode(170013), SvnErrorCode(175002)]
+    )]
+    #[case("E1234567 is too long, AE123456 is inside a word, E12345 is too short", vec![])]
+    #[case("svn: warning: W155010: not found", vec![])]
+    fn test_parse_error_codes(#[case] stderr: &str, #[case] exp: Vec<SvnErrorCode>) {
+        assert_eq!(exp, parse_error_codes(stderr));
+    }
+
+    #[rstest]
+    #[case(CmdResult(true, "".into(), "".into()), false)]
+    #[case(CmdResult(false, "".into(), "svn: E175002: Connection reset".into()), true)]
+    #[case(CmdResult(false, "".into(), "svn: E170013: Unable to connect".into()), true)]
+    #[case(CmdResult(false, "".into(), "Operation timed out".into()), true)]
+    #[case(CmdResult(false, "".into(), "svn: E155007: not a working copy".into()), false)]
+    fn test_is_transient_failure(#[case] res: CmdResult, #[case] exp: bool) {
+        assert_eq!(exp, res.is_transient_failure());
+    }
+
+    #[rstest]
+    #[case(CmdResult(true, "".into(), "".into()), None)]
+    #[case(CmdResult(false, "".into(), "svn: E155011: File 'a.txt' is out of date".into()), Some(Precondition::Update))]
+    #[case(CmdResult(false, "".into(), "svn: E155004: Run 'svn cleanup'".into()), Some(Precondition::Cleanup))]
+    #[case(CmdResult(false, "".into(), "svn: E160038: no lock token".into()), Some(Precondition::Lock))]
+    #[case(CmdResult(false, "".into(), "svn: E155007: not a working copy".into()), None)]
+    fn test_unmet_precondition(#[case] res: CmdResult, #[case] exp: Option<Precondition>) {
+        assert_eq!(exp, res.unmet_precondition());
+    }
+
+    #[rstest]
+    #[case(2, vec![true, true, true], 3, vec![1, 2])]
+    #[case(2, vec![true, false, true], 2, vec![1])]
+    #[case(2, vec![false], 1, vec![])]
+    #[case(0, vec![true], 1, vec![])]
+    fn test_with_retries(
+        #[case] retries: u8,
+        #[case] transient: Vec<bool>,
+        #[case] exp_attempts: usize,
+        #[case] exp_retries: Vec<u8>,
+    ) {
+        let policy = RetryPolicy {
+            retries,
+            backoff: Duration::ZERO,
+        };
+        let mut attempts = 0;
+        let mut notified = vec![];
+        with_retries(
+            &policy,
+            || {
+                attempts += 1;
+                transient[attempts - 1]
+            },
+            |transient| *transient,
+            |retry| notified.push(retry),
+        );
+        assert_eq!(exp_attempts, attempts);
+        assert_eq!(exp_retries, notified);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            retries: 3,
+            backoff: Duration::from_millis(100),
+        };
+        assert_eq!(Duration::from_millis(100), policy.delay(1));
+        assert_eq!(Duration::from_millis(200), policy.delay(2));
+        assert_eq!(Duration::from_millis(400), policy.delay(3));
+    }
+
+    #[test]
+    fn test_stream_command_retries() {
+        let policy = RetryPolicy {
+            retries: 2,
+            backoff: Duration::ZERO,
+        };
+        let progress = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let sent = progress.clone();
+        stream_command(
+            "svn",
+            &["update", "--non-interactive", "-r", "42"],
+            &policy,
+            move |p| sent.lock().unwrap().push(p),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                CommandProgress::Output("network error, retrying (1/2)".into()),
+                CommandProgress::Output("network error, retrying (2/2)".into()),
+                CommandProgress::Finished(Err("svn: E175002: Connection reset".into())),
+            ],
+            *progress.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_error_code_display() {
+        assert_eq!("E155007", SvnErrorCode(155007).to_string());
+        assert_eq!("E000042", SvnErrorCode(42).to_string());
+    }
+}
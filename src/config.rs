@@ -1,38 +1,478 @@
-use clap::Parser;
-use std::io::Read;
+use crate::{command::RetryPolicy, svn};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    style::Color,
+};
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-#[derive(Debug)]
+/// Per repository overrides, read from the root of the working copy
+pub const REPO_CONFIG_FILE: &str = ".svn-util.toml";
+
+#[derive(Debug, PartialEq)]
 pub struct Config {
-    pub svn_status_timeout: u8,
+    /// How long to wait between automatic 'svn status' refreshes
+    pub refresh_interval: Duration,
+    /// Refresh every `refresh_interval`, otherwise only on focus gain or when asked to
+    pub auto_refresh: bool,
+    /// Read the branch and status again when the terminal gains focus
+    pub refresh_on_focus: bool,
+    /// Unselect the selected change when the terminal loses focus
+    pub clear_selection_on_blur: bool,
+    /// How long to wait between asking the repository whether the working copy is behind it,
+    /// zero to never ask
+    pub incoming_check_interval: Duration,
+    /// Indent entries in the changes list by their directory depth
+    pub indent_by_depth: bool,
+    /// Show Nerd Font file type icons and state glyphs instead of the state letters
+    pub nerd_font_icons: bool,
+    /// The command used to open directories, falls back to the platform default if unset
+    pub file_manager: Option<String>,
+    /// How many times to retry svn commands that fail with a transient network error
+    pub network_retries: u8,
+    /// Load the initial status one top-level directory at a time so huge working copies
+    /// show results as soon as possible
+    pub incremental_status: bool,
+    /// How deep 'svn status' looks, shallower for gigantic trees, with subtrees drilled into
+    /// at full depth with 'X'
+    pub status_depth: StatusDepth,
+    /// Changelists left out of select-all, so they aren't committed by accident
+    pub excluded_changelists: Vec<String>,
+    /// Whether changes in the excluded changelists start hidden
+    pub hide_excluded_changelists: bool,
+    /// Whether entries without text changes, e.g. a directory with only property changes,
+    /// start hidden
+    pub hide_property_only: bool,
+    /// Globs of the paths left out of the changes list, e.g. "*.orig" anywhere or "target/**"
+    /// for everything under a directory, without touching svn:ignore
+    pub hide_patterns: Vec<String>,
+    /// Working copies shown together on the workspaces dashboard
+    pub workspaces: Vec<PathBuf>,
+    /// Working copies to jump between from the popup opened with 'b'
+    pub bookmarks: Vec<PathBuf>,
+    /// Where the branches offered by the branch switcher are, see [`svn::list_branches`]
+    pub branch_roots: Vec<String>,
+    /// The command diffs are shown with, falls back to `$PAGER` and then `less -R` if unset
+    pub pager: Option<String>,
+    /// The command files are opened with in the terminal, a floating zellij pane if unset
+    pub editor: Option<String>,
+    /// How the changes list is grouped, cycled with 'v'
+    pub group_by: GroupBy,
+    /// How much of the width the conflicts pane takes, or of the height when stacked, resized
+    /// with '<' and '>'
+    pub conflicts_pane_percent: u16,
+    /// Whether the conflicts pane is beside the changes list or under it, toggled with 'L'
+    pub pane_layout: PaneLayout,
+    /// A shell command run when the branch changes, with `{branch}` and `{previous}` replaced
+    /// by the branch names
+    pub on_branch_change: Option<String>,
+    /// A shell command run after a status refresh that finds the changes have changed
+    pub on_refresh: Option<String>,
+    /// A shell command run after a commit succeeds
+    pub on_commit_success: Option<String>,
+    /// A shell command run when a refresh finds new conflicts
+    pub on_conflict_detected: Option<String>,
+    /// The colours used for each state
+    pub theme: Theme,
+    /// The colours blamed lines are tinted with, from the file's oldest revision to its newest
+    pub blame_gradient: BlameGradient,
+    /// How timestamps are shown, as a strftime format
+    pub date_format: String,
+    /// Show timestamps in UTC rather than the local timezone
+    pub utc_timestamps: bool,
+    /// How the time of the last refresh is shown in the status bar, "relative" for e.g.
+    /// "12s ago" or a strftime format
+    pub last_updated_format: String,
+    /// Send a desktop notification when a refresh finds new conflicts while unfocused
+    pub desktop_notifications: bool,
+    /// Print a line summarising the working copy's state after quitting
+    pub exit_summary: bool,
+    /// Keep the locks on committed files instead of releasing them, the default for the commit
+    /// review which can be toggled there
+    pub keep_locks: bool,
+    /// Resolve new conflicts that are only whitespace or the same change on both sides as soon
+    /// as a refresh finds them, rather than waiting for the auto-resolve action
+    pub auto_resolve_trivial: bool,
+    /// Shell commands run with a key, see [`KeyBinding`]
+    pub custom_keys: HashMap<KeyBinding, String>,
+    /// Run against a throwaway demo repository instead of the current directory
+    pub demo: bool,
+    /// Show only one line with the branch and change counts, for a status bar pane
+    pub minimal: bool,
+    /// Print the status to stdout whenever it changes instead of running the TUI
+    pub watch: bool,
+    /// A repository url to check out into the current directory, then show
+    pub checkout: Option<String>,
+    /// Where to record the terminal events of the session, to replay them later
+    pub record_events: Option<PathBuf>,
+    /// A recording of terminal events to replay without a terminal, printing the final screen
+    pub replay_events: Option<PathBuf>,
+    /// Where to listen for status requests and commands from other programs, e.g. editors
+    pub control_socket: Option<PathBuf>,
+    /// Where to write a report of the status to instead of running the TUI
+    pub export_status: Option<String>,
+    /// The directory svn-util was started in when the config was read from its command line,
+    /// so it can be layered again for another working copy
+    pub started_in: Option<PathBuf>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            svn_status_timeout: 2,
+            refresh_interval: Duration::from_secs(2),
+            auto_refresh: true,
+            refresh_on_focus: true,
+            clear_selection_on_blur: true,
+            incoming_check_interval: Duration::from_secs(300),
+            indent_by_depth: false,
+            nerd_font_icons: false,
+            file_manager: None,
+            network_retries: 2,
+            incremental_status: false,
+            status_depth: StatusDepth::Infinity,
+            excluded_changelists: vec!["ignore-on-commit".into()],
+            hide_excluded_changelists: false,
+            hide_property_only: false,
+            hide_patterns: vec![],
+            workspaces: vec![],
+            bookmarks: vec![],
+            branch_roots: vec!["^/trunk".into(), "^/branches/*".into()],
+            pager: None,
+            editor: None,
+            group_by: GroupBy::None,
+            conflicts_pane_percent: 40,
+            pane_layout: PaneLayout::Horizontal,
+            on_branch_change: None,
+            on_refresh: None,
+            on_commit_success: None,
+            on_conflict_detected: None,
+            theme: Theme::Default,
+            blame_gradient: BlameGradient::default(),
+            date_format: "%Y-%m-%d %H:%M:%S".into(),
+            utc_timestamps: false,
+            last_updated_format: "relative".into(),
+            desktop_notifications: false,
+            exit_summary: false,
+            keep_locks: false,
+            auto_resolve_trivial: false,
+            custom_keys: HashMap::new(),
+            demo: false,
+            minimal: false,
+            watch: false,
+            checkout: None,
+            record_events: None,
+            replay_events: None,
+            control_socket: None,
+            export_status: None,
+            started_in: None,
         }
     }
 }
 
+/// What the command line asked for
+#[derive(Debug)]
+pub enum Launch {
+    /// Show the working copy with the config
+    Run(Box<Config>),
+    /// Print a completion script for the shell
+    Completions(Shell),
+    /// Write the default config to the path
+    InitConfig(PathBuf),
+}
+
+/// The command line's definition, for generating completions from
+pub fn command_line() -> clap::Command {
+    ConfigSource::command()
+}
+
 impl Config {
-    pub fn update_from_env_args(&mut self) {
+    /// Reads the command line, building the config if it asks for the working copy to be shown
+    /// rather than for a script or config file to be written
+    pub fn load() -> Result<Launch, String> {
         let args = ConfigSource::parse();
-        self.update(args);
+        if let Some(SubCommand::Completions { shell }) = args.command {
+            return Ok(Launch::Completions(shell));
+        }
+        if args.init_config {
+            let path = args
+                .config
+                .clone()
+                .or_else(global_config_path)
+                .ok_or("could not find a config directory, pass --config <path>")?;
+            return Ok(Launch::InitConfig(path));
+        }
+        let started_in = std::env::current_dir().unwrap_or_default();
+        let config = Self::from_args(args, &started_in, &started_in)?;
+        Ok(Launch::Run(Box::new(config)))
     }
 
-    pub fn update_from_file(&mut self) -> Result<(), String> {
-        if let Ok(mut file) = std::fs::File::open("settings.toml") {
+    /// Layers the config again from the same command line for the working copy at `cwd`, so
+    /// its `.svn-util.toml` replaces the last one's. `None` if the config wasn't read from the
+    /// command line.
+    pub fn reload(&self, cwd: &Path) -> Option<Result<Self, String>> {
+        let started_in = self.started_in.as_ref()?;
+        Some(
+            ConfigSource::try_parse()
+                .map_err(|e| e.to_string())
+                .and_then(|args| Self::from_args(args, started_in, cwd)),
+        )
+    }
+
+    /// Builds the config from the config files and the command line arguments, with later
+    /// sources taking precedence: the global config, then `settings.toml` in `cwd`, then the
+    /// saved `layout.toml`, then `.svn-util.toml` at the root of the working copy `cwd` is in,
+    /// then `SVN_UTIL_*`
+    /// environment variables, then the command line.
+    /// Passing `--config <path>` replaces the global config and `settings.toml`, a relative
+    /// path being relative to `started_in`.
+    fn from_args(args: ConfigSource, started_in: &Path, cwd: &Path) -> Result<Self, String> {
+        let mut config = Config::default();
+        match &args.config {
+            Some(path) => {
+                let path = started_in.join(path);
+                if !config.update_from_file(&path)? {
+                    return Err(format!("config file not found: {}", path.display()));
+                }
+            }
+            None => {
+                if let Some(path) = global_config_path() {
+                    config.update_from_file(&path)?;
+                }
+                config.update_from_file(&cwd.join("settings.toml"))?;
+            }
+        }
+        if let Some(path) = layout_path() {
+            config.update_from_file(&path)?;
+        }
+        if let Some(root) = svn::find_wc_root(cwd) {
+            config.update_from_file(&root.join(REPO_CONFIG_FILE))?;
+        }
+        config.update(env_overrides(|key| std::env::var(key).ok())?);
+        config.demo = args.demo;
+        config.minimal = args.minimal;
+        config.watch = args.watch;
+        config.checkout = args.checkout.clone();
+        config.record_events = args.record_events.clone();
+        config.replay_events = args.replay_events.clone();
+        config.control_socket = args.control_socket.clone();
+        config.export_status = args.export_status.clone();
+        config.started_in = Some(started_in.to_path_buf());
+        config.update(args);
+        Ok(config)
+    }
+
+    /// Updates the config from a TOML file, returning whether the file existed
+    pub fn update_from_file(&mut self, path: &Path) -> Result<bool, String> {
+        if let Ok(mut file) = std::fs::File::open(path) {
             let mut buf = String::new();
             file.read_to_string(&mut buf).map_err(|e| e.to_string())?;
-            let parsed: ConfigSource = toml::from_str(&buf).map_err(|e| e.to_string())?;
+            let parsed: ConfigSource =
+                toml::from_str(&buf).map_err(|e| format!("{}: {e}", path.display()))?;
             self.update(parsed);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// The command used to open a directory, e.g. "xdg-open" or a user configured file manager
+    pub fn file_manager_command(&self) -> String {
+        match &self.file_manager {
+            Some(cmd) => cmd.clone(),
+            None if cfg!(target_os = "macos") => "open".into(),
+            None if cfg!(target_os = "windows") => "explorer".into(),
+            None => "xdg-open".into(),
+        }
+    }
+
+    /// The command used to page through diffs, e.g. "less -R"
+    pub fn pager_command(&self) -> String {
+        self.pager
+            .clone()
+            .or_else(|| std::env::var("PAGER").ok())
+            .filter(|cmd| !cmd.trim().is_empty())
+            .unwrap_or_else(|| "less -R".into())
+    }
+
+    /// Formats the time with `date_format`, in UTC or local time. An invalid format falls back
+    /// to RFC 3339 rather than failing.
+    pub fn format_timestamp(&self, time: DateTime<Utc>) -> String {
+        self.format_time(&self.date_format, time)
+    }
+
+    fn format_time(&self, format: &str, time: DateTime<Utc>) -> String {
+        use std::fmt::Write;
+        let mut text = String::new();
+        let written = match self.utc_timestamps {
+            true => write!(text, "{}", time.format(format)),
+            false => write!(text, "{}", time.with_timezone(&Local).format(format)),
+        };
+        match written {
+            Ok(()) => text,
+            Err(_) => time.to_rfc3339_opts(SecondsFormat::Secs, self.utc_timestamps),
+        }
+    }
+
+    /// Formats the time of the last refresh with `last_updated_format`, e.g. "12s ago"
+    pub fn format_last_updated(&self, time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        if self.last_updated_format != "relative" {
+            return self.format_time(&self.last_updated_format, time);
+        }
+        let seconds = now.signed_duration_since(time).num_seconds().max(0);
+        match seconds {
+            0..60 => format!("{seconds}s ago"),
+            60..3600 => format!("{}m ago", seconds / 60),
+            3600..86400 => format!("{}h ago", seconds / 3600),
+            _ => format!("{}d ago", seconds / 86400),
+        }
+    }
+
+    /// Saves the pane proportions changed at runtime, so they're kept for next time
+    pub fn save_layout(&self) -> Result<(), String> {
+        let path = layout_path().ok_or("could not find a config directory")?;
+        self.save_layout_to(&path)
+    }
+
+    fn save_layout_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let layout = format!(
+            "# written by svn-util when the panes are resized\nconflicts_pane_percent = {}\npane_layout = \"{}\"\n",
+            self.conflicts_pane_percent,
+            self.pane_layout.label()
+        );
+        std::fs::write(path, layout).map_err(|e| e.to_string())
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.network_retries,
+            backoff: Duration::from_millis(500),
         }
-        Ok(())
     }
 
     fn update(&mut self, args: ConfigSource) {
-        if let Some(n) = args.svn_timeout {
-            self.svn_status_timeout = n;
+        if let Some(interval) = args.refresh_interval {
+            self.refresh_interval = interval.0;
+        }
+        if let Some(auto) = args.auto_refresh {
+            self.auto_refresh = auto;
+        }
+        if args.no_auto_refresh {
+            self.auto_refresh = false;
+        }
+        if let Some(refresh) = args.refresh_on_focus {
+            self.refresh_on_focus = refresh;
+        }
+        if let Some(clear) = args.clear_selection_on_blur {
+            self.clear_selection_on_blur = clear;
+        }
+        if let Some(interval) = args.incoming_check_interval {
+            self.incoming_check_interval = interval.0;
+        }
+        if let Some(indent) = args.indent_by_depth {
+            self.indent_by_depth = indent;
+        }
+        if let Some(icons) = args.nerd_font_icons {
+            self.nerd_font_icons = icons;
+        }
+        if let Some(file_manager) = args.file_manager {
+            self.file_manager = Some(file_manager);
+        }
+        if let Some(retries) = args.network_retries {
+            self.network_retries = retries;
+        }
+        if let Some(incremental) = args.incremental_status {
+            self.incremental_status = incremental;
+        }
+        if let Some(depth) = args.status_depth {
+            self.status_depth = depth;
+        }
+        if let Some(changelists) = args.excluded_changelists {
+            self.excluded_changelists = changelists;
+        }
+        if let Some(hide) = args.hide_excluded_changelists {
+            self.hide_excluded_changelists = hide;
+        }
+        if let Some(hide) = args.hide_property_only {
+            self.hide_property_only = hide;
+        }
+        if let Some(patterns) = args.hide_patterns {
+            self.hide_patterns = patterns;
+        }
+        if let Some(workspaces) = args.workspaces {
+            self.workspaces = workspaces;
+        }
+        if let Some(bookmarks) = args.bookmarks {
+            self.bookmarks = bookmarks;
+        }
+        if let Some(roots) = args.branch_roots {
+            self.branch_roots = roots;
+        }
+        if let Some(pager) = args.pager {
+            self.pager = Some(pager);
+        }
+        if let Some(editor) = args.editor {
+            self.editor = Some(editor);
+        }
+        if let Some(group_by) = args.group_by {
+            self.group_by = group_by;
+        }
+        if let Some(percent) = args.conflicts_pane_percent {
+            self.conflicts_pane_percent = percent.clamp(MIN_PANE_PERCENT, MAX_PANE_PERCENT);
+        }
+        if let Some(layout) = args.pane_layout {
+            self.pane_layout = layout;
+        }
+        if let Some(hook) = args.on_branch_change {
+            self.on_branch_change = Some(hook);
+        }
+        if let Some(hook) = args.on_refresh {
+            self.on_refresh = Some(hook);
+        }
+        if let Some(hook) = args.on_commit_success {
+            self.on_commit_success = Some(hook);
+        }
+        if let Some(hook) = args.on_conflict_detected {
+            self.on_conflict_detected = Some(hook);
+        }
+        if let Some(theme) = args.theme {
+            self.theme = theme;
+        }
+        if let Some(gradient) = args.blame_gradient {
+            self.blame_gradient = gradient;
+        }
+        if let Some(format) = args.date_format {
+            self.date_format = format;
+        }
+        if let Some(utc) = args.utc_timestamps {
+            self.utc_timestamps = utc;
+        }
+        if let Some(format) = args.last_updated_format {
+            self.last_updated_format = format;
+        }
+        if let Some(notify) = args.desktop_notifications {
+            self.desktop_notifications = notify;
+        }
+        if let Some(summary) = args.exit_summary {
+            self.exit_summary = summary;
+        }
+        if let Some(keep_locks) = args.keep_locks {
+            self.keep_locks = keep_locks;
+        }
+        if let Some(auto_resolve) = args.auto_resolve_trivial {
+            self.auto_resolve_trivial = auto_resolve;
+        }
+        if let Some(keys) = args.keys {
+            // bindings for other keys in earlier sources are kept
+            self.custom_keys.extend(keys.custom);
         }
     }
 }
@@ -40,6 +480,1065 @@ impl Config {
 #[derive(Parser, serde::Deserialize)]
 #[command(version, about, long_about = None)]
 struct ConfigSource {
+    #[command(subcommand)]
+    #[serde(skip)]
+    command: Option<SubCommand>,
+    /// Read settings from this file instead of the default locations
     #[arg(short, long)]
-    svn_timeout: Option<u8>,
+    #[serde(skip)]
+    config: Option<PathBuf>,
+    /// Write a commented default config to the config path and exit
+    #[arg(long)]
+    #[serde(skip)]
+    init_config: bool,
+    /// Explore the app in a throwaway repository with example changes and a conflict
+    #[arg(long)]
+    #[serde(skip)]
+    demo: bool,
+    /// Show one line with the branch, the number of changes of each state and whether there
+    /// are conflicts, e.g. for a tmux or zellij status bar pane. Only q to quit and r to
+    /// refresh are bound.
+    #[arg(long)]
+    #[serde(skip)]
+    minimal: bool,
+    /// Print the status whenever it changes, like `watch svn status`, without taking over the
+    /// terminal or capturing the mouse
+    #[arg(long, conflicts_with_all = ["minimal", "checkout", "record_events", "replay_events"])]
+    #[serde(skip)]
+    watch: bool,
+    /// Check out the repository url into the current directory, showing the progress, then
+    /// open the new working copy
+    #[arg(long, value_name = "URL")]
+    #[serde(skip)]
+    checkout: Option<String>,
+    /// Record the key presses, mouse and other terminal events of the session to the file
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip)]
+    record_events: Option<PathBuf>,
+    /// Replay recorded events against the app without a terminal, then print the final screen
+    #[arg(long, value_name = "PATH", conflicts_with = "record_events")]
+    #[serde(skip)]
+    replay_events: Option<PathBuf>,
+    /// Listen on a Unix socket at the path for editors and other tools to ask for the status
+    /// as JSON, or to refresh or commit, one request per line
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["watch", "replay_events"])]
+    #[serde(skip)]
+    control_socket: Option<PathBuf>,
+    /// Write the branch, changes and conflicts to the file and exit, as CSV, JSON, HTML or
+    /// Markdown by its extension. "{timestamp}" in the path is replaced by the time.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath, conflicts_with_all = ["watch", "minimal", "checkout", "record_events", "replay_events"])]
+    #[serde(skip)]
+    export_status: Option<String>,
+    /// Time between status refreshes, e.g. "500ms", "10s" or "5m"
+    #[arg(short = 's', long, visible_alias = "svn-timeout")]
+    #[serde(alias = "svn_timeout")]
+    refresh_interval: Option<DurationArg>,
+    /// Refresh on a timer, "false" to only refresh on focus gain or with r
+    #[arg(long)]
+    auto_refresh: Option<bool>,
+    /// Only refresh on focus gain or with r, for working copies too big to read every few
+    /// seconds
+    #[arg(long, conflicts_with = "auto_refresh")]
+    #[serde(skip)]
+    no_auto_refresh: bool,
+    #[arg(long)]
+    refresh_on_focus: Option<bool>,
+    #[arg(long)]
+    clear_selection_on_blur: Option<bool>,
+    /// Time between checks for revisions the working copy is behind, "0s" to never check
+    #[arg(long)]
+    incoming_check_interval: Option<DurationArg>,
+    #[arg(long)]
+    indent_by_depth: Option<bool>,
+    #[arg(long)]
+    nerd_font_icons: Option<bool>,
+    #[arg(long)]
+    file_manager: Option<String>,
+    #[arg(long)]
+    network_retries: Option<u8>,
+    #[arg(long)]
+    incremental_status: Option<bool>,
+    #[arg(long)]
+    status_depth: Option<StatusDepth>,
+    #[arg(long, value_delimiter = ',')]
+    excluded_changelists: Option<Vec<String>>,
+    #[arg(long)]
+    hide_excluded_changelists: Option<bool>,
+    #[arg(long)]
+    hide_property_only: Option<bool>,
+    #[arg(long, value_delimiter = ',')]
+    hide_patterns: Option<Vec<String>>,
+    /// Working copies to show on the workspaces dashboard, opened with 'w'
+    #[arg(long, value_delimiter = ',')]
+    workspaces: Option<Vec<PathBuf>>,
+    /// Working copies to jump between from the popup opened with 'b'
+    #[arg(long, value_delimiter = ',')]
+    bookmarks: Option<Vec<PathBuf>>,
+    /// Where the branch switcher finds branches, a root ending in /* is a directory of them
+    #[arg(long, value_delimiter = ',')]
+    branch_roots: Option<Vec<String>>,
+    #[arg(long)]
+    pager: Option<String>,
+    #[arg(long)]
+    editor: Option<String>,
+    #[arg(long)]
+    group_by: Option<GroupBy>,
+    #[arg(long)]
+    conflicts_pane_percent: Option<u16>,
+    #[arg(long)]
+    pane_layout: Option<PaneLayout>,
+    #[arg(long)]
+    on_branch_change: Option<String>,
+    #[arg(long)]
+    on_refresh: Option<String>,
+    #[arg(long)]
+    on_commit_success: Option<String>,
+    #[arg(long)]
+    on_conflict_detected: Option<String>,
+    #[arg(long)]
+    theme: Option<Theme>,
+    /// Two colours blamed lines are tinted between by age, e.g. "#262626,#d75f00"
+    #[arg(long)]
+    blame_gradient: Option<BlameGradient>,
+    #[arg(long)]
+    date_format: Option<String>,
+    #[arg(long)]
+    utc_timestamps: Option<bool>,
+    /// How the time of the last refresh is shown, "relative" or a strftime format, e.g. "%H:%M"
+    #[arg(long)]
+    last_updated_format: Option<String>,
+    #[arg(long)]
+    desktop_notifications: Option<bool>,
+    #[arg(long)]
+    exit_summary: Option<bool>,
+    #[arg(long)]
+    keep_locks: Option<bool>,
+    #[arg(long)]
+    auto_resolve_trivial: Option<bool>,
+    #[arg(skip)]
+    keys: Option<KeysSource>,
+}
+
+/// The `[keys]` table, only read from config files
+#[derive(Default, serde::Deserialize)]
+struct KeysSource {
+    #[serde(default)]
+    custom: HashMap<KeyBinding, String>,
+}
+
+/// A key bound to a custom command: a character, F1 to F12, or either of them after any of
+/// "ctrl+", "alt+" and "shift+", e.g. "F5", "T" or "ctrl+t"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = name;
+        // a trailing + is the key itself, e.g. "ctrl++"
+        while let Some((modifier, rest)) = key.split_once('+').filter(|(_, rest)| !rest.is_empty())
+        {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{modifier}' in the key '{name}'")),
+            };
+            key = rest;
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.strip_prefix(['F', 'f']).and_then(|n| n.parse().ok()) {
+                Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                _ => {
+                    return Err(format!(
+                        "unknown key '{name}', expected a character or F1 to F12"
+                    ));
+                }
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether the key event is for this key. Shift is ignored with characters, where it's
+    /// already in the character.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let ignored = match key.code {
+            KeyCode::Char(_) => KeyModifiers::SHIFT,
+            _ => KeyModifiers::NONE,
+        };
+        key.code == self.code
+            && key.modifiers.difference(ignored) == self.modifiers.difference(ignored)
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::parse(&name)
+    }
+}
+
+#[derive(clap::Subcommand)]
+enum SubCommand {
+    /// Print a completion script for the shell, e.g. `svn-util completions fish >
+    /// ~/.config/fish/completions/svn-util.fish`
+    #[command(hide = true)]
+    Completions { shell: Shell },
+}
+
+/// The range the conflicts pane can be resized within
+pub const MIN_PANE_PERCENT: u16 = 10;
+pub const MAX_PANE_PERCENT: u16 = 90;
+
+/// How the changes list is grouped
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// One flat list, in the order svn prints them
+    #[default]
+    None,
+    /// Under a collapsible header for each directory
+    Directory,
+    /// Nested by directory, each one collapsed until it's expanded
+    Tree,
+    /// Under a collapsible section for each state, conflicts first
+    State,
+}
+
+impl GroupBy {
+    /// The grouping after this one, for cycling through them with a key
+    pub fn next(&self) -> GroupBy {
+        match self {
+            GroupBy::None => GroupBy::Directory,
+            GroupBy::Directory => GroupBy::Tree,
+            GroupBy::Tree => GroupBy::State,
+            GroupBy::State => GroupBy::None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::None => "none",
+            GroupBy::Directory => "directory",
+            GroupBy::Tree => "tree",
+            GroupBy::State => "state",
+        }
+    }
+}
+
+/// How deep 'svn status' looks into the working copy, as its --depth
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusDepth {
+    /// Only the working copy's root
+    Empty,
+    /// The root and the files directly inside it
+    Files,
+    /// The root and everything directly inside it
+    Immediates,
+    /// Everything
+    #[default]
+    Infinity,
+}
+
+impl StatusDepth {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusDepth::Empty => "empty",
+            StatusDepth::Files => "files",
+            StatusDepth::Immediates => "immediates",
+            StatusDepth::Infinity => "infinity",
+        }
+    }
+}
+
+/// Where the conflicts pane goes relative to the changes list
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaneLayout {
+    /// Side by side, for wide terminals, stacking them when there isn't the width
+    #[default]
+    Horizontal,
+    /// The conflicts under the changes
+    Vertical,
+}
+
+impl PaneLayout {
+    pub fn next(&self) -> PaneLayout {
+        match self {
+            PaneLayout::Horizontal => PaneLayout::Vertical,
+            PaneLayout::Vertical => PaneLayout::Horizontal,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaneLayout::Horizontal => "horizontal",
+            PaneLayout::Vertical => "vertical",
+        }
+    }
+}
+
+/// The colours the states are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Default,
+    /// Blue and orange instead of green and red, for red-green colour blindness
+    #[value(alias = "deuteranopia-safe")]
+    #[serde(alias = "deuteranopia-safe")]
+    Deuteranopia,
+    /// Like deuteranopia, but avoiding dark reds which look black with protanopia
+    Protanopia,
+    /// No colours, the states told apart by bold, underlined, italic and crossed out text
+    Monochrome,
+    /// Bold, bright colours and no dim grey text, for projectors and poor terminals
+    #[serde(rename = "high-contrast")]
+    HighContrast,
+}
+
+impl Theme {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::Deuteranopia => "deuteranopia",
+            Theme::Protanopia => "protanopia",
+            Theme::Monochrome => "monochrome",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+}
+
+/// Two colours, e.g. "#262626,#d75f00", blended between to show how old something is
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct BlameGradient {
+    pub oldest: (u8, u8, u8),
+    pub newest: (u8, u8, u8),
+}
+
+impl Default for BlameGradient {
+    fn default() -> Self {
+        Self {
+            oldest: (0x26, 0x26, 0x26),
+            newest: (0xd7, 0x5f, 0x00),
+        }
+    }
+}
+
+impl BlameGradient {
+    /// The colour `age` of the way from the oldest colour to the newest, from 0.0 to 1.0
+    pub fn colour_at(&self, age: f64) -> Color {
+        let age = age.clamp(0.0, 1.0);
+        let blend = |old: u8, new: u8| (old as f64 + (new as f64 - old as f64) * age).round() as u8;
+        Color::Rgb(
+            blend(self.oldest.0, self.newest.0),
+            blend(self.oldest.1, self.newest.1),
+            blend(self.oldest.2, self.newest.2),
+        )
+    }
+
+    pub fn label(&self) -> String {
+        let hex = |(r, g, b): (u8, u8, u8)| format!("#{r:02x}{g:02x}{b:02x}");
+        format!("{},{}", hex(self.oldest), hex(self.newest))
+    }
+}
+
+impl std::str::FromStr for BlameGradient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |colour: &str| {
+            let hex = colour.trim().strip_prefix('#').unwrap_or(colour.trim());
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+            };
+            match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok((r, g, b)),
+                _ => Err(format!(
+                    "invalid colour '{colour}', expected e.g. \"#d75f00\""
+                )),
+            }
+        };
+        match s.split_once(',') {
+            Some((oldest, newest)) => Ok(Self {
+                oldest: parse(oldest)?,
+                newest: parse(newest)?,
+            }),
+            None => Err(format!(
+                "invalid gradient '{s}', expected two colours like \"#262626,#d75f00\""
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for BlameGradient {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+/// A duration given as a string with a unit, e.g. "500ms", or a number of seconds
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(try_from = "RawDuration")]
+struct DurationArg(Duration);
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawDuration {
+    Seconds(u64),
+    Text(String),
+}
+
+impl TryFrom<RawDuration> for DurationArg {
+    type Error = String;
+
+    fn try_from(value: RawDuration) -> Result<Self, Self::Error> {
+        match value {
+            RawDuration::Seconds(secs) => Ok(DurationArg(Duration::from_secs(secs))),
+            RawDuration::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl std::str::FromStr for DurationArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(DurationArg)
+    }
+}
+
+/// Parses durations like "500ms", "10s", "1.5m" or "2h". A bare number is in seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{s}', expected e.g. \"500ms\" or \"10s\""))?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 60.0 * 60.0,
+        other => return Err(format!("unknown duration unit '{other}' in '{s}'")),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("invalid duration '{s}': {e}"))
+}
+
+/// Formats a duration in the largest unit that represents it exactly
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    match millis {
+        0 => "0s".into(),
+        m if m % 3_600_000 == 0 => format!("{}h", m / 3_600_000),
+        m if m % 60_000 == 0 => format!("{}m", m / 60_000),
+        m if m % 1000 == 0 => format!("{}s", m / 1000),
+        m => format!("{m}ms"),
+    }
+}
+
+/// Prefix for environment variables that override settings, e.g. `SVN_UTIL_NETWORK_RETRIES=3`
+const ENV_PREFIX: &str = "SVN_UTIL_";
+
+/// Shorter names for environment variables, mapped to the setting they override
+const ENV_ALIASES: [(&str, &str); 1] = [("TIMEOUT", "refresh_interval")];
+
+/// Reads any settings overridden by environment variables. Every setting that can be passed on
+/// the command line can be overridden, values are parsed as TOML and fall back to strings.
+fn env_overrides(env: impl Fn(&str) -> Option<String>) -> Result<ConfigSource, String> {
+    let settings = ConfigSource::command()
+        .get_arguments()
+        .map(|arg| arg.get_id().to_string())
+        .filter(|id| {
+            ![
+                "config",
+                "init_config",
+                "demo",
+                "minimal",
+                "watch",
+                "checkout",
+                "record_events",
+                "replay_events",
+                "control_socket",
+                "export_status",
+                "no_auto_refresh",
+                "help",
+                "version",
+            ]
+            .contains(&id.as_str())
+        })
+        .collect::<Vec<_>>();
+    let names = settings
+        .iter()
+        .map(|setting| (setting.to_uppercase(), setting.as_str()))
+        .chain(
+            ENV_ALIASES
+                .iter()
+                .map(|(alias, setting)| (alias.to_string(), *setting)),
+        );
+    let mut table = toml::Table::new();
+    for (name, setting) in names {
+        if let Some(raw) = env(&format!("{ENV_PREFIX}{name}")) {
+            let value = match format!("value = {raw}").parse::<toml::Table>() {
+                Ok(mut parsed) => parsed.remove("value").expect("just parsed"),
+                Err(_) => toml::Value::String(raw),
+            };
+            table.insert(setting.to_string(), value);
+        }
+    }
+    table
+        .try_into()
+        .map_err(|e| format!("environment variable override: {e}"))
+}
+
+/// Writes the default config to the path, refusing to overwrite an existing file
+pub fn write_default_config(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, default_config_template()).map_err(|e| e.to_string())
+}
+
+/// Every supported setting, commented out and set to its default
+fn default_config_template() -> String {
+    let default = Config::default();
+    format!(
+        "\
+# svn-util settings, uncomment a setting to change it.
+# Any setting can also be overridden with an SVN_UTIL_<SETTING> environment variable.
+
+# Time between automatic 'svn status' refreshes, e.g. \"500ms\", \"10s\" or \"5m\"
+# refresh_interval = \"{refresh_interval}\"
+
+# Refresh every refresh_interval, false to only refresh when the terminal gains focus or on r,
+# for working copies too big to read every few seconds
+# auto_refresh = {auto_refresh}
+
+# Refresh the branch and status when the terminal gains focus
+# refresh_on_focus = {refresh_on_focus}
+
+# Unselect the selected change when the terminal loses focus, false to keep it while switching
+# between windows
+# clear_selection_on_blur = {clear_selection_on_blur}
+
+# Time between asking the repository how many revisions the working copy is behind, shown in
+# the branch box, \"0s\" to never ask
+# incoming_check_interval = \"{incoming_check_interval}\"
+
+# Indent entries in the changes list by their directory depth
+# indent_by_depth = {indent_by_depth}
+
+# Show file type icons and state glyphs, needs a Nerd Font (https://www.nerdfonts.com)
+# nerd_font_icons = {nerd_font_icons}
+
+# Command used to open directories, defaults to the platform's opener
+# file_manager = \"{file_manager}\"
+
+# How many times to retry svn commands that fail with a transient network error
+# network_retries = {network_retries}
+
+# Load the initial status one top-level directory at a time
+# incremental_status = {incremental_status}
+
+# How deep the status looks, \"empty\", \"files\", \"immediates\" or \"infinity\". Shallower is
+# quicker for gigantic trees, and a directory can still be drilled into at full depth with 'X'
+# status_depth = \"{status_depth}\"
+
+# Changelists left out when selecting all changes, e.g. TortoiseSVN's ignore-on-commit
+# excluded_changelists = {excluded_changelists:?}
+
+# Hide changes in the excluded changelists, toggled with 'h'
+# hide_excluded_changelists = {hide_excluded_changelists}
+
+# Hide entries with only property changes, like ' M .', toggled with 'H'
+# hide_property_only = {hide_property_only}
+
+# Paths left out of the changes list without touching svn:ignore, e.g. [\"*.orig\", \"target/**\"].
+# A pattern without a '/' matches the file name in any directory
+# hide_patterns = {hide_patterns:?}
+
+# Working copies shown together on the dashboard opened with 'w', e.g. one per component
+# workspaces = {workspaces:?}
+
+# Working copies to jump between from the popup opened with 'b', without loading their status
+# bookmarks = {bookmarks:?}
+
+# Where the branch switcher opened with 'B' finds branches. An entry ending in /* is a
+# directory of branches, anything else is a branch itself.
+# branch_roots = {branch_roots:?}
+
+# Command diffs are paged through, defaults to $PAGER and then less
+# pager = \"less -R\"
+
+# Command files are opened with by 'o', taking over the terminal until it quits. Unset, they
+# open in a floating zellij pane.
+# editor = \"vim\"
+
+# How the changes list is grouped, \"none\", \"directory\", \"tree\" or \"state\", cycled with 'v'
+# group_by = \"{group_by}\"
+
+# How much of the width the conflicts pane takes, or of the height when stacked, from 10 to
+# 90. Resizing it with '<' and '>' saves the new size to layout.toml next to this file, which
+# is read after this file.
+# conflicts_pane_percent = {conflicts_pane_percent}
+
+# \"horizontal\" for the conflicts pane beside the changes, or \"vertical\" for it under them.
+# Toggling it with 'L' saves the choice to layout.toml too.
+# pane_layout = \"{pane_layout}\"
+
+# Shell command run when the branch changes, e.g. after an 'svn switch' in another terminal.
+# {{branch}} and {{previous}} are replaced with the branch names, which are also in the
+# SVN_UTIL_BRANCH and SVN_UTIL_PREVIOUS_BRANCH environment variables.
+# on_branch_change = \"notify-send 'now on {{branch}}'\"
+
+# More hooks, run without waiting for them. Every hook has the branch and working copy in
+# SVN_UTIL_BRANCH and SVN_UTIL_WORKING_COPY.
+# After a status refresh that finds the changes have changed, with SVN_UTIL_CHANGES and SVN_UTIL_CONFLICTS set to the counts.
+# on_refresh = \"echo $SVN_UTIL_CHANGES > /tmp/svn-util-changes\"
+# After a commit, with SVN_UTIL_REVISION, SVN_UTIL_COMMIT_MESSAGE and SVN_UTIL_PATHS, one
+# path per line.
+# on_commit_success = \"curl -X POST https://ci.example.com/build\"
+# When a refresh finds new conflicts, with them in SVN_UTIL_PATHS, one path per line.
+# on_conflict_detected = \"notify-send 'new conflicts' \\\"$SVN_UTIL_PATHS\\\"\"
+
+# Colours for the states, \"default\", or \"deuteranopia\" or \"protanopia\" which avoid
+# telling states apart by red and green and mark conflicts with more than colour, or
+# \"monochrome\" which doesn't use colour at all, or \"high-contrast\" for projectors and poor
+# terminals, which Z also toggles
+# theme = \"{theme}\"
+
+# The colours the blame view tints lines with, from the file's oldest revision to its newest,
+# so recently changed regions stand out
+# blame_gradient = \"{blame_gradient}\"
+
+# How timestamps are shown, see https://docs.rs/chrono/latest/chrono/format/strftime
+# date_format = \"{date_format}\"
+
+# Show timestamps in UTC instead of the local timezone
+# utc_timestamps = {utc_timestamps}
+
+# How the time of the last refresh is shown in the status bar, \"relative\" for e.g. \"12s ago\"
+# or a strftime format like date_format's, e.g. \"%H:%M:%S\"
+# last_updated_format = \"{last_updated_format}\"
+
+# Send a desktop notification when new conflicts appear while the terminal isn't focused,
+# with notify-send on Linux or osascript on macOS
+# desktop_notifications = {desktop_notifications}
+
+# Print the branch and change counts after quitting, so they stay in the scrollback
+# exit_summary = {exit_summary}
+
+# Keep the locks on committed files by default, for files with svn:needs-lock that are still
+# being worked on. 'k' toggles it when reviewing a commit.
+# keep_locks = {keep_locks}
+
+# Resolve conflicts where both sides made the same change, or one side only changed whitespace,
+# as soon as they appear. 'A' on a conflict does the same when this is off.
+# auto_resolve_trivial = {auto_resolve_trivial}
+
+# Shell commands run with a key, with the output shown as they run. A key is a character or
+# F1 to F12, optionally after ctrl+, alt+ or shift+. {{cwd}}, {{branch}}, {{path}} (the
+# highlighted change) and {{paths}} (every selected change) are replaced, quoted for the shell.
+# keys.custom.F5 = \"svn update {{cwd}}\"
+# keys.custom.\"ctrl+t\" = \"make test\"
+",
+        refresh_interval = format_duration(default.refresh_interval),
+        auto_refresh = default.auto_refresh,
+        refresh_on_focus = default.refresh_on_focus,
+        clear_selection_on_blur = default.clear_selection_on_blur,
+        incoming_check_interval = format_duration(default.incoming_check_interval),
+        indent_by_depth = default.indent_by_depth,
+        nerd_font_icons = default.nerd_font_icons,
+        file_manager = default.file_manager_command(),
+        network_retries = default.network_retries,
+        incremental_status = default.incremental_status,
+        status_depth = default.status_depth.label(),
+        excluded_changelists = default.excluded_changelists,
+        hide_excluded_changelists = default.hide_excluded_changelists,
+        hide_property_only = default.hide_property_only,
+        hide_patterns = default.hide_patterns,
+        workspaces = default.workspaces,
+        bookmarks = default.bookmarks,
+        branch_roots = default.branch_roots,
+        group_by = default.group_by.label(),
+        conflicts_pane_percent = default.conflicts_pane_percent,
+        pane_layout = default.pane_layout.label(),
+        theme = default.theme.label(),
+        blame_gradient = default.blame_gradient.label(),
+        date_format = default.date_format,
+        utc_timestamps = default.utc_timestamps,
+        last_updated_format = default.last_updated_format,
+        desktop_notifications = default.desktop_notifications,
+        exit_summary = default.exit_summary,
+        keep_locks = default.keep_locks,
+        auto_resolve_trivial = default.auto_resolve_trivial,
+    )
+}
+
+/// The location of the user's config file, e.g. `~/.config/svn-util/config.toml`
+pub fn global_config_path() -> Option<PathBuf> {
+    config_dir(|key| std::env::var_os(key)).map(|dir| dir.join("svn-util").join("config.toml"))
+}
+
+/// Where the pane proportions are saved, next to the global config
+fn layout_path() -> Option<PathBuf> {
+    global_config_path().map(|path| path.with_file_name("layout.toml"))
+}
+
+/// Where the branch lists of every repository are cached, next to the global config
+pub fn branch_cache_path() -> Option<PathBuf> {
+    global_config_path().map(|path| path.with_file_name("branches.toml"))
+}
+
+/// Where the pinned files of every working copy are saved, next to the global config
+fn pins_path() -> Option<PathBuf> {
+    global_config_path().map(|path| path.with_file_name("pins.toml"))
+}
+
+type PinsFile = std::collections::BTreeMap<String, Vec<PathBuf>>;
+
+/// The files pinned to the top of the changes list in the working copy
+pub fn load_pins(working_copy: &Path) -> Vec<PathBuf> {
+    pins_path()
+        .map(|path| load_pins_from(&path, working_copy))
+        .unwrap_or_default()
+}
+
+/// Saves the files pinned in the working copy, keeping the other working copies' pins
+pub fn save_pins(working_copy: &Path, pins: &[PathBuf]) -> Result<(), String> {
+    let path = pins_path().ok_or("could not find a config directory")?;
+    save_pins_to(&path, working_copy, pins)
+}
+
+fn read_pins_file(path: &Path) -> PinsFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn load_pins_from(path: &Path, working_copy: &Path) -> Vec<PathBuf> {
+    read_pins_file(path)
+        .remove(&working_copy.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn save_pins_to(path: &Path, working_copy: &Path, pins: &[PathBuf]) -> Result<(), String> {
+    let mut all = read_pins_file(path);
+    let key = working_copy.to_string_lossy().to_string();
+    match pins.is_empty() {
+        true => all.remove(&key),
+        false => all.insert(key, pins.to_vec()),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = toml::to_string(&all).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+/// The platform's config directory, honouring `$XDG_CONFIG_HOME` everywhere but Windows
+fn config_dir(env: impl Fn(&str) -> Option<std::ffi::OsString>) -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        return env("APPDATA").map(PathBuf::from);
+    }
+    if let Some(xdg) = env("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(xdg));
+    }
+    let home = PathBuf::from(env("HOME")?);
+    match cfg!(target_os = "macos") {
+        true => Some(home.join("Library").join("Application Support")),
+        false => Some(home.join(".config")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    use rstest::*;
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn test_config_dir() {
+        let env = |xdg: Option<&'static str>| {
+            move |key: &str| match key {
+                "XDG_CONFIG_HOME" => xdg.map(Into::into),
+                "HOME" => Some("/home/user".into()),
+                _ => None,
+            }
+        };
+        assert_eq!(Some(PathBuf::from("/xdg")), config_dir(env(Some("/xdg"))));
+        assert_eq!(
+            Some(PathBuf::from("/home/user/.config")),
+            config_dir(env(None))
+        );
+        assert_eq!(
+            Some(PathBuf::from("/home/user/.config")),
+            config_dir(env(Some("")))
+        );
+    }
+
+    #[test]
+    fn test_default_config_template() {
+        let uncommented: String = default_config_template()
+            .lines()
+            .filter(|line| line.starts_with("# ") && line.contains(" = "))
+            .map(|line| format!("{}\n", &line[2..]))
+            .collect();
+        let parsed: ConfigSource = toml::from_str(&uncommented).unwrap();
+        let mut config = Config::default();
+        config.update(parsed);
+        let expected = Config {
+            file_manager: Some(Config::default().file_manager_command()),
+            pager: Some("less -R".into()),
+            editor: Some("vim".into()),
+            on_branch_change: Some("notify-send 'now on {branch}'".into()),
+            on_refresh: Some("echo $SVN_UTIL_CHANGES > /tmp/svn-util-changes".into()),
+            on_commit_success: Some("curl -X POST https://ci.example.com/build".into()),
+            on_conflict_detected: Some("notify-send 'new conflicts' \"$SVN_UTIL_PATHS\"".into()),
+            custom_keys: HashMap::from([
+                (
+                    KeyBinding::parse("F5").unwrap(),
+                    "svn update {cwd}".to_string(),
+                ),
+                (
+                    KeyBinding::parse("ctrl+t").unwrap(),
+                    "make test".to_string(),
+                ),
+            ]),
+            ..Config::default()
+        };
+        assert_eq!(expected, config);
+    }
+
+    #[rstest]
+    #[case("F5", Ok((KeyCode::F(5), KeyModifiers::NONE)))]
+    #[case("T", Ok((KeyCode::Char('T'), KeyModifiers::NONE)))]
+    #[case("Ctrl+Alt+t", Ok((KeyCode::Char('t'), KeyModifiers::CONTROL | KeyModifiers::ALT)))]
+    #[case("ctrl++", Ok((KeyCode::Char('+'), KeyModifiers::CONTROL)))]
+    #[case("F13", Err("unknown key 'F13', expected a character or F1 to F12".to_string()))]
+    #[case("hyper+x", Err("unknown modifier 'hyper' in the key 'hyper+x'".to_string()))]
+    fn test_parse_key_binding(
+        #[case] name: &str,
+        #[case] exp: Result<(KeyCode, KeyModifiers), String>,
+    ) {
+        let parsed = KeyBinding::parse(name).map(|key| (key.code, key.modifiers));
+        assert_eq!(exp, parsed);
+    }
+
+    #[test]
+    fn test_key_binding_matches() {
+        let shifted = KeyEvent::new(KeyCode::Char('T'), KeyModifiers::SHIFT);
+        assert!(KeyBinding::parse("T").unwrap().matches(&shifted));
+        let ctrl_t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert!(KeyBinding::parse("ctrl+t").unwrap().matches(&ctrl_t));
+        assert!(!KeyBinding::parse("t").unwrap().matches(&ctrl_t));
+        let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::SHIFT);
+        assert!(!KeyBinding::parse("F5").unwrap().matches(&f5));
+    }
+
+    #[test]
+    fn test_no_auto_refresh() {
+        let mut config = Config::default();
+        config.update(ConfigSource::try_parse_from(["svn-util", "--no-auto-refresh"]).unwrap());
+        assert!(!config.auto_refresh);
+        let args = ["svn-util", "--no-auto-refresh", "--auto-refresh", "true"];
+        assert!(ConfigSource::try_parse_from(args).is_err());
+    }
+
+    #[rstest]
+    #[case("relative", 12, "12s ago")]
+    #[case("relative", 150, "2m ago")]
+    #[case("relative", 7200, "2h ago")]
+    #[case("relative", 3 * 86400, "3d ago")]
+    #[case("%H:%M:%S", 12, "05:05:55")]
+    fn test_format_last_updated(#[case] format: &str, #[case] seconds: i64, #[case] exp: &str) {
+        let config = Config {
+            last_updated_format: format.into(),
+            utc_timestamps: true,
+            ..Config::default()
+        };
+        let now = DateTime::parse_from_rfc3339("2025-03-04T05:06:07Z")
+            .unwrap()
+            .to_utc();
+        let time = now - chrono::TimeDelta::seconds(seconds);
+        assert_eq!(exp, config.format_last_updated(time, now));
+    }
+
+    #[rstest]
+    #[case("monochrome", Theme::Monochrome)]
+    #[case("deuteranopia", Theme::Deuteranopia)]
+    #[case("deuteranopia-safe", Theme::Deuteranopia)]
+    #[case("high-contrast", Theme::HighContrast)]
+    fn test_theme_names(#[case] name: &str, #[case] exp: Theme) {
+        let parsed: ConfigSource = toml::from_str(&format!("theme = \"{name}\"")).unwrap();
+        assert_eq!(Some(exp), parsed.theme);
+        assert_eq!(Ok(exp), clap::ValueEnum::from_str(name, false));
+    }
+
+    #[rstest]
+    #[case("%Y-%m-%d %H:%M:%S", "2025-03-04 05:06:07")]
+    #[case("%H:%M", "05:06")]
+    #[case("%Q", "2025-03-04T05:06:07Z")]
+    fn test_format_timestamp(#[case] format: &str, #[case] exp: &str) {
+        let config = Config {
+            date_format: format.into(),
+            utc_timestamps: true,
+            ..Config::default()
+        };
+        let time = DateTime::parse_from_rfc3339("2025-03-04T05:06:07Z").unwrap();
+        assert_eq!(exp, config.format_timestamp(time.to_utc()));
+    }
+
+    #[rstest]
+    #[case("500ms", Ok(Duration::from_millis(500)))]
+    #[case("10s", Ok(Duration::from_secs(10)))]
+    #[case("10", Ok(Duration::from_secs(10)))]
+    #[case("1.5m", Ok(Duration::from_secs(90)))]
+    #[case(" 5m ", Ok(Duration::from_secs(300)))]
+    #[case("2h", Ok(Duration::from_secs(7200)))]
+    #[case("ten seconds", Err("invalid duration 'ten seconds', expected e.g. \"500ms\" or \"10s\"".into()))]
+    #[case("10d", Err("unknown duration unit 'd' in '10d'".into()))]
+    fn test_parse_duration(#[case] s: &str, #[case] exp: Result<Duration, String>) {
+        assert_eq!(exp, parse_duration(s));
+    }
+
+    #[rstest]
+    #[case("#262626,#d75f00", Ok(BlameGradient::default()))]
+    #[case("000000, FFFFFF", Ok(BlameGradient { oldest: (0, 0, 0), newest: (255, 255, 255) }))]
+    #[case("#262626", Err("invalid gradient '#262626', expected two colours like \"#262626,#d75f00\"".into()))]
+    #[case("#2626,#d75f00", Err("invalid colour '#2626', expected e.g. \"#d75f00\"".into()))]
+    #[case("#262626,red", Err("invalid colour 'red', expected e.g. \"#d75f00\"".into()))]
+    fn test_parse_blame_gradient(#[case] s: &str, #[case] exp: Result<BlameGradient, String>) {
+        assert_eq!(exp, s.parse());
+    }
+
+    #[test]
+    fn test_blame_gradient_colour_at() {
+        let gradient = BlameGradient {
+            oldest: (0, 100, 200),
+            newest: (200, 100, 0),
+        };
+        assert_eq!(Color::Rgb(0, 100, 200), gradient.colour_at(0.0));
+        assert_eq!(Color::Rgb(100, 100, 100), gradient.colour_at(0.5));
+        assert_eq!(Color::Rgb(200, 100, 0), gradient.colour_at(1.5));
+        assert_eq!("#0064c8,#c86400", gradient.label());
+    }
+
+    #[rstest]
+    #[case(Duration::from_millis(500), "500ms")]
+    #[case(Duration::from_secs(2), "2s")]
+    #[case(Duration::from_secs(300), "5m")]
+    #[case(Duration::from_secs(7200), "2h")]
+    fn test_format_duration(#[case] duration: Duration, #[case] exp: &str) {
+        assert_eq!(exp, format_duration(duration));
+        assert_eq!(Ok(duration), parse_duration(exp));
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let env = |key: &str| match key {
+            "SVN_UTIL_TIMEOUT" => Some("9s".to_string()),
+            "SVN_UTIL_INDENT_BY_DEPTH" => Some("true".to_string()),
+            "SVN_UTIL_FILE_MANAGER" => Some("nautilus --new-window".to_string()),
+            "SVN_UTIL_EDITOR" => Some("hx".to_string()),
+            "SVN_UTIL_CONFIG" => Some("ignored.toml".to_string()),
+            _ => None,
+        };
+        let mut config = Config::default();
+        config.update(env_overrides(env).unwrap());
+        let expected = Config {
+            refresh_interval: Duration::from_secs(9),
+            indent_by_depth: true,
+            file_manager: Some("nautilus --new-window".into()),
+            editor: Some("hx".into()),
+            ..Config::default()
+        };
+        assert_eq!(expected, config);
+        assert!(env_overrides(|_| Some("not a number".into())).is_err());
+    }
+
+    #[test]
+    fn test_update_from_file() {
+        let mut config = Config::default();
+        assert_eq!(
+            Ok(false),
+            config.update_from_file(Path::new("missing.toml"))
+        );
+        let dir = TempDir::new("update-from-file");
+        let path = dir.path().join("settings.toml");
+        // svn_timeout is the old name for refresh_interval, and was a number of seconds
+        std::fs::write(&path, "svn_timeout = 7\nindent_by_depth = true\n").unwrap();
+        assert_eq!(Ok(true), config.update_from_file(&path));
+        assert_eq!(Duration::from_secs(7), config.refresh_interval);
+        assert!(config.indent_by_depth);
+    }
+
+    #[test]
+    fn test_from_args_layers_the_working_copy_config() {
+        let dir = TempDir::new("wc-config");
+        let root = dir.path().join("wc");
+        std::fs::create_dir_all(root.join(".svn")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(REPO_CONFIG_FILE), "indent_by_depth = true\n").unwrap();
+        std::fs::write(dir.path().join("custom.toml"), "nerd_font_icons = true\n").unwrap();
+        let args = ConfigSource::parse_from(["svn-util", "--config", "custom.toml"]);
+        let config = Config::from_args(args, dir.path(), &root.join("src")).unwrap();
+        assert!(config.indent_by_depth);
+        assert!(config.nerd_font_icons);
+        assert_eq!(Some(dir.path().to_path_buf()), config.started_in);
+
+        let args = ConfigSource::parse_from(["svn-util", "--config", "custom.toml"]);
+        let config = Config::from_args(args, dir.path(), dir.path()).unwrap();
+        assert!(!config.indent_by_depth);
+    }
+
+    #[test]
+    fn test_save_layout() {
+        let dir = TempDir::new("save-layout");
+        let path = dir.path().join("layout.toml");
+        let saved = Config {
+            conflicts_pane_percent: 65,
+            pane_layout: PaneLayout::Vertical,
+            ..Config::default()
+        };
+        saved.save_layout_to(&path).unwrap();
+        let mut config = Config::default();
+        assert_eq!(Ok(true), config.update_from_file(&path));
+        assert_eq!(saved, config);
+    }
+
+    #[test]
+    fn test_save_pins() {
+        let dir = TempDir::new("save-pins");
+        let path = dir.path().join("pins.toml");
+        let (wc1, wc2) = (Path::new("/work/wc1"), Path::new("/work/wc2"));
+        let pins = vec![PathBuf::from("CHANGELOG.md"), PathBuf::from("src/lib.rs")];
+        save_pins_to(&path, wc1, &pins).unwrap();
+        save_pins_to(&path, wc2, &[PathBuf::from("b.txt")]).unwrap();
+        assert_eq!(pins, load_pins_from(&path, wc1));
+
+        save_pins_to(&path, wc2, &[]).unwrap();
+        assert_eq!(Vec::<PathBuf>::new(), load_pins_from(&path, wc2));
+        assert_eq!(pins, load_pins_from(&path, wc1));
+    }
 }
@@ -0,0 +1,114 @@
+use crate::{
+    command::run_command,
+    svn::{Error, Result},
+};
+use std::path::{Path, PathBuf};
+
+/// Creates a throwaway repository with a checkout of its trunk that has every kind of change
+/// in it (modified, added, deleted, missing, unversioned and a text conflict), so the app can
+/// be explored without touching a real working copy. Returns the path of the checkout.
+pub fn create_demo_working_copy() -> Result<PathBuf> {
+    let base = std::env::temp_dir().join(format!("svn-util-demo-{}", std::process::id()));
+    if base.exists() {
+        std::fs::remove_dir_all(&base)?;
+    }
+    std::fs::create_dir_all(&base)?;
+    let repo = base.join("repo");
+    let wc = base.join("wc");
+    let other = base.join("other");
+
+    run_ok("svnadmin", &["create", &repo.to_string_lossy()])?;
+    let url = file_url(&repo);
+    let trunk = format!("{url}/trunk");
+    run_svn(&[
+        "mkdir",
+        "-m",
+        "Create standard layout",
+        &trunk,
+        &format!("{url}/branches"),
+        &format!("{url}/tags"),
+    ])?;
+    run_svn(&["checkout", &trunk, &wc.to_string_lossy()])?;
+
+    write(
+        &wc,
+        "README.md",
+        "# Demo project\n\nA working copy to try svn-util in.\n",
+    )?;
+    write(&wc, "CHANGELOG.md", "## 0.1.0\n\n- first release\n")?;
+    write(&wc, "src/main.c", "int main() {\n    return 0;\n}\n")?;
+    write(
+        &wc,
+        "src/util.c",
+        "int add(int a, int b) {\n    return a + b;\n}\n",
+    )?;
+    write(&wc, "docs/guide.txt", "How to use the demo project.\n")?;
+    for path in ["README.md", "CHANGELOG.md", "src", "docs"] {
+        run_svn(&["add", &wc.join(path).to_string_lossy()])?;
+    }
+    run_svn(&["commit", "-m", "Initial import", &wc.to_string_lossy()])?;
+
+    // someone else changes the changelog, which will conflict with our local edit
+    run_svn(&["checkout", &trunk, &other.to_string_lossy()])?;
+    write(&other, "CHANGELOG.md", "## 0.1.1\n\n- fixed a bug\n")?;
+    run_svn(&["commit", "-m", "Release 0.1.1", &other.to_string_lossy()])?;
+    write(&wc, "CHANGELOG.md", "## 0.2.0\n\n- added a feature\n")?;
+    run_svn(&["update", "--accept", "postpone", &wc.to_string_lossy()])?;
+
+    write(
+        &wc,
+        "README.md",
+        "# Demo project\n\nNow with local modifications.\n",
+    )?;
+    write(&wc, "src/feature.c", "void feature() {}\n")?;
+    run_svn(&["add", &wc.join("src/feature.c").to_string_lossy()])?;
+    run_svn(&["delete", &wc.join("docs/guide.txt").to_string_lossy()])?;
+    std::fs::remove_file(wc.join("src/util.c"))?;
+    write(&wc, "notes.txt", "An unversioned scratch file.\n")?;
+    Ok(wc)
+}
+
+pub(crate) fn run_svn(args: &[&str]) -> Result<()> {
+    let mut with_flags = vec!["--non-interactive"];
+    with_flags.extend_from_slice(args);
+    run_ok("svn", &with_flags)
+}
+
+pub(crate) fn run_ok(cmd: &str, args: &[&str]) -> Result<()> {
+    let res = run_command(cmd, args)?;
+    match res.success() {
+        true => Ok(()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+pub(crate) fn write(root: &Path, path: &str, contents: &str) -> Result<()> {
+    let path = root.join(path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// A file:// URL for a local repository, which needs an extra slash before a Windows drive
+pub(crate) fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    match path.starts_with('/') {
+        true => format!("file://{path}"),
+        false => format!("file:///{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("/tmp/demo/repo", "file:///tmp/demo/repo")]
+    #[case("C:\\Temp\\demo\\repo", "file:///C:/Temp/demo/repo")]
+    fn test_file_url(#[case] path: &str, #[case] exp: &str) {
+        assert_eq!(exp, file_url(Path::new(path)));
+    }
+}
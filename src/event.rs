@@ -1,7 +1,23 @@
+use crate::{
+    app::{worker::Job, workspace::WorkspaceSummary},
+    command::CommandProgress,
+    ipc::Control,
+    svn::{
+        StatusEntry,
+        blame::BlameLine,
+        checkout::CheckoutProgress,
+        incoming::Incoming,
+        log::{BranchHistory, LogEntry},
+    },
+};
 use color_eyre::eyre::WrapErr;
 use ratatui::crossterm::event::{self, Event as CrosstermEvent};
 use std::{
-    sync::mpsc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
     thread,
     time::{Duration, Instant},
 };
@@ -40,6 +56,53 @@ pub enum AppEvent {
     PrevChange,
     SelectChange,
     Message(String),
+    /// Output from a checkout running in the background.
+    Checkout(CheckoutProgress),
+    /// The paths that differ from the url, listed in the background.
+    DiffSummary {
+        other: String,
+        result: Result<Vec<StatusEntry>, String>,
+    },
+    /// Output from a merge, update or commit running in the background.
+    Output(CommandProgress),
+    /// The history for the revision graph, read in the background.
+    RevisionGraph(Result<Vec<BranchHistory>, String>),
+    /// A working copy on the workspaces dashboard, read in the background.
+    Workspace(WorkspaceSummary),
+    /// The branches of the repository with the UUID, listed in the background.
+    Branches {
+        uuid: String,
+        result: Result<Vec<String>, String>,
+    },
+    /// The revisions of a file, read in the background.
+    History {
+        path: String,
+        result: Result<Vec<LogEntry>, String>,
+    },
+    /// Who last changed each line of a file, read in the background.
+    Blame {
+        path: String,
+        result: Result<Vec<BlameLine>, String>,
+    },
+    /// What an update would pull in, asked of the repository in the background.
+    Incoming(Result<Incoming, String>),
+    /// How many revisions the working copy is behind the repository, checked in the background.
+    Behind(Result<usize, String>),
+    /// The newest revision the working copy at the path has, read in the background.
+    LastCommit {
+        path: String,
+        result: Result<Option<LogEntry>, String>,
+    },
+    /// The revisions of a source still to merge and already merged, formatted for the pager
+    /// in the background.
+    Mergeinfo(Result<String, String>),
+    /// A request from another program over the control socket.
+    Control(Control),
+    /// A job that failed transiently, due to run again now its backoff has passed.
+    Retry {
+        job: Job,
+        retry: u8,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -55,6 +118,8 @@ pub struct EventHandler {
     sender: mpsc::Sender<Event>,
     /// Event receiver channel.
     receiver: mpsc::Receiver<Event>,
+    /// Stops the event thread reading the terminal, while another program is using it.
+    paused: Arc<AtomicBool>,
 }
 
 impl Default for EventHandler {
@@ -67,9 +132,14 @@ impl EventHandler {
     /// Constructs a new instance of [`EventHandler`] and spawns a new thread to handle events.
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel();
-        let actor = EventThread::new(sender.clone());
+        let paused = Arc::new(AtomicBool::new(false));
+        let actor = EventThread::new(sender.clone(), paused.clone());
         thread::spawn(|| actor.run());
-        Self { sender, receiver }
+        Self {
+            sender,
+            receiver,
+            paused,
+        }
     }
 
     /// Receives an event from the sender.
@@ -85,6 +155,42 @@ impl EventHandler {
         Ok(self.receiver.recv()?)
     }
 
+    /// Constructs an [`EventHandler`] that doesn't read the terminal or emit ticks, for
+    /// replaying recorded events without a terminal.
+    pub fn headless() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            paused: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Receives an event if one is waiting, without blocking.
+    pub fn try_next(&self) -> Option<Event> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Returns a function that queues app events, for sending them from other threads.
+    pub fn app_sender(&self) -> impl Fn(AppEvent) + Send + Sync + 'static {
+        let sender = self.sender.clone();
+        move |app_event| {
+            // Ignore the result as the app may have quit while the other thread was running
+            let _ = sender.send(Event::App(app_event));
+        }
+    }
+
+    /// Pauses or resumes reading terminal events, so that a program run in the foreground
+    /// (e.g. a pager) gets the user's input instead.
+    ///
+    /// Pausing waits for the event thread to finish any poll that was already in progress.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        if paused {
+            thread::sleep(Duration::from_secs_f64(2.0 / TICK_FPS));
+        }
+    }
+
     /// Queue an app event to be sent to the event receiver.
     ///
     /// This is useful for sending events to the event handler which will be processed by the next
@@ -100,12 +206,14 @@ impl EventHandler {
 struct EventThread {
     /// Event sender channel.
     sender: mpsc::Sender<Event>,
+    /// Whether to stop reading terminal events.
+    paused: Arc<AtomicBool>,
 }
 
 impl EventThread {
     /// Constructs a new instance of [`EventThread`].
-    fn new(sender: mpsc::Sender<Event>) -> Self {
-        Self { sender }
+    fn new(sender: mpsc::Sender<Event>, paused: Arc<AtomicBool>) -> Self {
+        Self { sender, paused }
     }
 
     /// Runs the event thread.
@@ -117,6 +225,10 @@ impl EventThread {
         loop {
             // emit tick events at a fixed rate
             let timeout = tick_interval.saturating_sub(last_tick.elapsed());
+            if self.paused.load(Ordering::SeqCst) {
+                thread::sleep(tick_interval);
+                continue;
+            }
             if timeout == Duration::ZERO {
                 last_tick = Instant::now();
                 self.send(Event::Tick);
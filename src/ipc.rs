@@ -0,0 +1,201 @@
+use crate::event::AppEvent;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+/// How long a client waits for the app to answer, e.g. while it's showing a popup
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What another program, e.g. an editor, can ask of the running app over the control socket
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlRequest {
+    /// The branch, the changes and the conflicts as JSON
+    Status,
+    /// Read the status again
+    Refresh,
+    /// Commit every committable change with the message
+    Commit { message: String },
+}
+
+impl ControlRequest {
+    /// Parses one line sent to the socket: "status", "refresh" or "commit <message>"
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (name, argument) = line.split_once(' ').unwrap_or((line, ""));
+        match (name, argument.trim()) {
+            ("status", "") => Ok(ControlRequest::Status),
+            ("refresh", "") => Ok(ControlRequest::Refresh),
+            ("commit", "") => Err("commit needs a message, e.g. \"commit fix the build\"".into()),
+            ("commit", message) => Ok(ControlRequest::Commit {
+                message: message.to_string(),
+            }),
+            ("status" | "refresh", _) => Err(format!("{name} doesn't take arguments")),
+            _ => Err(format!(
+                "unknown request \"{name}\", expected status, refresh or commit"
+            )),
+        }
+    }
+}
+
+/// A request from the socket and where to send the line of JSON answering it
+#[derive(Debug, Clone)]
+pub struct Control {
+    pub request: ControlRequest,
+    pub reply: mpsc::Sender<String>,
+}
+
+/// The socket file while the app listens on it, removed when dropped so the next instance can
+/// bind the same path
+#[derive(Debug)]
+pub struct ControlSocket {
+    path: PathBuf,
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Listens on a Unix socket at the path in the background, sending each request to the app
+/// and writing its answer back to the client
+#[cfg(unix)]
+pub fn listen(
+    path: &Path,
+    send: impl Fn(AppEvent) + Send + Sync + 'static,
+) -> io::Result<ControlSocket> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::Arc;
+
+    // a socket left behind by an instance that crashed refuses connections, so it's replaced
+    if path.exists() && UnixStream::connect(path).is_err() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    let send = Arc::new(send);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let send = send.clone();
+            std::thread::spawn(move || serve(stream, send.as_ref()));
+        }
+    });
+    Ok(ControlSocket {
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn listen(
+    _path: &Path,
+    _send: impl Fn(AppEvent) + Send + Sync + 'static,
+) -> io::Result<ControlSocket> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the control socket needs Unix sockets",
+    ))
+}
+
+/// Answers the requests of one client, one line each, until it disconnects
+#[cfg(unix)]
+fn serve(stream: std::os::unix::net::UnixStream, send: &dyn Fn(AppEvent)) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut writer = stream;
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let answer = match ControlRequest::parse(&line) {
+            Ok(request) => {
+                let (reply, answer) = mpsc::channel();
+                send(AppEvent::Control(Control { request, reply }));
+                answer
+                    .recv_timeout(REPLY_TIMEOUT)
+                    .unwrap_or_else(|_| error_json("the app didn't answer"))
+            }
+            Err(e) => error_json(&e),
+        };
+        if writeln!(writer, "{answer}").is_err() {
+            return;
+        }
+    }
+}
+
+/// The text as a JSON string, quoted and escaped
+pub fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch.is_control() => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+pub fn error_json(message: &str) -> String {
+    format!("{{\"ok\":false,\"error\":{}}}", json_string(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("status", Ok(ControlRequest::Status))]
+    #[case(" refresh \n", Ok(ControlRequest::Refresh))]
+    #[case("commit fix the build", Ok(ControlRequest::Commit { message: "fix the build".into() }))]
+    #[case("commit", Err("commit needs a message, e.g. \"commit fix the build\"".into()))]
+    #[case("status now", Err("status doesn't take arguments".into()))]
+    #[case("update", Err("unknown request \"update\", expected status, refresh or commit".into()))]
+    fn test_parse(#[case] line: &str, #[case] exp: Result<ControlRequest, String>) {
+        assert_eq!(exp, ControlRequest::parse(line));
+    }
+
+    #[rstest]
+    #[case("src/a.rs", "\"src/a.rs\"")]
+    #[case("say \"hi\"\\", "\"say \\\"hi\\\"\\\\\"")]
+    #[case("a\nb\tc\u{1}", "\"a\\nb\\tc\\u0001\"")]
+    fn test_json_string(#[case] text: &str, #[case] exp: &str) {
+        assert_eq!(exp, json_string(text));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_listen() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let path = std::env::temp_dir().join(format!("svn-util-test-{}.sock", std::process::id()));
+        let socket = listen(&path, |event| {
+            if let AppEvent::Control(control) = event {
+                let _ = control.reply.send(format!("{:?}", control.request));
+            }
+        })
+        .unwrap();
+        let mut client = UnixStream::connect(&path).unwrap();
+        client.write_all(b"status\n\nbogus\n").unwrap();
+        let mut lines = BufReader::new(client).lines();
+        assert_eq!("Status", lines.next().unwrap().unwrap());
+        assert_eq!(
+            error_json("unknown request \"bogus\", expected status, refresh or commit"),
+            lines.next().unwrap().unwrap()
+        );
+        drop(socket);
+        assert!(!path.exists());
+    }
+}
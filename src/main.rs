@@ -1,29 +1,121 @@
 pub mod app;
 pub mod command;
 pub mod config;
+pub mod demo;
 pub mod error;
 pub mod event;
+pub mod ipc;
+pub mod recording;
 pub mod svn;
+#[cfg(test)]
+mod testing;
+pub mod vcs;
 
-use config::Config;
+use config::{Config, Launch};
 use crossterm::{
     ExecutableCommand,
     event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    terminal::{EnterAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode},
 };
+use ratatui::{DefaultTerminal, TerminalOptions, Viewport};
+use std::io::Write;
 
 use crate::app::App;
 
 fn main() -> color_eyre::Result<()> {
-    let mut config = Config::default();
-    config.update_from_file().unwrap();
-    config.update_from_env_args();
+    let config = match Config::load().unwrap() {
+        Launch::Run(config) => *config,
+        Launch::Completions(shell) => {
+            let mut command = config::command_line();
+            clap_complete::generate(shell, &mut command, "svn-util", &mut std::io::stdout());
+            return Ok(());
+        }
+        Launch::InitConfig(path) => {
+            config::write_default_config(&path).map_err(|e| color_eyre::eyre::eyre!(e))?;
+            println!("wrote default config to {}", path.display());
+            return Ok(());
+        }
+    };
+    if config.demo {
+        let wc = demo::create_demo_working_copy().expect("failed to create the demo repository");
+        std::env::set_current_dir(wc)?;
+    }
+    color_eyre::install()?;
+    if let Some(path) = config.replay_events.clone() {
+        let recording = std::fs::read_to_string(&path)?;
+        let events = recording::parse_recording(&recording)
+            .map_err(|e| color_eyre::eyre::eyre!("{}: {e}", path.display()))?;
+        println!("{}", App::new().with_config(config).replay(events)?);
+        return Ok(());
+    }
+    if let Some(path) = config.export_status.clone() {
+        return App::new().with_config(config).export_status(&path);
+    }
+    if config.watch {
+        return App::new().with_config(config).watch();
+    }
+    // before either way of taking over the terminal, so a panic in the minimal line doesn't leave
+    // the shell in raw mode either
+    install_panic_hook();
+    if config.minimal {
+        // drawn in place on the line the cursor is on, for a status bar pane, with the mouse
+        // and title left to the terminal
+        let terminal = ratatui::init_with_options(TerminalOptions {
+            viewport: Viewport::Inline(1),
+        });
+        let result = App::new().with_config(config).run(terminal);
+        let _ = disable_raw_mode();
+        println!();
+        return result.map(|_| ());
+    }
     std::io::stdout().execute(EnableMouseCapture).unwrap();
     std::io::stdout().execute(EnableFocusChange).unwrap();
-    color_eyre::install()?;
+    let exit_summary = config.exit_summary;
     let terminal = ratatui::init();
+    set_title();
     let result = App::new().with_config(config).run(terminal);
+    restore_terminal();
+    let summary = result?;
+    if exit_summary {
+        println!("{summary}");
+    }
+    Ok(())
+}
+
+/// Saves the terminal's title on xterm's title stack before replacing it, so
+/// [`restore_terminal`] can put it back
+fn set_title() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x1b[22;0t");
+    let _ = stdout.execute(SetTitle("svn-util"));
+}
+
+/// Restores the terminal before the panic is printed, otherwise a panic leaves the terminal in
+/// raw mode with mouse reporting still on
+fn install_panic_hook() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        hook(info);
+    }));
+}
+
+fn restore_terminal() {
     ratatui::restore();
-    std::io::stdout().execute(DisableMouseCapture).unwrap();
-    std::io::stdout().execute(DisableFocusChange).unwrap();
-    result
+    // ignore errors as this also runs while panicking, when there's nothing more to be done
+    let _ = std::io::stdout().execute(DisableMouseCapture);
+    let _ = std::io::stdout().execute(DisableFocusChange);
+    // pops the title saved by set_title, terminals without a title stack ignore it
+    let _ = std::io::stdout().write_all(b"\x1b[23;0t");
+    let _ = std::io::stdout().flush();
+}
+
+/// Takes the terminal back after [`restore_terminal`] handed it to another program
+fn resume_terminal(terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+    enable_raw_mode()?;
+    std::io::stdout().execute(EnterAlternateScreen)?;
+    std::io::stdout().execute(EnableMouseCapture)?;
+    std::io::stdout().execute(EnableFocusChange)?;
+    set_title();
+    terminal.clear()
 }
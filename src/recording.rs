@@ -0,0 +1,314 @@
+use crate::event::Event;
+use ratatui::crossterm::event::{
+    Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEvent, MouseEventKind,
+};
+use std::{
+    fs::File,
+    io::{LineWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// An event and when it happened, relative to the start of the recording
+#[derive(Debug, Clone)]
+pub struct RecordedEvent {
+    pub at: Duration,
+    pub event: Event,
+}
+
+/// Writes the terminal events of a session to a file as they happen, one per line, e.g.
+/// "1520\tkey\tchar:c\t0". The app's own events aren't recorded, as replaying the terminal's
+/// makes the app send them again.
+#[derive(Debug)]
+pub struct EventRecorder {
+    file: LineWriter<File>,
+    started: Instant,
+}
+
+impl EventRecorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: LineWriter::new(File::create(path)?),
+            started: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, event: &Event) -> std::io::Result<()> {
+        match format_event(event) {
+            Some(line) => writeln!(self.file, "{}\t{line}", self.started.elapsed().as_millis()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads a recording written by [`EventRecorder`], skipping blank lines and '#' comments
+pub fn parse_recording(text: &str) -> Result<Vec<RecordedEvent>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| parse_line(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<RecordedEvent, String> {
+    let mut fields = line.split('\t');
+    let at = fields
+        .next()
+        .and_then(|millis| millis.parse().ok())
+        .map(Duration::from_millis)
+        .ok_or("expected the milliseconds since the recording started")?;
+    let kind = fields.next().ok_or("expected the kind of event")?;
+    let fields: Vec<&str> = fields.collect();
+    let number = |i: usize| -> Result<u16, String> {
+        let field = fields.get(i).ok_or(format!("{kind} is missing a field"))?;
+        field
+            .parse()
+            .map_err(|_| format!("'{field}' isn't a number"))
+    };
+    let modifiers = |i: usize| -> Result<KeyModifiers, String> {
+        let bits = number(i)?
+            .try_into()
+            .map_err(|_| "modifiers out of range")?;
+        Ok(KeyModifiers::from_bits_truncate(bits))
+    };
+    let event = match kind {
+        "tick" => Event::Tick,
+        "key" => {
+            let code = fields.first().ok_or("key is missing its code")?;
+            let code = parse_key_code(code).ok_or(format!("unknown key '{code}'"))?;
+            Event::Crossterm(CrosstermEvent::Key(KeyEvent::new(code, modifiers(1)?)))
+        }
+        "mouse" => {
+            let mouse_kind = fields.first().ok_or("mouse is missing its kind")?;
+            Event::Crossterm(CrosstermEvent::Mouse(MouseEvent {
+                kind: parse_mouse_kind(mouse_kind)
+                    .ok_or(format!("unknown mouse event '{mouse_kind}'"))?,
+                column: number(1)?,
+                row: number(2)?,
+                modifiers: modifiers(3)?,
+            }))
+        }
+        "resize" => Event::Crossterm(CrosstermEvent::Resize(number(0)?, number(1)?)),
+        "focus-gained" => Event::Crossterm(CrosstermEvent::FocusGained),
+        "focus-lost" => Event::Crossterm(CrosstermEvent::FocusLost),
+        "paste" => Event::Crossterm(CrosstermEvent::Paste(unescape(
+            fields.first().unwrap_or(&""),
+        ))),
+        _ => return Err(format!("unknown kind of event '{kind}'")),
+    };
+    Ok(RecordedEvent { at, event })
+}
+
+/// The event as the fields of a recording line, `None` for events that aren't recorded
+fn format_event(event: &Event) -> Option<String> {
+    let event = match event {
+        Event::Tick => return Some("tick".into()),
+        Event::Crossterm(event) => event,
+        Event::App(_) => return None,
+    };
+    Some(match event {
+        // some terminals also report releases, which the app ignores
+        CrosstermEvent::Key(key) if key.kind != KeyEventKind::Press => return None,
+        CrosstermEvent::Key(key) => {
+            format!(
+                "key\t{}\t{}",
+                format_key_code(key.code)?,
+                key.modifiers.bits()
+            )
+        }
+        CrosstermEvent::Mouse(mouse) => format!(
+            "mouse\t{}\t{}\t{}\t{}",
+            format_mouse_kind(mouse.kind),
+            mouse.column,
+            mouse.row,
+            mouse.modifiers.bits()
+        ),
+        CrosstermEvent::Resize(width, height) => format!("resize\t{width}\t{height}"),
+        CrosstermEvent::FocusGained => "focus-gained".into(),
+        CrosstermEvent::FocusLost => "focus-lost".into(),
+        CrosstermEvent::Paste(text) => format!("paste\t{}", escape(text)),
+    })
+}
+
+fn format_key_code(code: KeyCode) -> Option<String> {
+    Some(match code {
+        KeyCode::Char(c) => format!("char:{}", escape(&c.to_string())),
+        KeyCode::F(n) => format!("f{n}"),
+        KeyCode::Enter => "enter".into(),
+        KeyCode::Esc => "esc".into(),
+        KeyCode::Backspace => "backspace".into(),
+        KeyCode::Tab => "tab".into(),
+        KeyCode::BackTab => "backtab".into(),
+        KeyCode::Delete => "delete".into(),
+        KeyCode::Insert => "insert".into(),
+        KeyCode::Home => "home".into(),
+        KeyCode::End => "end".into(),
+        KeyCode::PageUp => "pageup".into(),
+        KeyCode::PageDown => "pagedown".into(),
+        KeyCode::Up => "up".into(),
+        KeyCode::Down => "down".into(),
+        KeyCode::Left => "left".into(),
+        KeyCode::Right => "right".into(),
+        // the app has no use for media keys and the like
+        _ => return None,
+    })
+}
+
+fn parse_key_code(code: &str) -> Option<KeyCode> {
+    if let Some(c) = code.strip_prefix("char:") {
+        let mut chars = unescape(c).chars().collect::<Vec<_>>().into_iter();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(KeyCode::Char(c)),
+            _ => None,
+        };
+    }
+    if let Some(n) = code.strip_prefix('f').and_then(|n| n.parse().ok()) {
+        return Some(KeyCode::F(n));
+    }
+    Some(match code {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "backspace" => KeyCode::Backspace,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        _ => return None,
+    })
+}
+
+const MOUSE_BUTTONS: [(MouseButton, &str); 3] = [
+    (MouseButton::Left, "left"),
+    (MouseButton::Right, "right"),
+    (MouseButton::Middle, "middle"),
+];
+
+fn format_mouse_kind(kind: MouseEventKind) -> String {
+    let button_name = |button| {
+        MOUSE_BUTTONS
+            .iter()
+            .find(|(b, _)| *b == button)
+            .map_or("left", |(_, name)| name)
+    };
+    match kind {
+        MouseEventKind::Down(button) => format!("down-{}", button_name(button)),
+        MouseEventKind::Up(button) => format!("up-{}", button_name(button)),
+        MouseEventKind::Drag(button) => format!("drag-{}", button_name(button)),
+        MouseEventKind::Moved => "moved".into(),
+        MouseEventKind::ScrollDown => "scroll-down".into(),
+        MouseEventKind::ScrollUp => "scroll-up".into(),
+        MouseEventKind::ScrollLeft => "scroll-left".into(),
+        MouseEventKind::ScrollRight => "scroll-right".into(),
+    }
+}
+
+fn parse_mouse_kind(kind: &str) -> Option<MouseEventKind> {
+    let button = |name: &str| {
+        MOUSE_BUTTONS
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(button, _)| *button)
+    };
+    Some(match kind.split_once('-') {
+        Some(("down", name)) => MouseEventKind::Down(button(name)?),
+        Some(("up", name)) => MouseEventKind::Up(button(name)?),
+        Some(("drag", name)) => MouseEventKind::Drag(button(name)?),
+        _ => match kind {
+            "moved" => MouseEventKind::Moved,
+            "scroll-down" => MouseEventKind::ScrollDown,
+            "scroll-up" => MouseEventKind::ScrollUp,
+            "scroll-left" => MouseEventKind::ScrollLeft,
+            "scroll-right" => MouseEventKind::ScrollRight,
+            _ => return None,
+        },
+    })
+}
+
+/// Escapes the characters that would break up a recording line
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("tick")]
+    #[case("key\tchar:c\t0")]
+    #[case("key\tchar:a\t2")]
+    #[case("key\tchar:\\t\t0")]
+    #[case("key\tchar:\\\\\t0")]
+    #[case("key\tf5\t0")]
+    #[case("key\tpagedown\t0")]
+    #[case("mouse\tdown-right\t12\t3\t0")]
+    #[case("mouse\tscroll-up\t0\t7\t1")]
+    #[case("resize\t120\t40")]
+    #[case("focus-lost")]
+    #[case("paste\tone\\ttwo\\nthree")]
+    fn test_round_trip(#[case] line: &str) {
+        let recorded = parse_line(&format!("250\t{line}")).unwrap();
+        assert_eq!(Duration::from_millis(250), recorded.at);
+        assert_eq!(Some(line.to_string()), format_event(&recorded.event));
+    }
+
+    #[test]
+    fn test_parse_recording() {
+        let events =
+            parse_recording("# made by hand\n0\tresize\t80\t24\n\n40\tkey\tchar:q\t0\n").unwrap();
+        assert_eq!(2, events.len());
+        assert!(matches!(
+            events[1].event,
+            Event::Crossterm(CrosstermEvent::Key(KeyEvent {
+                code: KeyCode::Char('q'),
+                ..
+            }))
+        ));
+        assert_eq!(
+            Some("line 2: unknown key 'hyper'".to_string()),
+            parse_recording("0\ttick\n5\tkey\thyper\t0").err()
+        );
+        assert_eq!(
+            Some("line 1: expected the milliseconds since the recording started".to_string()),
+            parse_recording("soon\ttick").err()
+        );
+    }
+
+    #[test]
+    fn test_app_events_are_not_recorded() {
+        let event = Event::App(crate::event::AppEvent::UpdateRequest);
+        assert_eq!(None, format_event(&event));
+    }
+}
@@ -0,0 +1,63 @@
+use super::{Error, Result};
+use crate::command::run_command;
+
+/// A line of 'svn blame' output: who last changed it and in which revision
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlameLine {
+    /// `None` for a line changed locally and not committed yet
+    pub revision: Option<u32>,
+    pub author: Option<String>,
+    pub text: String,
+}
+
+pub fn get_blame(path: &str) -> Result<Vec<BlameLine>> {
+    let res = run_command("svn", &["blame", "--non-interactive", path])?;
+    match res.success() {
+        true => Ok(parse_blame(res.output())),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// Reads lines like "    12      alice fn main() {". The revision and author are padded to a
+/// width, but an author longer than it pushes the text along, so they're split on whitespace.
+/// Local changes have "-" for both.
+pub fn parse_blame(output: &str) -> Vec<BlameLine> {
+    output
+        .lines()
+        .map(|line| {
+            let rest = line.trim_start();
+            let (revision, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+            let rest = rest.trim_start();
+            let (author, text) = rest.split_once(' ').unwrap_or((rest, ""));
+            BlameLine {
+                revision: revision.parse().ok(),
+                author: Some(author.to_string()).filter(|author| author != "-"),
+                text: text.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blame() {
+        let output = "     3      alice fn main() {\n    12 a-very-long-name     println!(\"hi\");\n     -          - }\n     3      alice \n";
+        let line = |revision, author: Option<&str>, text: &str| BlameLine {
+            revision,
+            author: author.map(String::from),
+            text: text.to_string(),
+        };
+        assert_eq!(
+            vec![
+                line(Some(3), Some("alice"), "fn main() {"),
+                line(Some(12), Some("a-very-long-name"), "    println!(\"hi\");"),
+                line(None, None, "}"),
+                line(Some(3), Some("alice"), ""),
+            ],
+            parse_blame(output)
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use super::{Error, Result};
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// What a running checkout has done, sent as each line of svn's output is read
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckoutProgress {
+    /// A file or directory was checked out, with the size of the file
+    Added { path: PathBuf, bytes: u64 },
+    /// The checkout ended, with the revision that was checked out or why it failed
+    Finished(std::result::Result<u32, String>),
+}
+
+/// A line of 'svn checkout' output
+#[derive(Debug, PartialEq)]
+enum CheckoutLine {
+    Added(PathBuf),
+    Revision(u32),
+    Other,
+}
+
+/// An 'svn checkout' running in the background, killed if cancelled
+#[derive(Debug)]
+pub struct Checkout {
+    child: Arc<Mutex<Child>>,
+}
+
+impl Checkout {
+    /// Starts checking out the url into the directory, calling `on_progress` from another
+    /// thread for every checked out path and once more when the checkout ends
+    pub fn start(
+        url: &str,
+        dest: &Path,
+        on_progress: impl Fn(CheckoutProgress) + Send + 'static,
+    ) -> Result<Self> {
+        let mut child = Command::new("svn")
+            .args(["checkout", "--non-interactive", url])
+            .arg(dest)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().ok_or(Error::Unknown(
+            "could not read the output of svn checkout".into(),
+        ))?;
+        let mut stderr = child.stderr.take();
+        let child = Arc::new(Mutex::new(child));
+        let waiting_child = child.clone();
+        thread::spawn(move || {
+            let mut revision = None;
+            for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                match parse_checkout_line(&line) {
+                    CheckoutLine::Added(path) => {
+                        let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                        on_progress(CheckoutProgress::Added { path, bytes });
+                    }
+                    CheckoutLine::Revision(rev) => revision = Some(rev),
+                    CheckoutLine::Other => {}
+                }
+            }
+            let mut error = String::new();
+            if let Some(stderr) = stderr.as_mut() {
+                let _ = stderr.read_to_string(&mut error);
+            }
+            let status = waiting_child.lock().expect("checkout lock poisoned").wait();
+            let result = match (status, revision) {
+                (Ok(status), Some(rev)) if status.success() => Ok(rev),
+                (Ok(_), _) if error.trim().is_empty() => Err("checkout cancelled".into()),
+                (Ok(_), _) => Err(error.trim().to_string()),
+                (Err(e), _) => Err(e.to_string()),
+            };
+            on_progress(CheckoutProgress::Finished(result));
+        });
+        Ok(Self { child })
+    }
+
+    pub fn cancel(&self) {
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// The directory svn checks a url out into when none is given, i.e. its last path segment
+pub fn default_checkout_dir(url: &str) -> PathBuf {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty() && !name.ends_with(':'))
+        .unwrap_or("checkout");
+    PathBuf::from(name)
+}
+
+fn parse_checkout_line(line: &str) -> CheckoutLine {
+    if let Some(rev) = line
+        .strip_prefix("Checked out revision ")
+        .and_then(|rest| rest.trim_end_matches('.').parse().ok())
+    {
+        return CheckoutLine::Revision(rev);
+    }
+    // e.g. "A    trunk/file.txt", where the path starts after the 5 status columns
+    match line.split_at_checked(5) {
+        Some((status, path)) if status.starts_with('A') && status[1..].trim().is_empty() => {
+            CheckoutLine::Added(PathBuf::from(path))
+        }
+        _ => CheckoutLine::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("A    wc/src/main.c", CheckoutLine::Added("wc/src/main.c".into()))]
+    #[case("A    wc/dir with spaces", CheckoutLine::Added("wc/dir with spaces".into()))]
+    #[case("Checked out revision 42.", CheckoutLine::Revision(42))]
+    #[case(" U   wc", CheckoutLine::Other)]
+    #[case("", CheckoutLine::Other)]
+    fn test_parse_checkout_line(#[case] line: &str, #[case] exp: CheckoutLine) {
+        assert_eq!(exp, parse_checkout_line(line));
+    }
+
+    #[rstest]
+    #[case("https://svn.example.com/repo/trunk", "trunk")]
+    #[case("https://svn.example.com/repo/trunk/", "trunk")]
+    #[case("file:///tmp/repo", "repo")]
+    #[case("https://", "checkout")]
+    fn test_default_checkout_dir(#[case] url: &str, #[case] exp: &str) {
+        assert_eq!(PathBuf::from(exp), default_checkout_dir(url));
+    }
+}
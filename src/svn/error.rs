@@ -1,12 +1,36 @@
+use crate::command::{CmdResult, SvnErrorCode, is_transient_error, parse_error_codes};
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     PathNotUnderVersionControl(String),
+    /// Another svn command is using the working copy, or one was interrupted
+    LockedWorkingCopy(String),
+    /// The repository rejected the credentials, or svn had none to give it
+    AuthFailed(String),
+    /// The repository couldn't be reached, which may well work if tried again
+    Network(String),
+    /// Any other error svn gave a code for
+    Svn(SvnErrorCode, String),
     BranchParseFailure,
     UnrecognisedStatus(String),
     Unknown(String),
     Io(#[from] std::io::Error),
 }
 
+impl Error {
+    /// The svn error code the error was made from, if it was one of svn's
+    pub fn code(&self) -> Option<SvnErrorCode> {
+        match self {
+            Error::PathNotUnderVersionControl(_) => Some(NOT_A_WORKING_COPY),
+            Error::LockedWorkingCopy(_) => Some(WORKING_COPY_LOCKED),
+            Error::AuthFailed(_) => Some(AUTH_FAILED),
+            Error::Network(msg) => parse_error_codes(msg).first().copied(),
+            Error::Svn(code, _) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -15,7 +39,12 @@ impl PartialEq for Error {
             }
             (Error::Io(error), Error::Io(other)) => error.kind() == other.kind(),
             (Error::Unknown(s), Error::Unknown(s2)) => s == s2,
+            (Error::LockedWorkingCopy(s), Error::LockedWorkingCopy(s2)) => s == s2,
+            (Error::AuthFailed(s), Error::AuthFailed(s2)) => s == s2,
+            (Error::Network(s), Error::Network(s2)) => s == s2,
+            (Error::Svn(code, s), Error::Svn(code2, s2)) => code == code2 && s == s2,
             (Error::BranchParseFailure, Error::BranchParseFailure) => true,
+            (Error::UnrecognisedStatus(s), Error::UnrecognisedStatus(s2)) => s == s2,
             _ => false,
         }
     }
@@ -26,6 +55,18 @@ impl std::fmt::Display for Error {
         let msg = match self {
             Error::PathNotUnderVersionControl(p) => format!("Not svn controlled: {p}"),
             Error::Io(error) => error.to_string(),
+            Error::LockedWorkingCopy(s) => {
+                format!(
+                    "the working copy is locked, it may need cleaning up: {}",
+                    s.trim()
+                )
+            }
+            Error::AuthFailed(s) => format!(
+                "authentication failed, run svn in a terminal to save your credentials: {}",
+                s.trim()
+            ),
+            Error::Network(s) => format!("could not reach the repository: {}", s.trim()),
+            Error::Svn(_, s) => s.trim().to_string(),
             Error::Unknown(s) => s.clone(),
             Error::UnrecognisedStatus(status) => format!("Unrecognised status: {status}"),
             Error::BranchParseFailure => "failed to parse URL from svn info".into(),
@@ -34,10 +75,38 @@ impl std::fmt::Display for Error {
     }
 }
 
+/// svn's error code for "is not a working copy"
+pub const NOT_A_WORKING_COPY: SvnErrorCode = SvnErrorCode(155007);
+
+/// svn's error code for "working copy locked"
+pub const WORKING_COPY_LOCKED: SvnErrorCode = SvnErrorCode(155004);
+
+/// svn's error code for "authorization failed"
+pub const AUTH_FAILED: SvnErrorCode = SvnErrorCode(170001);
+
+impl From<&CmdResult> for Error {
+    fn from(value: &CmdResult) -> Self {
+        Error::from(value.output())
+    }
+}
+
 impl From<&str> for Error {
+    /// Uses the svn error codes in the message, e.g. a failed command's stderr, to pick the
+    /// error
     fn from(value: &str) -> Self {
-        match value {
-            _ => Error::Unknown(value.into()), // consider any errors that fall back to Unknown to see iof they could have their own discriminant
+        let codes = parse_error_codes(value);
+        if codes.contains(&NOT_A_WORKING_COPY) {
+            Error::PathNotUnderVersionControl(value.trim().into())
+        } else if codes.contains(&WORKING_COPY_LOCKED) {
+            Error::LockedWorkingCopy(value.into())
+        } else if codes.contains(&AUTH_FAILED) {
+            Error::AuthFailed(value.into())
+        } else if is_transient_error(value) {
+            Error::Network(value.into())
+        } else if let Some(code) = codes.first() {
+            Error::Svn(*code, value.into())
+        } else {
+            Error::Unknown(value.into())
         }
     }
 }
@@ -55,3 +124,37 @@ impl From<String> for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "svn: E155007: '/tmp' is not a working copy\n",
+        Error::PathNotUnderVersionControl("svn: E155007: '/tmp' is not a working copy".into())
+    )]
+    #[case(
+        "svn: E155004: Run 'svn cleanup' to remove locks\n",
+        Error::LockedWorkingCopy("svn: E155004: Run 'svn cleanup' to remove locks\n".into())
+    )]
+    #[case(
+        "svn: E170001: Authorization failed\n",
+        Error::AuthFailed("svn: E170001: Authorization failed\n".into())
+    )]
+    #[case(
+        "svn: E175002: Connection reset\n",
+        Error::Network("svn: E175002: Connection reset\n".into())
+    )]
+    #[case(
+        "svn: E200009: Could not display info\n",
+        Error::Svn(SvnErrorCode(200009), "svn: E200009: Could not display info\n".into())
+    )]
+    #[case("unknown issue with svn", Error::Unknown("unknown issue with svn".into()))]
+    fn test_from_str(#[case] stderr: &str, #[case] exp: Error) {
+        let error = Error::from(stderr);
+        assert_eq!(exp, error);
+        assert_eq!(parse_error_codes(stderr).first(), error.code().as_ref());
+    }
+}
@@ -1,36 +1,192 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use super::{
-    Conflict, ConflictPart, ParsedStatusLine, create_empty_text_conflict, is_conflict_part,
-    parse_conflict_part, parse_svn_status, state::State, trim_conflict_suffix,
+    Conflict, ConflictPart, LockStatus, PropertyStatus, StatusEntry, create_empty_text_conflict,
+    is_conflict_part, parse_changelists, parse_conflict_part, parse_svn_status, state::State,
+    trim_conflict_suffix,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FileList {
-    list: Vec<ParsedStatusLine>,
+    list: Vec<StatusEntry>,
+    /// The changelist each path belongs to, if any
+    changelists: HashMap<PathBuf, String>,
+    /// Changelists whose members are left out of the renderable list
+    hidden_changelists: Vec<String>,
+    /// Whether entries without text changes, i.e. only property changes, are left out of the
+    /// renderable list
+    hide_property_only: bool,
+    /// Globs of the paths left out of the renderable list, see [`matches_hide_pattern`]
+    hide_patterns: Vec<String>,
 }
 
 impl FileList {
-    pub fn list(&self) -> &[ParsedStatusLine] {
+    pub fn list(&self) -> &[StatusEntry] {
         &self.list
     }
 
-    pub fn list_mut(&mut self) -> &mut Vec<ParsedStatusLine> {
+    pub fn list_mut(&mut self) -> &mut Vec<StatusEntry> {
         &mut self.list
     }
 
     pub fn empty() -> Self {
-        Self { list: vec![] }
+        Self {
+            list: vec![],
+            changelists: HashMap::new(),
+            hidden_changelists: vec![],
+            hide_property_only: false,
+            hide_patterns: vec![],
+        }
+    }
+
+    pub fn from_svn_status(svn_status: &str) -> super::Result<Self> {
+        let mut list = Self::empty();
+        list.populate_from_svn_status(svn_status)?;
+        Ok(list)
     }
 
     pub fn populate_from_svn_status(&mut self, svn_status: &str) -> super::Result<()> {
         *self.list_mut() = parse_svn_status(svn_status)?;
+        self.changelists = parse_changelists(svn_status);
         Ok(())
     }
 
+    /// Replaces the changes with those from a newer status, keeping display settings. Returns
+    /// whether anything changed, the list being left alone if nothing did.
+    pub fn replace_changes(&mut self, other: FileList) -> bool {
+        if self.list == other.list && self.changelists == other.changelists {
+            return false;
+        }
+        self.list = other.list;
+        self.changelists = other.changelists;
+        true
+    }
+
+    /// Replaces the changes to the paths, anything inside them and their conflict parts with
+    /// those from a status of just the paths, leaving every other change as it was
+    pub fn patch_changes(&mut self, paths: &[PathBuf], other: FileList) {
+        let is_stale = |path: &Path| {
+            let path_str = path.to_string_lossy();
+            paths.iter().any(|p| {
+                path.starts_with(p)
+                    || (is_conflict_part(&path_str)
+                        && Path::new(trim_conflict_suffix(&path_str)) == p)
+            })
+        };
+        let position = self.list.iter().position(|entry| is_stale(&entry.path));
+        self.list.retain(|entry| !is_stale(&entry.path));
+        self.changelists.retain(|path, _| !is_stale(path));
+        let index = position.unwrap_or(self.list.len());
+        self.list.splice(index..index, other.list);
+        self.changelists.extend(other.changelists);
+    }
+
+    /// Appends the changes from the status of another part of the working copy
+    pub fn extend_changes(&mut self, other: FileList) {
+        self.list.extend(other.list);
+        self.changelists.extend(other.changelists);
+    }
+
+    pub fn changelist(&self, path: &Path) -> Option<&str> {
+        self.changelists.get(path).map(String::as_str)
+    }
+
+    /// The change to the path, if it has one
+    pub fn entry(&self, path: &Path) -> Option<&StatusEntry> {
+        self.list.iter().find(|entry| entry.path == path)
+    }
+
+    pub fn lock(&self, path: &Path) -> Option<LockStatus> {
+        self.entry(path).and_then(|entry| entry.lock)
+    }
+
+    pub fn property_status(&self, path: &Path) -> Option<PropertyStatus> {
+        self.entry(path).and_then(|entry| entry.properties)
+    }
+
+    /// Whether only the entry's properties changed, not its contents
+    fn is_property_only(entry: &StatusEntry) -> bool {
+        entry.state == State::Clean && entry.properties.is_some()
+    }
+
+    pub fn in_changelist(&self, path: &Path, changelists: &[String]) -> bool {
+        self.changelist(path)
+            .is_some_and(|name| changelists.iter().any(|c| c == name))
+    }
+
+    /// Every changed path in the changelist, including any that are hidden
+    pub fn changelist_members(&self, name: &str) -> Vec<&PathBuf> {
+        self.list
+            .iter()
+            .map(|entry| &entry.path)
+            .filter(|path| self.changelist(path) == Some(name))
+            .collect()
+    }
+
+    pub fn set_hidden_changelists(&mut self, changelists: Vec<String>) {
+        self.hidden_changelists = changelists;
+    }
+
+    pub fn hidden_changelists(&self) -> &[String] {
+        &self.hidden_changelists
+    }
+
+    pub fn set_hide_property_only(&mut self, hide: bool) {
+        self.hide_property_only = hide;
+    }
+
+    pub fn hide_property_only(&self) -> bool {
+        self.hide_property_only
+    }
+
+    pub fn set_hide_patterns(&mut self, patterns: Vec<String>) {
+        self.hide_patterns = patterns;
+    }
+
+    /// How many entries the hide patterns leave out
+    pub fn hidden_by_pattern_count(&self) -> usize {
+        self.list()
+            .iter()
+            .filter(|entry| self.is_hidden_by_pattern(&entry.path))
+            .count()
+    }
+
+    fn is_hidden_by_pattern(&self, path: &Path) -> bool {
+        self.hide_patterns
+            .iter()
+            .any(|pattern| matches_hide_pattern(pattern, path))
+    }
+
+    /// How many property-only entries are being hidden, so the list doesn't look cleaner
+    /// than the working copy is
+    pub fn hidden_property_only_count(&self) -> usize {
+        match self.hide_property_only {
+            true => self
+                .list()
+                .iter()
+                .filter(|entry| Self::is_property_only(entry) && self.is_listed(&entry.path))
+                .count(),
+            false => 0,
+        }
+    }
+
+    fn is_renderable(&self, entry: &StatusEntry) -> bool {
+        !(self.hide_property_only && Self::is_property_only(entry)) && self.is_listed(&entry.path)
+    }
+
+    /// Whether the path is shown whatever its state
+    fn is_listed(&self, path: &Path) -> bool {
+        !is_conflict_part(path.to_str().unwrap())
+            && !self.in_changelist(path, &self.hidden_changelists)
+            && !self.is_hidden_by_pattern(path)
+    }
+
     pub fn conflicts(&self) -> Vec<Conflict> {
         let mut conflict_map = HashMap::new();
-        for (state, path) in self.list().iter() {
+        for StatusEntry { state, path, .. } in self.list().iter() {
             let path_str = &path.to_str().expect("bad path");
             if *state == State::Conflicting && !conflict_map.contains_key(path_str) {
                 conflict_map.insert(*path_str, create_empty_text_conflict(path));
@@ -63,33 +219,235 @@ impl FileList {
         conflict_map.into_values().collect()
     }
 
+    /// The paths of the conflicted changes
+    pub fn conflicted_paths(&self) -> Vec<&PathBuf> {
+        self.list()
+            .iter()
+            .filter(|entry| entry.state == State::Conflicting)
+            .map(|entry| &entry.path)
+            .collect()
+    }
+
     pub fn has_conflicts(&self) -> bool {
         self.list()
             .iter()
-            .any(|(state, _)| *state == State::Conflicting)
+            .any(|entry| entry.state == State::Conflicting)
     }
 
-    pub fn get(&self, index: usize) -> Option<&(State, PathBuf)> {
+    pub fn get(&self, index: usize) -> Option<&StatusEntry> {
         self.list()
             .iter()
-            .filter(|(_, path)| !is_conflict_part(path.to_str().unwrap()))
+            .filter(|entry| self.is_renderable(entry))
             .nth(index)
     }
 
-    pub fn renderable(&self) -> Vec<&ParsedStatusLine> {
+    pub fn renderable(&self) -> Vec<&StatusEntry> {
         self.list()
             .iter()
-            .filter(|(_, path)| !is_conflict_part(path.to_str().unwrap()))
+            .filter(|entry| self.is_renderable(entry))
             .collect()
     }
+
+    /// How many renderable changes there are of each state, most urgent first, leaving out
+    /// states with no changes
+    pub fn state_counts(&self) -> Vec<(State, usize)> {
+        let renderable = self.renderable();
+        [
+            State::Conflicting,
+            State::Missing,
+            State::Modified,
+            State::Replaced,
+            State::Added,
+            State::Deleted,
+            State::Unversioned,
+        ]
+        .into_iter()
+        .map(|state| {
+            let count = renderable.iter().filter(|e| e.state == state).count();
+            (state, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect()
+    }
+}
+
+/// Whether the path matches the glob, where `*` is any part of a name, `?` any one character
+/// and `**` any number of directories. A pattern without a '/' matches the file name in any
+/// directory, and one ending in "/**" the directory itself as well as everything in it.
+fn matches_hide_pattern(pattern: &str, path: &Path) -> bool {
+    let names: Vec<String> = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    if !pattern.contains('/') {
+        return names.last().is_some_and(|name| matches_name(pattern, name));
+    }
+    let parts: Vec<&str> = pattern.trim_start_matches('/').split('/').collect();
+    matches_parts(&parts, &names)
+}
+
+/// Whether the path's names match the pattern's, a "**" matching none or more of them
+fn matches_parts(parts: &[&str], names: &[&str]) -> bool {
+    match (parts, names) {
+        ([], []) => true,
+        (["**", rest @ ..], _) => (0..=names.len()).any(|skip| matches_parts(rest, &names[skip..])),
+        ([part, parts @ ..], [name, names @ ..]) => {
+            matches_name(part, name) && matches_parts(parts, names)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the name matches the glob, with `*` and `?` not crossing directories
+fn matches_name(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern, name) {
+            ([], []) => true,
+            (['*', rest @ ..], _) => (0..=name.len()).any(|skip| matches(rest, &name[skip..])),
+            (['?', rest @ ..], [_, name @ ..]) => matches(rest, name),
+            ([c, rest @ ..], [n, name @ ..]) => c == n && matches(rest, name),
+            _ => false,
+        }
+    }
+    matches(&pattern, &name)
 }
 
 #[cfg(test)]
 mod tests {
     use super::State::*;
     use super::*;
+    use rstest::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_patch_changes() {
+        let mut l = FileList::from_svn_status(
+            "\
+M       a.txt
+C       b.txt
+?       b.txt.merge-left.r1
+?       b.txt.working
+A       dir
+A       dir/new.txt
+M       z.txt
+--- Changelist 'feature':
+M       in_feature.txt
+",
+        )
+        .unwrap();
+        let paths = [PathBuf::from("b.txt"), PathBuf::from("dir")];
+        l.patch_changes(
+            &paths,
+            FileList::from_svn_status("M       b.txt\n").unwrap(),
+        );
+        let expected: Vec<StatusEntry> = ["a.txt", "b.txt", "z.txt", "in_feature.txt"]
+            .into_iter()
+            .map(|path| StatusEntry::new(Modified, path))
+            .collect();
+        assert_eq!(expected, l.list());
+        assert_eq!(Some("feature"), l.changelist(Path::new("in_feature.txt")));
+    }
+
+    #[rstest]
+    #[case("*.orig", "a.rs.orig", true)]
+    #[case("*.orig", "src/deep/a.rs.orig", true)]
+    #[case("*.orig", "src/a.rs", false)]
+    #[case("target/**", "target", true)]
+    #[case("target/**", "target/debug/app", true)]
+    #[case("target/**", "src/target/app", false)]
+    #[case("src/*.rs", "src/a.rs", true)]
+    #[case("src/*.rs", "src/app/a.rs", false)]
+    #[case("**/gen/*.rs", "src/gen/a.rs", true)]
+    #[case("**/gen/*.rs", "gen/a.rs", true)]
+    #[case("a?.txt", "ab.txt", true)]
+    #[case("a?.txt", "a.txt", false)]
+    fn test_matches_hide_pattern(#[case] pattern: &str, #[case] path: &str, #[case] exp: bool) {
+        assert_eq!(exp, matches_hide_pattern(pattern, Path::new(path)));
+    }
+
+    #[test]
+    fn test_hide_patterns() {
+        let mut l = FileList::from_svn_status(
+            "M       a.txt\n?       a.txt.orig\n?       target\nC       target/gen.rs\n",
+        )
+        .unwrap();
+        l.set_hide_patterns(vec!["*.orig".into(), "target/**".into()]);
+        assert_eq!(vec![&StatusEntry::new(Modified, "a.txt")], l.renderable());
+        assert_eq!(3, l.hidden_by_pattern_count());
+        // the conflicts are still there to resolve
+        assert!(l.has_conflicts());
+    }
+
+    #[test]
+    fn test_replace_changes() {
+        let mut l = FileList::from_svn_status("M       a.txt\n").unwrap();
+        l.set_hide_property_only(true);
+        assert!(!l.replace_changes(FileList::from_svn_status("M       a.txt\n").unwrap()));
+        assert!(l.replace_changes(FileList::from_svn_status("M    K  a.txt\n").unwrap()));
+        assert_eq!(Some(LockStatus::Mine), l.lock(Path::new("a.txt")));
+        assert!(l.hide_property_only());
+    }
+
+    #[test]
+    fn test_patched_locks() {
+        let mut l = FileList::from_svn_status("M    K  a.psd\n     O  b.psd\n").unwrap();
+        // committing without keeping locks releases them
+        l.patch_changes(&[PathBuf::from("a.psd")], FileList::empty());
+        assert_eq!(None, l.lock(Path::new("a.psd")));
+        assert_eq!(Some(LockStatus::Other), l.lock(Path::new("b.psd")));
+    }
+
+    #[test]
+    fn test_property_status() {
+        let mut l =
+            FileList::from_svn_status("MM      a.txt\n M      dir\n C      b.txt\nM       c.txt\n")
+                .unwrap();
+        assert_eq!(
+            Some(PropertyStatus::Modified),
+            l.property_status(Path::new("a.txt"))
+        );
+        assert_eq!(
+            Some(PropertyStatus::Modified),
+            l.property_status(Path::new("dir"))
+        );
+        assert_eq!(
+            Some(PropertyStatus::Conflicted),
+            l.property_status(Path::new("b.txt"))
+        );
+        assert_eq!(None, l.property_status(Path::new("c.txt")));
+        // reverting the properties is a change even though the states stay the same
+        assert!(l.replace_changes(FileList::from_svn_status("MM      a.txt\n").unwrap()));
+        l.patch_changes(
+            &[PathBuf::from("a.txt")],
+            FileList::from_svn_status("M       a.txt\n").unwrap(),
+        );
+        assert_eq!(None, l.property_status(Path::new("a.txt")));
+    }
+
+    #[test]
+    fn test_hide_property_only() {
+        let mut l = FileList::from_svn_status(
+            "
+ M      .
+M       a.txt
+ M      dir
+--- Changelist 'hidden':
+ M      in_hidden.txt
+",
+        )
+        .unwrap();
+        l.set_hidden_changelists(vec!["hidden".into()]);
+        assert_eq!(3, l.renderable().len());
+        assert_eq!(0, l.hidden_property_only_count());
+        l.set_hide_property_only(true);
+        assert_eq!(vec![&StatusEntry::new(Modified, "a.txt")], l.renderable());
+        // the one in the hidden changelist is hidden anyway
+        assert_eq!(2, l.hidden_property_only_count());
+    }
+
     #[test]
     fn can_populate_from_svn_output() {
         let svn_output = "
@@ -108,23 +466,28 @@ C       conflict.txt
         let mut l = FileList::empty();
         l.populate_from_svn_status(svn_output)
             .expect("failed to populate");
-        assert_eq!(true, l.has_conflicts());
+        assert!(l.has_conflicts());
+        assert_eq!(vec![&PathBuf::from("conflict.txt")], l.conflicted_paths());
         assert_eq!(
             l,
             FileList {
                 list: vec![
-                    (Modified, "file1.txt".into()),
-                    (Modified, "dir1/file2.txt".into()),
-                    (Modified, "dir1/nested1/file3.txt".into()),
-                    (Added, "dir2/newfile1.txt".into()),
-                    (Added, "dir2/newimage.png".into()),
-                    (Clean, ".".into()),
-                    (Replaced, "replaced.txt".into()),
-                    (Missing, "missing.txt".into()),
-                    (Unversioned, "new.txt".into()),
-                    (Deleted, "deleted.txt".into()),
-                    (Conflicting, "conflict.txt".into()),
-                ]
+                    StatusEntry::new(Modified, "file1.txt"),
+                    StatusEntry::new(Modified, "dir1/file2.txt"),
+                    StatusEntry::new(Modified, "dir1/nested1/file3.txt"),
+                    StatusEntry::new(Added, "dir2/newfile1.txt"),
+                    StatusEntry::new(Added, "dir2/newimage.png"),
+                    StatusEntry {
+                        properties: Some(PropertyStatus::Modified),
+                        ..StatusEntry::new(Clean, ".")
+                    },
+                    StatusEntry::new(Replaced, "replaced.txt"),
+                    StatusEntry::new(Missing, "missing.txt"),
+                    StatusEntry::new(Unversioned, "new.txt"),
+                    StatusEntry::new(Deleted, "deleted.txt"),
+                    StatusEntry::new(Conflicting, "conflict.txt"),
+                ],
+                ..FileList::empty()
             }
         )
     }
@@ -146,7 +509,7 @@ Summary of conflicts:
         let mut l = FileList::empty();
         l.populate_from_svn_status(svn_output)
             .expect("failed to populate");
-        assert_eq!(true, l.has_conflicts());
+        assert!(l.has_conflicts());
         assert_eq!(
             vec![Conflict::Text {
                 file: PathBuf::from("dir1/file3.txt"),
@@ -178,4 +541,34 @@ Summary of conflicts:
         assert_eq!(Some(&l.list()[5]), l.get(2)); // the get method skips the conflict parts
         assert_eq!(Some(&l.list()[0]), l.get(0));
     }
+
+    #[test]
+    fn can_hide_changelists() {
+        let svn_output = "M       file1.txt
+
+--- Changelist 'ignore-on-commit':
+M       local.cfg
+";
+        let mut l = FileList::from_svn_status(svn_output).expect("failed to populate");
+        assert_eq!(2, l.renderable().len());
+        assert_eq!(
+            Some("ignore-on-commit"),
+            l.changelist(Path::new("local.cfg"))
+        );
+        l.set_hidden_changelists(vec!["ignore-on-commit".into()]);
+        assert_eq!(vec![&l.list()[0]], l.renderable());
+        assert_eq!(None, l.get(1));
+    }
+
+    #[test]
+    fn counts_renderable_changes_by_state() {
+        let l = FileList::from_svn_status(
+            "M       a.txt\nM       b.txt\n?       c.txt\nC       d.txt\n?       d.txt.working\n",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![(Conflicting, 1), (Modified, 2), (Unversioned, 1)],
+            l.state_counts()
+        );
+    }
 }
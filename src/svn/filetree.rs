@@ -1,7 +1,7 @@
 #![allow(dead_code, unused_variables)]
 use std::path::PathBuf;
 
-use super::state::State;
+use super::{StatusEntry, state::State};
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -20,7 +20,7 @@ impl Tree {
     pub fn build_from_svn_status(svn_status: &str) -> super::Result<Self> {
         let mut parsed = super::parse_svn_status(svn_status)?;
         let t = Self::default();
-        for (i, (_, path)) in parsed.iter_mut().enumerate() {
+        for (i, StatusEntry { path, .. }) in parsed.iter_mut().enumerate() {
             let components = path.components().collect::<Vec<_>>();
         }
         Ok(t)
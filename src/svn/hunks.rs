@@ -0,0 +1,117 @@
+/// A file's diff from 'svn diff', split into hunks that can be picked separately
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    /// The "Index:" and "---"/"+++" lines before the first hunk
+    pub header: Vec<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// The "@@ -1,3 +1,4 @@" line
+    pub range: String,
+    /// The context, added and removed lines, with their ' ', '+' or '-' prefixes
+    pub lines: Vec<String>,
+}
+
+impl FileDiff {
+    /// Splits the diff of a single file into its hunks, `None` if it has none, e.g. it's a
+    /// binary file or only its properties changed
+    pub fn parse(diff: &str) -> Option<Self> {
+        let mut header = vec![];
+        let mut hunks: Vec<Hunk> = vec![];
+        for line in diff.lines() {
+            if line.starts_with("@@") {
+                hunks.push(Hunk {
+                    range: line.to_string(),
+                    lines: vec![],
+                });
+            } else if let Some(hunk) = hunks.last_mut() {
+                // property changes come after the hunks, and aren't part of any of them
+                if line.starts_with("Property changes on:") {
+                    break;
+                }
+                hunk.lines.push(line.to_string());
+            } else {
+                header.push(line.to_string());
+            }
+        }
+        match hunks.is_empty() {
+            true => None,
+            false => Some(Self { header, hunks }),
+        }
+    }
+
+    /// A patch of just the picked hunks, for 'svn patch' to apply to the unmodified file.
+    /// svn patch finds where each hunk goes by its context, so leaving hunks out is fine.
+    pub fn patch_with(&self, picked: &[bool]) -> String {
+        let mut patch = self.header.join("\n");
+        for (hunk, _) in self.hunks.iter().zip(picked).filter(|(_, picked)| **picked) {
+            patch.push('\n');
+            patch.push_str(&hunk.range);
+            for line in &hunk.lines {
+                patch.push('\n');
+                patch.push_str(line);
+            }
+        }
+        patch.push('\n');
+        patch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+Index: a.txt
+===================================================================
+--- a.txt\t(revision 3)
++++ a.txt\t(working copy)
+@@ -1,3 +1,3 @@
+-one
++ONE
+ two
+ three
+@@ -8,3 +8,4 @@
+ eight
+ nine
+ ten
++eleven
+";
+
+    #[test]
+    fn test_parse() {
+        let diff = FileDiff::parse(DIFF).unwrap();
+        assert_eq!(4, diff.header.len());
+        assert_eq!(
+            vec!["@@ -1,3 +1,3 @@", "@@ -8,3 +8,4 @@"],
+            diff.hunks.iter().map(|h| &h.range).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["-one", "+ONE", " two", " three"], diff.hunks[0].lines);
+        assert_eq!(
+            None,
+            FileDiff::parse("Index: image.png\nCannot display: file marked as a binary type.\n")
+        );
+    }
+
+    #[test]
+    fn test_patch_with() {
+        let diff = FileDiff::parse(DIFF).unwrap();
+        assert_eq!(DIFF, diff.patch_with(&[true, true]));
+        assert_eq!(
+            "\
+Index: a.txt
+===================================================================
+--- a.txt\t(revision 3)
++++ a.txt\t(working copy)
+@@ -8,3 +8,4 @@
+ eight
+ nine
+ ten
++eleven
+",
+            diff.patch_with(&[false, true])
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use super::{Error, Result, log::get_log, state::State};
+use crate::command::{RetryPolicy, run_command_retrying};
+use std::{path::PathBuf, str::FromStr};
+
+/// What an update would pull in, from 'svn status --show-updates'
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Incoming {
+    /// The revision the repository is at, which the paths were compared against
+    pub head: Option<u32>,
+    pub changes: Vec<IncomingChange>,
+}
+
+/// A path the repository has a newer version of
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomingChange {
+    pub path: PathBuf,
+    /// What's changed in the working copy, `Clean` when an update only brings changes in
+    pub local: State,
+    /// The revision the working copy has, `None` for paths it doesn't have yet
+    pub revision: Option<u32>,
+}
+
+/// Asks the repository which paths under the path are out of date
+pub fn get_incoming(path: &str, policy: &RetryPolicy) -> Result<Incoming> {
+    let res = run_command_retrying(
+        "svn",
+        &["status", "--show-updates", "--non-interactive", path],
+        policy,
+    )?;
+    match res.success() {
+        true => Ok(parse_incoming(res.output())),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// How many revisions that changed the path the repository has past the working copy's. A
+/// log of BASE:HEAD includes BASE itself when it changed the path, so it isn't counted.
+pub fn get_revisions_behind(path: &str, policy: &RetryPolicy) -> Result<usize> {
+    let args = ["info", "--show-item", "revision", path];
+    let res = run_command_retrying("svn", &args, policy)?;
+    if !res.success() {
+        return Err(Error::from(&res));
+    }
+    let output = res.output().trim();
+    let base: u32 = output
+        .parse()
+        .map_err(|_| Error::Unknown(format!("unrecognised revision \"{output}\"")))?;
+    let entries = get_log(&["--quiet", "-r", "BASE:HEAD", path], policy)?;
+    Ok(entries.iter().filter(|entry| entry.revision > base).count())
+}
+
+/// Reads the lines marked out of date, laid out as the seven status columns, a space, then
+/// `*`, a space, the working revision right aligned in eight characters, three spaces and the
+/// path, e.g. `M       *       42   src/a.rs`
+fn parse_incoming(output: &str) -> Incoming {
+    let mut incoming = Incoming::default();
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("Status against revision:") {
+            incoming.head = head.trim().parse().ok();
+            continue;
+        }
+        if line.get(8..9) != Some("*") {
+            continue;
+        }
+        let (Some(revision), Some(path)) = (line.get(10..18), line.get(21..)) else {
+            continue;
+        };
+        incoming.changes.push(IncomingChange {
+            path: PathBuf::from(path),
+            local: State::from_str(line).unwrap_or(State::Clean),
+            revision: revision.trim().parse().ok(),
+        });
+    }
+    incoming
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_incoming() {
+        let output = "\
+M       *       42   src/a.rs
+M               42   src/local.rs
+        *       42   docs/readme.md
+        *            src/new.rs
+?                    scratch.txt
+Status against revision:     45
+";
+        assert_eq!(
+            Incoming {
+                head: Some(45),
+                changes: vec![
+                    IncomingChange {
+                        path: "src/a.rs".into(),
+                        local: State::Modified,
+                        revision: Some(42),
+                    },
+                    IncomingChange {
+                        path: "docs/readme.md".into(),
+                        local: State::Clean,
+                        revision: Some(42),
+                    },
+                    IncomingChange {
+                        path: "src/new.rs".into(),
+                        local: State::Clean,
+                        revision: None,
+                    },
+                ],
+            },
+            parse_incoming(output)
+        );
+    }
+
+    #[test]
+    fn test_get_revisions_behind() {
+        let policy = RetryPolicy {
+            retries: 0,
+            backoff: std::time::Duration::ZERO,
+        };
+        let behind = get_revisions_behind("changed_by_someone_else", &policy);
+        assert_eq!(2, behind.unwrap());
+    }
+}
@@ -0,0 +1,227 @@
+//! Tests that drive the svn module against real, throwaway repositories created with
+//! `svnadmin`. Run with `cargo test --features svn-integration`; each test is skipped if
+//! svn isn't installed.
+
+use super::*;
+use crate::{
+    command::USE_REAL_COMMANDS,
+    demo::{file_url, run_ok, run_svn, write},
+};
+
+/// A repository with a checkout of its trunk, deleted when dropped
+struct TestRepo {
+    base: PathBuf,
+    url: String,
+    wc: PathBuf,
+}
+
+impl TestRepo {
+    /// Creates the repository with an initial commit of `files`, or returns None if svn
+    /// isn't available
+    fn new(name: &str, files: &[(&str, &str)]) -> Option<Self> {
+        USE_REAL_COMMANDS.with(|real| real.set(true));
+        if run_ok("svnadmin", &["--version", "--quiet"]).is_err() {
+            eprintln!("svnadmin not found, skipping {name}");
+            return None;
+        }
+        let base = std::env::temp_dir().join(format!(
+            "svn-util-integration-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(&base).unwrap();
+        let repo = base.join("repo");
+        run_ok("svnadmin", &["create", &repo.to_string_lossy()]).unwrap();
+        let url = file_url(&repo);
+        run_svn(&[
+            "mkdir",
+            "-m",
+            "layout",
+            &format!("{url}/trunk"),
+            &format!("{url}/branches"),
+        ])
+        .unwrap();
+        let repo = TestRepo {
+            wc: base.join("wc"),
+            base,
+            url,
+        };
+        repo.checkout(&format!("{}/trunk", repo.url), &repo.wc);
+        for (path, contents) in files {
+            repo.write(path, contents);
+            run_svn(&["add", "--parents", &repo.path(path)]).unwrap();
+        }
+        repo.commit("initial import");
+        Some(repo)
+    }
+
+    fn checkout(&self, url: &str, to: &Path) {
+        run_svn(&["checkout", url, &to.to_string_lossy()]).unwrap();
+    }
+
+    fn path(&self, path: &str) -> String {
+        self.wc.join(path).to_string_lossy().to_string()
+    }
+
+    fn write(&self, path: &str, contents: &str) {
+        write(&self.wc, path, contents).unwrap();
+    }
+
+    fn commit(&self, message: &str) {
+        run_svn(&["commit", "-m", message, &self.wc.to_string_lossy()]).unwrap();
+        run_svn(&["update", &self.wc.to_string_lossy()]).unwrap();
+    }
+
+    /// The status with paths made relative to the working copy
+    fn status(&self) -> Vec<StatusEntry> {
+        let mut status: Vec<_> = get_svn_status(&self.wc)
+            .unwrap()
+            .list()
+            .iter()
+            .map(|entry| StatusEntry {
+                path: self.relative(&entry.path),
+                ..entry.clone()
+            })
+            .collect();
+        status.sort_by(|a, b| a.path.cmp(&b.path));
+        status
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.wc).unwrap_or(path).to_path_buf()
+    }
+}
+
+impl Drop for TestRepo {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.base);
+    }
+}
+
+#[test]
+fn branch_name_comes_from_the_checkout_url() {
+    let Some(repo) = TestRepo::new("branch-name", &[("file.txt", "one\n")]) else {
+        return;
+    };
+    assert_eq!(Ok("trunk".to_string()), get_branch_name(&repo.wc));
+    run_svn(&[
+        "copy",
+        "-m",
+        "branch",
+        &format!("{}/trunk", repo.url),
+        &format!("{}/branches/feature-1", repo.url),
+    ])
+    .unwrap();
+    let branch_wc = repo.base.join("branch");
+    repo.checkout(&format!("{}/branches/feature-1", repo.url), &branch_wc);
+    assert_eq!(Ok("feature-1".to_string()), get_branch_name(&branch_wc));
+}
+
+#[test]
+fn status_parses_every_kind_of_local_change() {
+    let files = [
+        ("modified.txt", "one\n"),
+        ("deleted.txt", "one\n"),
+        ("missing.txt", "one\n"),
+        ("dir/nested.txt", "one\n"),
+    ];
+    let Some(repo) = TestRepo::new("status", &files) else {
+        return;
+    };
+    repo.write("modified.txt", "two\n");
+    repo.write("added.txt", "new\n");
+    repo.write("unversioned.txt", "new\n");
+    svn_add(&[&repo.path("added.txt")]).unwrap();
+    svn_delete(&[&repo.path("deleted.txt")]).unwrap();
+    std::fs::remove_file(repo.wc.join("missing.txt")).unwrap();
+    assert_eq!(
+        vec![
+            StatusEntry::new(State::Added, "added.txt"),
+            StatusEntry::new(State::Deleted, "deleted.txt"),
+            StatusEntry::new(State::Missing, "missing.txt"),
+            StatusEntry::new(State::Modified, "modified.txt"),
+            StatusEntry::new(State::Unversioned, "unversioned.txt"),
+        ],
+        repo.status()
+    );
+}
+
+#[test]
+fn add_and_revert_round_trip() {
+    let Some(repo) = TestRepo::new("add-revert", &[("file.txt", "one\n")]) else {
+        return;
+    };
+    repo.write("new.txt", "new\n");
+    assert!(svn_add(&[&repo.path("new.txt")]).unwrap().success());
+    assert_eq!(
+        vec![StatusEntry::new(State::Added, "new.txt")],
+        repo.status()
+    );
+    assert!(svn_revert(&[&repo.path("new.txt")]).unwrap().success());
+    assert_eq!(
+        vec![StatusEntry::new(State::Unversioned, "new.txt")],
+        repo.status()
+    );
+}
+
+#[test]
+fn merge_conflicts_are_grouped_with_their_parts() {
+    let Some(repo) = TestRepo::new("merge-conflict", &[("file.txt", "base\n")]) else {
+        return;
+    };
+    run_svn(&[
+        "copy",
+        "-m",
+        "branch",
+        &format!("{}/trunk", repo.url),
+        &format!("{}/branches/feature", repo.url),
+    ])
+    .unwrap();
+    let branch_wc = repo.base.join("branch");
+    repo.checkout(&format!("{}/branches/feature", repo.url), &branch_wc);
+    write(&branch_wc, "file.txt", "from the branch\n").unwrap();
+    run_svn(&[
+        "commit",
+        "-m",
+        "branch change",
+        &branch_wc.to_string_lossy(),
+    ])
+    .unwrap();
+    repo.write("file.txt", "from trunk\n");
+    repo.commit("trunk change");
+
+    run_svn(&[
+        "merge",
+        "--accept",
+        "postpone",
+        &format!("{}/branches/feature", repo.url),
+        &repo.wc.to_string_lossy(),
+    ])
+    .unwrap();
+    let list = get_svn_status(&repo.wc).unwrap();
+    assert!(list.has_conflicts());
+    let conflicts = list.conflicts();
+    assert_eq!(1, conflicts.len());
+    let Conflict::Text {
+        file,
+        left,
+        right,
+        working,
+    } = &conflicts[0];
+    assert_eq!(PathBuf::from("file.txt"), repo.relative(file));
+    assert!(left.is_some() && right.is_some() && working.is_some());
+    assert_eq!(1, list.renderable().len());
+}
+
+#[test]
+fn not_a_working_copy_is_reported() {
+    let Some(repo) = TestRepo::new("not-a-wc", &[]) else {
+        return;
+    };
+    let outside = repo.base.join("outside");
+    std::fs::create_dir_all(&outside).unwrap();
+    assert!(matches!(
+        get_branch_name(&outside),
+        Err(Error::PathNotUnderVersionControl(_))
+    ));
+}
@@ -0,0 +1,271 @@
+use super::{Error, Result};
+use crate::command::{RetryPolicy, run_command_retrying};
+use chrono::{DateTime, Utc};
+
+/// How many revisions of each branch are shown on the revision graph
+const GRAPH_REVISIONS_PER_BRANCH: usize = 50;
+
+/// How many branches the revision graph follows back through their copies
+const MAX_GRAPH_BRANCHES: usize = 5;
+
+/// How many of a file's revisions its history shows
+const FILE_HISTORY_REVISIONS: usize = 100;
+
+/// A revision from 'svn log --xml --verbose'
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub revision: u32,
+    pub author: String,
+    pub date: Option<DateTime<Utc>>,
+    pub message: String,
+    pub paths: Vec<ChangedPath>,
+}
+
+/// A path changed in a revision, with where it was copied from if it was
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedPath {
+    pub path: String,
+    /// A, D, M or R
+    pub action: char,
+    pub copied_from: Option<(String, u32)>,
+}
+
+/// A branch's revisions back to when it was copied, newest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchHistory {
+    /// The branch's path in the repository, e.g. "/branches/feature"
+    pub path: String,
+    pub entries: Vec<LogEntry>,
+    /// The path and revision the branch was copied from, `None` if it wasn't
+    pub copied_from: Option<(String, u32)>,
+}
+
+impl LogEntry {
+    /// The first line of the message
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    /// Where the branch at `path` was copied from in this revision, if it was. The copy may
+    /// have been of a parent directory, with the branch inside it.
+    fn copy_source_of(&self, path: &str) -> Option<(String, u32)> {
+        self.paths.iter().find_map(|changed| {
+            let rest = path.strip_prefix(&changed.path)?;
+            if !rest.is_empty() && !rest.starts_with('/') {
+                return None;
+            }
+            let (from, rev) = changed.copied_from.as_ref()?;
+            Some((format!("{from}{rest}"), *rev))
+        })
+    }
+}
+
+/// Runs 'svn log --xml' with the arguments
+pub fn get_log(args: &[&str], policy: &RetryPolicy) -> Result<Vec<LogEntry>> {
+    let mut all_args = vec!["log", "--xml", "--non-interactive"];
+    all_args.extend_from_slice(args);
+    let res = run_command_retrying("svn", &all_args, policy)?;
+    match res.success() {
+        true => Ok(parse_log_xml(res.output())),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The newest revision of the working copy's branch it has, i.e. up to BASE
+pub fn get_last_commit(path: &str, policy: &RetryPolicy) -> Result<Option<LogEntry>> {
+    Ok(get_log(&["--limit", "1", path], policy)?.into_iter().next())
+}
+
+/// The recent revisions that changed the path in the working copy, newest first, following
+/// it back through copies and renames
+pub fn get_file_history(path: &str, policy: &RetryPolicy) -> Result<Vec<LogEntry>> {
+    let limit = FILE_HISTORY_REVISIONS.to_string();
+    get_log(&["--limit", &limit, path], policy)
+}
+
+/// The recent history of the branch at the repository path, e.g. "/branches/feature", then of
+/// the branch it was copied from up to the copy, and so on
+pub fn get_revision_graph(path: &str, policy: &RetryPolicy) -> Result<Vec<BranchHistory>> {
+    let mut branches = vec![];
+    let mut next = Some((path.to_string(), None));
+    while let Some((path, peg)) = next.take()
+        && branches.len() < MAX_GRAPH_BRANCHES
+    {
+        let target = match peg {
+            Some(rev) => format!("^{path}@{rev}"),
+            None => format!("^{path}"),
+        };
+        let limit = GRAPH_REVISIONS_PER_BRANCH.to_string();
+        let entries = get_log(
+            &["--stop-on-copy", "-v", "--limit", &limit, &target],
+            policy,
+        )?;
+        // the copy is the branch's oldest revision, which the limit may have cut off
+        let oldest = match entries.len() < GRAPH_REVISIONS_PER_BRANCH {
+            true => entries.last().cloned(),
+            false => {
+                let end = peg.map_or("HEAD".to_string(), |rev: u32| rev.to_string());
+                let range = format!("1:{end}");
+                let first = [
+                    "--stop-on-copy",
+                    "-v",
+                    "--limit",
+                    "1",
+                    "-r",
+                    &range,
+                    &target,
+                ];
+                get_log(&first, policy)?.pop()
+            }
+        };
+        let copied_from = oldest.and_then(|entry| entry.copy_source_of(&path));
+        next = copied_from.clone().map(|(from, rev)| (from, Some(rev)));
+        branches.push(BranchHistory {
+            path,
+            entries,
+            copied_from,
+        });
+    }
+    Ok(branches)
+}
+
+/// Reads the log entries out of 'svn log --xml' output, which is regular enough to not need a
+/// full XML parser
+pub fn parse_log_xml(xml: &str) -> Vec<LogEntry> {
+    xml.split("<logentry")
+        .skip(1)
+        .filter_map(parse_log_entry)
+        .collect()
+}
+
+fn parse_log_entry(xml: &str) -> Option<LogEntry> {
+    let revision = attribute(opening_tag(xml), "revision")?.parse().ok()?;
+    let paths = xml
+        .split("<path")
+        .skip(1)
+        // "<paths>" is split on too, but has no attributes or text of its own
+        .filter(|part| !part.starts_with('s'))
+        .filter_map(parse_changed_path)
+        .collect();
+    Some(LogEntry {
+        revision,
+        author: element_text(xml, "author").unwrap_or_default(),
+        date: element_text(xml, "date").and_then(|date| date.parse().ok()),
+        message: element_text(xml, "msg").unwrap_or_default(),
+        paths,
+    })
+}
+
+fn parse_changed_path(xml: &str) -> Option<ChangedPath> {
+    let tag = opening_tag(xml);
+    let text = &xml[tag.len() + 1..xml.find("</path>")?];
+    let copied_from = attribute(tag, "copyfrom-path")
+        .zip(attribute(tag, "copyfrom-rev").and_then(|rev| rev.parse().ok()));
+    Some(ChangedPath {
+        path: unescape(text),
+        action: attribute(tag, "action")?.chars().next()?,
+        copied_from,
+    })
+}
+
+/// The attributes of the tag the xml starts in, up to the closing '>'
+fn opening_tag(xml: &str) -> &str {
+    &xml[..xml.find('>').unwrap_or(xml.len())]
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = start + tag[start..].find('"')?;
+    Some(unescape(&tag[start..end]))
+}
+
+fn element_text(xml: &str, name: &str) -> Option<String> {
+    let start = xml.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = start + xml[start..].find(&format!("</{name}>"))?;
+    Some(unescape(&xml[start..end]))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<log>
+<logentry
+   revision="12">
+<author>alice</author>
+<date>2024-03-01T10:15:00.000000Z</date>
+<paths>
+<path
+   prop-mods="false"
+   text-mods="true"
+   kind="file"
+   action="M">/branches/feature/src/a &amp; b.rs</path>
+</paths>
+<msg>Fix the &lt;thing&gt;
+with details</msg>
+</logentry>
+<logentry
+   revision="10">
+<author>bob</author>
+<date>2024-02-28T09:00:00.000000Z</date>
+<paths>
+<path
+   copyfrom-path="/trunk"
+   copyfrom-rev="9"
+   kind="dir"
+   action="A">/branches/feature</path>
+</paths>
+<msg>Branch for the feature</msg>
+</logentry>
+</log>
+"#;
+
+    #[test]
+    fn test_parse_log_xml() {
+        let entries = parse_log_xml(LOG);
+        assert_eq!(2, entries.len());
+        assert_eq!(
+            LogEntry {
+                revision: 12,
+                author: "alice".into(),
+                date: "2024-03-01T10:15:00Z".parse().ok(),
+                message: "Fix the <thing>\nwith details".into(),
+                paths: vec![ChangedPath {
+                    path: "/branches/feature/src/a & b.rs".into(),
+                    action: 'M',
+                    copied_from: None,
+                }],
+            },
+            entries[0]
+        );
+        assert_eq!("Fix the <thing>", entries[0].summary());
+        assert_eq!(
+            Some(("/trunk".to_string(), 9)),
+            entries[1].paths[0].copied_from
+        );
+    }
+
+    #[test]
+    fn test_copy_source_of() {
+        let entries = parse_log_xml(LOG);
+        assert_eq!(
+            Some(("/trunk".to_string(), 9)),
+            entries[1].copy_source_of("/branches/feature")
+        );
+        assert_eq!(
+            Some(("/trunk/sub".to_string(), 9)),
+            entries[1].copy_source_of("/branches/feature/sub")
+        );
+        assert_eq!(None, entries[1].copy_source_of("/branches/feature-2"));
+        assert_eq!(None, entries[0].copy_source_of("/branches/feature"));
+    }
+}
@@ -0,0 +1,60 @@
+use super::Result;
+use crate::command::{CommandProgress, RetryPolicy, stream_command};
+
+/// Starts merging the source url into the working copy the current directory is in, calling
+/// `on_progress` from another thread for every line svn prints and once more when it ends.
+/// Conflicts are postponed, so they can be resolved from the conflicts pane afterwards.
+pub fn start_merge(
+    source: &str,
+    revision_args: &[String],
+    policy: &RetryPolicy,
+    on_progress: impl Fn(CommandProgress) + Send + 'static,
+) -> Result<()> {
+    let mut args = vec!["merge", "--non-interactive", "--accept", "postpone"];
+    args.extend(revision_args.iter().map(String::as_str));
+    args.extend([source, "."]);
+    Ok(stream_command("svn", &args, policy, on_progress)?)
+}
+
+/// The 'svn merge' arguments for the revisions typed into the merge prompt: nothing for every
+/// eligible revision, a range like "100:120" or a list of changes like "105,107"
+pub fn merge_revision_args(input: &str) -> std::result::Result<Vec<String>, String> {
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if input.is_empty() {
+        return Ok(vec![]);
+    }
+    let is_revisions = |text: &str, separator: char| {
+        text.split(separator)
+            .all(|rev| !rev.is_empty() && rev.trim_start_matches('r').parse::<u32>().is_ok())
+    };
+    if input.contains(':') && input.matches(':').count() == 1 && is_revisions(&input, ':') {
+        Ok(vec!["-r".into(), input.replace('r', "")])
+    } else if is_revisions(&input, ',') {
+        Ok(vec!["-c".into(), input.replace('r', "")])
+    } else {
+        Err(format!(
+            "'{input}' isn't a revision range like 100:120 or a list like 105,107"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("", Ok(vec![]))]
+    #[case("100:120", Ok(vec!["-r".into(), "100:120".into()]))]
+    #[case(" r100 : r120 ", Ok(vec!["-r".into(), "100:120".into()]))]
+    #[case("105", Ok(vec!["-c".into(), "105".into()]))]
+    #[case("105,r107", Ok(vec!["-c".into(), "105,107".into()]))]
+    #[case("1:2:3", Err("'1:2:3' isn't a revision range like 100:120 or a list like 105,107".into()))]
+    #[case("HEAD", Err("'HEAD' isn't a revision range like 100:120 or a list like 105,107".into()))]
+    fn test_merge_revision_args(
+        #[case] input: &str,
+        #[case] exp: std::result::Result<Vec<String>, String>,
+    ) {
+        assert_eq!(exp, merge_revision_args(input));
+    }
+}
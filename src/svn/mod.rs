@@ -1,12 +1,28 @@
+pub mod blame;
+pub mod checkout;
 pub mod error;
 pub mod filelist;
 pub mod filetree;
+pub mod hunks;
+pub mod incoming;
+#[cfg(all(test, feature = "svn-integration"))]
+mod integration_tests;
+pub mod log;
+pub mod merge;
 pub mod state;
-use crate::command::CmdResult;
+pub mod three_way;
+pub mod version;
+use crate::command::{
+    CmdResult, CommandProgress, RetryPolicy, run_command_retrying, stream_command,
+};
 
 use super::command::run_command;
 use state::State;
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 pub use {
     error::{Error, Result},
     filelist::FileList,
@@ -14,28 +30,200 @@ pub use {
 
 pub fn svn_revert(paths: &[&str]) -> Result<CmdResult> {
     let mut args = vec!["revert"];
-    args.extend_from_slice(&paths);
+    args.extend_from_slice(paths);
     run_command("svn", &args).map_err(Error::from)
 }
 
 pub fn svn_delete(paths: &[&str]) -> Result<CmdResult> {
     let mut args = vec!["remove"];
-    args.extend_from_slice(&paths);
+    args.extend_from_slice(paths);
     run_command("svn", &args).map_err(Error::from)
 }
 
 pub fn svn_add(paths: &[&str]) -> Result<CmdResult> {
     let mut args = vec!["add"];
-    args.extend_from_slice(&paths);
+    args.extend_from_slice(paths);
+    run_command("svn", &args).map_err(Error::from)
+}
+
+/// Starts committing the paths together with the message, passing them in a targets file so
+/// any number of them fit on the command line, and calling `on_progress` like
+/// [`merge::start_merge`]. Their locks are released unless `keep_locks` is set.
+pub fn start_commit(
+    message: &str,
+    keep_locks: bool,
+    paths: &[String],
+    policy: &RetryPolicy,
+    on_progress: impl Fn(CommandProgress) + Send + 'static,
+) -> Result<()> {
+    let file = std::env::temp_dir().join(format!("svn-util-{}.targets", std::process::id()));
+    std::fs::write(&file, paths.join("\n"))?;
+    let targets = file.to_string_lossy().to_string();
+    let mut args = vec!["commit", "-m", message, "--targets", &targets];
+    if keep_locks {
+        args.push("--keep-locks");
+    }
+    // svn reads the targets when it starts, but it's simplest to keep them until it's done
+    let started = stream_command("svn", &args, policy, move |progress| {
+        if matches!(progress, CommandProgress::Finished(_)) {
+            let _ = std::fs::remove_file(&file);
+        }
+        on_progress(progress)
+    });
+    Ok(started?)
+}
+
+/// Commits the paths, releasing their locks unless `keep_locks` is set
+pub fn svn_commit_with_message(
+    message: &str,
+    keep_locks: bool,
+    paths: &[&str],
+) -> Result<CmdResult> {
+    let mut args = vec!["commit", "-m", message];
+    if keep_locks {
+        args.push("--keep-locks");
+    }
+    args.extend_from_slice(paths);
+    run_command("svn", &args).map_err(Error::from)
+}
+
+/// Moves the paths into the changelist, taking them out of any changelist they were in
+pub fn svn_changelist(name: &str, paths: &[&str]) -> Result<CmdResult> {
+    let mut args = vec!["changelist", name];
+    args.extend_from_slice(paths);
+    run_command("svn", &args).map_err(Error::from)
+}
+
+pub fn svn_resolve(paths: &[&str]) -> Result<CmdResult> {
+    let mut args = vec!["resolve", "--accept", "working"];
+    args.extend_from_slice(paths);
     run_command("svn", &args).map_err(Error::from)
 }
 
-pub fn svn_commit(paths: &[&str]) -> Result<CmdResult> {
-    let mut args = vec!["commit"];
-    args.extend_from_slice(&paths);
+pub fn svn_lock(paths: &[&str]) -> Result<CmdResult> {
+    let mut args = vec!["lock"];
+    args.extend_from_slice(paths);
     run_command("svn", &args).map_err(Error::from)
 }
 
+pub fn svn_update(paths: &[&str]) -> Result<CmdResult> {
+    let mut args = vec!["update", "--non-interactive"];
+    args.extend_from_slice(paths);
+    run_command("svn", &args).map_err(Error::from)
+}
+
+/// Starts updating the working copy the current directory is in to the revision, calling
+/// `on_progress` like [`merge::start_merge`]
+pub fn start_update(
+    revision: &str,
+    policy: &RetryPolicy,
+    on_progress: impl Fn(CommandProgress) + Send + 'static,
+) -> Result<()> {
+    let args = ["update", "--non-interactive", "-r", revision];
+    Ok(stream_command("svn", &args, policy, on_progress)?)
+}
+
+/// The revision 'svn update' left the working copy at, from its last line
+pub fn parse_updated_revision(output: &str) -> Option<u32> {
+    output.lines().rev().find_map(|line| {
+        line.strip_prefix("Updated to revision ")
+            .or_else(|| line.strip_prefix("At revision "))
+            .and_then(|rev| rev.trim_end_matches('.').parse().ok())
+    })
+}
+
+/// The revision 'svn commit' created, from its "Committed revision N." line
+pub fn parse_committed_revision(output: &str) -> Option<u32> {
+    output.lines().rev().find_map(|line| {
+        line.strip_prefix("Committed revision ")
+            .and_then(|rev| rev.trim_end_matches('.').parse().ok())
+    })
+}
+
+/// Cleans up the working copy the current directory is in, whatever the paths
+pub fn svn_cleanup(_paths: &[&str]) -> Result<CmdResult> {
+    run_command("svn", &["cleanup"]).map_err(Error::from)
+}
+
+pub fn svn_unlock(paths: &[&str]) -> Result<CmdResult> {
+    let mut args = vec!["unlock"];
+    args.extend_from_slice(paths);
+    run_command("svn", &args).map_err(Error::from)
+}
+
+/// Applies a diff from 'svn diff' to the working copy, via a temporary patch file. svn
+/// succeeds even when hunks are rejected or files skipped, so those are made an error here.
+pub fn svn_apply_patch(patch: &str) -> Result<CmdResult> {
+    let file = std::env::temp_dir().join(format!("svn-util-{}.patch", std::process::id()));
+    std::fs::write(&file, patch)?;
+    let result = run_command("svn", &["patch", &file.to_string_lossy()]);
+    let _ = std::fs::remove_file(&file);
+    let result = result?;
+    let failures = patch_failures(result.output());
+    match failures.is_empty() {
+        true => Ok(result),
+        false => Err(Error::Unknown(format!(
+            "the patch didn't apply cleanly:\n{}",
+            failures.join("\n")
+        ))),
+    }
+}
+
+/// The lines of 'svn patch' output for files it left conflicted or skipped, e.g.
+/// "C         a.txt" or "Skipped missing target: 'b.txt'"
+fn patch_failures(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter(|line| {
+            line.starts_with("Skipped") || line.get(..2).is_some_and(|status| status.contains('C'))
+        })
+        .collect()
+}
+
+/// Adds each path's file name to the svn:ignore property of its parent directory
+pub fn svn_ignore(paths: &[&str]) -> Result<CmdResult> {
+    let mut last = None;
+    for path in paths {
+        let path = Path::new(path);
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy(),
+            _ => ".".into(),
+        };
+        let name = path
+            .file_name()
+            .ok_or(Error::Unknown(format!("cannot ignore {path:?}")))?
+            .to_string_lossy();
+        let existing = run_command("svn", &["propget", "svn:ignore", &parent])?;
+        let patterns = add_ignore_pattern(
+            if existing.success() {
+                existing.output()
+            } else {
+                ""
+            },
+            &name,
+        );
+        let res = run_command("svn", &["propset", "svn:ignore", &patterns, &parent])?;
+        if !res.success() {
+            return Ok(res);
+        }
+        last = Some(res);
+    }
+    last.ok_or(Error::Unknown("nothing to ignore".into()))
+}
+
+/// Appends the pattern to an svn:ignore property value, unless it is already present
+fn add_ignore_pattern(existing: &str, pattern: &str) -> String {
+    let mut patterns: Vec<&str> = existing
+        .lines()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !patterns.contains(&pattern) {
+        patterns.push(pattern);
+    }
+    patterns.join("\n")
+}
+
 pub fn parse_branch_name(svn_info: &str) -> Result<String> {
     for line in svn_info.lines() {
         if line.starts_with("URL:") {
@@ -54,41 +242,443 @@ pub fn parse_branch_name(svn_info: &str) -> Result<String> {
     Err(Error::BranchParseFailure)
 }
 
-pub fn get_branch_name(path: &PathBuf) -> Result<String> {
+pub fn get_branch_name(path: &Path) -> Result<String> {
     let res = run_command("svn", &["info", &path.to_string_lossy()])?;
     match res.success() {
         true => parse_branch_name(res.output()),
-        false => Err(Error::from(res.output())),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The full url of the working copy's branch
+pub fn get_url(path: &Path) -> Result<String> {
+    let res = run_command(
+        "svn",
+        &["info", "--show-item", "url", &path.to_string_lossy()],
+    )?;
+    match res.success() {
+        true => Ok(res.output().trim().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The url of the path in the repository, from the url of the directory it's relative to,
+/// percent-encoding the characters urls can't have like svn does
+pub fn path_url(base_url: &str, path: &Path) -> String {
+    let mut url = base_url.trim_end_matches('/').to_string();
+    for component in path.components() {
+        url.push('/');
+        for byte in component.as_os_str().to_string_lossy().bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    url.push(byte as char)
+                }
+                byte => url.push_str(&format!("%{byte:02X}")),
+            }
+        }
+    }
+    url
+}
+
+/// The paths that differ between the branch at the base url and the other url, relative to
+/// the base, with whether each was added, deleted or modified on the other branch
+pub fn get_diff_summary(
+    base_url: &str,
+    other: &str,
+    policy: &RetryPolicy,
+) -> Result<Vec<StatusEntry>> {
+    let res = run_command_retrying(
+        "svn",
+        &["diff", "--summarize", "--non-interactive", base_url, other],
+        policy,
+    )?;
+    match res.success() {
+        true => Ok(parse_diff_summary(res.output(), base_url)),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// Reads 'svn diff --summarize' output, where each line has the status columns then the url
+fn parse_diff_summary(output: &str, base_url: &str) -> Vec<StatusEntry> {
+    let base = base_url.trim_end_matches('/');
+    output
+        .lines()
+        .filter_map(|line| {
+            let (status, url) = line.split_at_checked(8)?;
+            let path = match url.strip_prefix(base)?.trim_start_matches('/') {
+                "" => ".",
+                path => path,
+            };
+            StatusEntry::from_columns(status, path)
+        })
+        .collect()
+}
+
+/// Where the working copy is in its repository, e.g. "/branches/feature"
+pub fn get_repo_path(path: &Path) -> Result<String> {
+    let res = run_command(
+        "svn",
+        &[
+            "info",
+            "--show-item",
+            "relative-url",
+            &path.to_string_lossy(),
+        ],
+    )?;
+    match res.success() {
+        true => Ok(res.output().trim().trim_start_matches('^').to_string()),
+        false => Err(Error::from(&res)),
     }
 }
 
-pub type ParsedStatusLine = (State, PathBuf);
+/// The UUID of the repository the working copy was checked out from
+pub fn get_repo_uuid(path: &Path) -> Result<String> {
+    let res = run_command(
+        "svn",
+        &["info", "--show-item", "repos-uuid", &path.to_string_lossy()],
+    )?;
+    match res.success() {
+        true => Ok(res.output().trim().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// Lists the branches under the roots, relative to the repository root of the working copy
+/// the app is in. A root ending in `/*` is a directory whose children are branches, e.g.
+/// "^/branches/*", anything else is a branch itself, e.g. "^/trunk".
+pub fn list_branches(roots: &[String], policy: &RetryPolicy) -> Result<Vec<String>> {
+    let mut branches = vec![];
+    for root in roots {
+        let Some(dir) = root.strip_suffix("/*") else {
+            branches.push(root.clone());
+            continue;
+        };
+        let res = run_command_retrying("svn", &["ls", "--non-interactive", dir], policy)?;
+        match res.success() {
+            true => branches.extend(parse_branch_listing(dir, res.output())),
+            false => return Err(Error::from(&res)),
+        }
+    }
+    Ok(branches)
+}
+
+/// The branch urls from an 'svn ls' of a directory of branches, skipping any files
+fn parse_branch_listing(dir: &str, listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .filter_map(|line| line.trim().strip_suffix('/'))
+        .map(|name| format!("{dir}/{name}"))
+        .collect()
+}
+
+/// Which revisions 'svn mergeinfo' lists
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeinfoRevs {
+    /// Revisions of the source not yet merged into the working copy
+    Eligible,
+    /// Revisions of the source already merged into the working copy
+    Merged,
+}
+
+impl MergeinfoRevs {
+    fn arg(&self) -> &'static str {
+        match self {
+            MergeinfoRevs::Eligible => "eligible",
+            MergeinfoRevs::Merged => "merged",
+        }
+    }
+}
 
-pub fn get_svn_status(path: &PathBuf) -> Result<Vec<ParsedStatusLine>> {
-    let res = run_command("svn", &["status", &path.to_string_lossy()])?;
+/// The revisions of the source url that are eligible for or have been merged into the
+/// working copy the current directory is in
+pub fn get_mergeinfo(source: &str, revs: MergeinfoRevs, policy: &RetryPolicy) -> Result<Vec<u32>> {
+    let res = run_command_retrying(
+        "svn",
+        &[
+            "mergeinfo",
+            "--non-interactive",
+            "--show-revs",
+            revs.arg(),
+            source,
+            ".",
+        ],
+        policy,
+    )?;
     match res.success() {
-        true => parse_svn_status(res.output()),
-        false => Err(Error::from(res.output())),
+        true => Ok(parse_mergeinfo_revisions(res.output())),
+        false => Err(Error::from(&res)),
     }
 }
 
-fn parse_status_line(status_line: &str) -> Result<ParsedStatusLine> {
-    let (status, path) = status_line.split_at(8);
-    match State::from_str(status) {
-        Ok(state) => {
-            let path = PathBuf::from_str(path).expect("bad path");
-            Ok((state, path))
+/// The revisions listed by 'svn mergeinfo --show-revs', one per line like "r12", with a '*'
+/// after those only merged into part of the tree
+fn parse_mergeinfo_revisions(output: &str) -> Vec<u32> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('r'))
+        .filter_map(|rev| rev.trim_end_matches('*').parse().ok())
+        .collect()
+}
+
+/// Copies the working copy's version of the path to the destination, without the .svn
+/// metadata
+pub fn svn_export(path: &str, dest: &str) -> Result<CmdResult> {
+    run_command("svn", &["export", "--non-interactive", path, dest]).map_err(Error::from)
+}
+
+pub fn svn_switch(url: &str) -> Result<CmdResult> {
+    run_command("svn", &["switch", "--non-interactive", url]).map_err(Error::from)
+}
+
+/// Runs 'svn status' on just the paths, e.g. to refresh them after an action
+pub fn get_svn_status_of(paths: &[&str]) -> Result<FileList> {
+    let mut args = vec!["status"];
+    args.extend_from_slice(paths);
+    let res = run_command("svn", &args)?;
+    match res.success() {
+        true => FileList::from_svn_status(res.output()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The local changes to the path in git's diff format, which 'svn patch' puts back more
+/// faithfully than the default, e.g. added and deleted files
+pub fn get_git_diff(path: &Path) -> Result<String> {
+    let res = run_command("svn", &["diff", "--git", &path.to_string_lossy()])?;
+    match res.success() {
+        true => Ok(res.output().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The files in the diff whose changes it doesn't have, e.g. binary files svn only says it
+/// "Cannot display", so a patch of them can't put them back
+pub fn files_without_content(diff: &str) -> Vec<String> {
+    let mut files = vec![];
+    let mut file = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("Index: ") {
+            file = Some(path.to_string());
+        } else if line.starts_with("Cannot display:")
+            && let Some(path) = file.take()
+        {
+            files.push(path);
         }
-        Err(_) => Err(Error::UnrecognisedStatus(status.into())),
     }
+    files
 }
 
-fn parse_svn_status(svn_status: &str) -> Result<Vec<ParsedStatusLine>> {
+pub fn get_diff(path: &Path) -> Result<String> {
+    let res = run_command("svn", &["diff", &path.to_string_lossy()])?;
+    match res.success() {
+        true => Ok(res.output().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The contents of the file at the revision
+pub fn svn_cat(path: &str, revision: u32) -> Result<String> {
+    let revision = revision.to_string();
+    let res = run_command("svn", &["cat", "-r", &revision, path])?;
+    match res.success() {
+        true => Ok(res.output().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// The changes to the path's properties, without those to its contents
+pub fn get_property_diff(path: &Path) -> Result<String> {
+    let res = run_command(
+        "svn",
+        &["diff", "--properties-only", &path.to_string_lossy()],
+    )?;
+    match res.success() {
+        true => Ok(res.output().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// What the revision changed in the path
+pub fn get_revision_diff(path: &str, revision: u32) -> Result<String> {
+    let revision = revision.to_string();
+    let res = run_command("svn", &["diff", "-c", &revision, path])?;
+    match res.success() {
+        true => Ok(res.output().to_string()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// Walks up from the path to find the root of the working copy, i.e. the nearest
+/// directory containing a `.svn` directory
+pub fn find_wc_root(path: &Path) -> Option<PathBuf> {
+    find_wc_roots(path).into_iter().next()
+}
+
+/// Every working copy root above the path, nearest first. There's more than one when working
+/// copies are nested, e.g. a separate checkout inside an unversioned directory of another.
+pub fn find_wc_roots(path: &Path) -> Vec<PathBuf> {
+    path.ancestors()
+        .filter(|dir| dir.join(".svn").is_dir())
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+/// A line of 'svn status': the path and what each of the seven columns before it says
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusEntry {
+    /// The first column, what happened to the path itself
+    pub state: State,
+    pub path: PathBuf,
+    /// The second column
+    pub properties: Option<PropertyStatus>,
+    /// L in the third column: the directory is locked by an operation that didn't finish,
+    /// which 'svn cleanup' clears
+    pub wc_locked: bool,
+    /// + in the fourth column: scheduled for addition with history, i.e. copied or moved
+    pub copied: bool,
+    /// S in the fifth column: switched to a url other than its parent's
+    pub switched: bool,
+    /// X in the fifth column: a file brought in by an externals definition
+    pub file_external: bool,
+    /// The sixth column
+    pub lock: Option<LockStatus>,
+    /// C in the seventh column: a tree conflict, e.g. an edit to a file an update deleted
+    pub tree_conflict: bool,
+}
+
+impl StatusEntry {
+    /// An entry with nothing in the columns after the state
+    pub fn new(state: State, path: impl Into<PathBuf>) -> Self {
+        Self {
+            state,
+            path: path.into(),
+            properties: None,
+            wc_locked: false,
+            copied: false,
+            switched: false,
+            file_external: false,
+            lock: None,
+            tree_conflict: false,
+        }
+    }
+
+    /// Reads the status columns, e.g. "A  +    ", which may be fewer than seven in the
+    /// output of commands like 'svn diff --summarize'
+    fn from_columns(columns: &str, path: impl Into<PathBuf>) -> Option<Self> {
+        let column = |i: usize| columns.chars().nth(i).unwrap_or(' ');
+        Some(Self {
+            properties: PropertyStatus::from_column(column(1)),
+            wc_locked: column(2) == 'L',
+            copied: column(3) == '+',
+            switched: column(4) == 'S',
+            file_external: column(4) == 'X',
+            lock: LockStatus::from_column(column(5)),
+            tree_conflict: column(6) == 'C',
+            ..Self::new(State::from_str(columns).ok()?, path)
+        })
+    }
+}
+
+/// Whether a path's properties changed, from the second column of 'svn status'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyStatus {
+    /// M: the properties were modified
+    Modified,
+    /// C: the properties conflict with an update or merge
+    Conflicted,
+}
+
+impl PropertyStatus {
+    fn from_column(column: char) -> Option<Self> {
+        match column {
+            'M' => Some(PropertyStatus::Modified),
+            'C' => Some(PropertyStatus::Conflicted),
+            _ => None,
+        }
+    }
+
+    /// The letter svn shows in the column
+    pub fn letter(&self) -> &'static str {
+        match self {
+            PropertyStatus::Modified => "M",
+            PropertyStatus::Conflicted => "C",
+        }
+    }
+}
+
+/// Who holds the lock on a path, from the sixth column of 'svn status'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockStatus {
+    /// K: locked by this working copy
+    Mine,
+    /// O: locked by someone else, or by another working copy
+    Other,
+    /// T: this working copy's lock was stolen by someone else
+    Stolen,
+    /// B: this working copy's lock was broken
+    Broken,
+}
+
+impl LockStatus {
+    fn from_column(column: char) -> Option<Self> {
+        match column {
+            'K' => Some(LockStatus::Mine),
+            'O' => Some(LockStatus::Other),
+            'T' => Some(LockStatus::Stolen),
+            'B' => Some(LockStatus::Broken),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LockStatus::Mine => "locked",
+            LockStatus::Other => "locked by someone else",
+            LockStatus::Stolen => "lock stolen",
+            LockStatus::Broken => "lock broken",
+        }
+    }
+
+    /// The label for narrow terminals, e.g. "lk!" for a lock held elsewhere
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            LockStatus::Mine => "lk",
+            LockStatus::Other => "lk!",
+            LockStatus::Stolen | LockStatus::Broken => "lk?",
+        }
+    }
+}
+
+pub fn get_svn_status(path: &Path) -> Result<FileList> {
+    get_svn_status_with_depth(path, "infinity")
+}
+
+/// Runs 'svn status' with the given depth, e.g. "files" to only get the status of the
+/// directory itself and the files directly inside it
+pub fn get_svn_status_with_depth(path: &Path, depth: &str) -> Result<FileList> {
+    let res = run_command(
+        "svn",
+        &["status", "--depth", depth, &path.to_string_lossy()],
+    )?;
+    match res.success() {
+        true => FileList::from_svn_status(res.output()),
+        false => Err(Error::from(&res)),
+    }
+}
+
+fn parse_status_line(status_line: &str) -> Result<StatusEntry> {
+    status_line
+        .split_at_checked(8)
+        .and_then(|(status, path)| StatusEntry::from_columns(status, path))
+        .ok_or_else(|| Error::UnrecognisedStatus(status_line.into()))
+}
+
+fn parse_svn_status(svn_status: &str) -> Result<Vec<StatusEntry>> {
     svn_status
         .lines()
         .filter(|line| svn_status_filter(line))
         .map(parse_status_line)
-        .collect::<Result<Vec<ParsedStatusLine>>>()
+        .collect::<Result<Vec<StatusEntry>>>()
 }
 
 pub fn is_conflict_part(path: &str) -> bool {
@@ -96,12 +686,44 @@ pub fn is_conflict_part(path: &str) -> bool {
 }
 
 fn svn_status_filter(line: &str) -> bool {
-    !(line.is_empty() || line.starts_with("Summary") || line.contains("onflicts:"))
+    !(line.is_empty()
+        || line.starts_with("Summary")
+        || line.contains("onflicts:")
+        || is_tree_conflict_description(line)
+        || parse_changelist_header(line).is_some())
+}
+
+/// Whether the line describes the tree conflict on the line above, e.g.
+/// `      >   local file edit, incoming file delete or move upon update`
+fn is_tree_conflict_description(line: &str) -> bool {
+    line.trim_start().starts_with('>')
+}
+
+/// Parses the name out of a changelist header line, e.g. `--- Changelist 'name':`
+fn parse_changelist_header(line: &str) -> Option<&str> {
+    line.strip_prefix("--- Changelist '")?.strip_suffix("':")
 }
 
-fn create_empty_text_conflict(file: &PathBuf) -> Conflict {
+/// Maps each path in the status output to the changelist it was listed under
+fn parse_changelists(svn_status: &str) -> HashMap<PathBuf, String> {
+    let mut changelists = HashMap::new();
+    let mut current = None;
+    for line in svn_status.lines() {
+        if let Some(name) = parse_changelist_header(line) {
+            current = Some(name);
+        } else if let Some(name) = current
+            && svn_status_filter(line)
+            && let Ok(entry) = parse_status_line(line)
+        {
+            changelists.insert(entry.path, name.to_string());
+        }
+    }
+    changelists
+}
+
+fn create_empty_text_conflict(file: &Path) -> Conflict {
     Conflict::Text {
-        file: file.clone(),
+        file: file.to_path_buf(),
         left: None,
         working: None,
         right: None,
@@ -127,6 +749,110 @@ pub enum Conflict {
     },
 }
 
+impl Conflict {
+    /// The conflicted file, containing the conflict markers
+    pub fn file(&self) -> &Path {
+        let Conflict::Text { file, .. } = self;
+        file
+    }
+
+    /// The common ancestor of both sides, i.e. `.merge-left.rN` or the older `.rN` of an update
+    pub fn base(&self) -> Option<&Path> {
+        let Conflict::Text { left, .. } = self;
+        left.as_deref()
+    }
+
+    /// The local side, i.e. `.working` or `.mine`
+    pub fn mine(&self) -> Option<&Path> {
+        let Conflict::Text { working, .. } = self;
+        working.as_deref()
+    }
+
+    /// The incoming side, i.e. `.merge-right.rN` or the newer `.rN` of an update
+    pub fn theirs(&self) -> Option<&Path> {
+        let Conflict::Text { right, .. } = self;
+        right.as_deref()
+    }
+
+    /// Fills in any parts the file names didn't reveal, e.g. the `.mine` and `.rN` files of
+    /// an update conflict, by asking 'svn info' where they are
+    pub fn locate_parts(self) -> Result<Conflict> {
+        if self.base().is_some() && self.mine().is_some() && self.theirs().is_some() {
+            return Ok(self);
+        }
+        let located = get_conflict_parts(self.file())?;
+        let Conflict::Text {
+            file,
+            left,
+            right,
+            working,
+        } = self;
+        Ok(Conflict::Text {
+            left: left.or_else(|| located.base().map(Path::to_path_buf)),
+            right: right.or_else(|| located.theirs().map(Path::to_path_buf)),
+            working: working.or_else(|| located.mine().map(Path::to_path_buf)),
+            file,
+        })
+    }
+
+    /// What the file resolves to if the conflict is trivial, see [`three_way::trivial_merge`].
+    /// `None` if it isn't, or svn didn't leave both sides' part files to compare.
+    pub fn trivial_resolution(&self) -> Result<Option<String>> {
+        let (Some(mine), Some(theirs)) = (self.mine(), self.theirs()) else {
+            return Ok(None);
+        };
+        let mine = std::fs::read_to_string(mine)?;
+        let theirs = std::fs::read_to_string(theirs)?;
+        Ok(three_way::trivial_merge(
+            &self.base_content()?,
+            &mine,
+            &theirs,
+        ))
+    }
+
+    /// The content of the common ancestor, read from its part file or, if svn didn't leave
+    /// one, from the pristine BASE copy
+    pub fn base_content(&self) -> Result<String> {
+        if let Some(base) = self.base() {
+            return Ok(std::fs::read_to_string(base)?);
+        }
+        let res = run_command(
+            "svn",
+            &["cat", "-r", "BASE", &self.file().to_string_lossy()],
+        )?;
+        match res.success() {
+            true => Ok(res.output().to_string()),
+            false => Err(Error::from(&res)),
+        }
+    }
+}
+
+/// Asks 'svn info' for the part files of a text conflict
+pub fn get_conflict_parts(file: &Path) -> Result<Conflict> {
+    let res = run_command("svn", &["info", &file.to_string_lossy()])?;
+    match res.success() {
+        true => Ok(parse_conflict_info(file, res.output())),
+        false => Err(Error::from(&res)),
+    }
+}
+
+/// Reads the conflict part files out of 'svn info' output, which names them relative to the
+/// conflicted file's directory
+fn parse_conflict_info(file: &Path, info: &str) -> Conflict {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let part = |key: &str| {
+        info.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .map(|name| dir.join(name.trim()))
+    };
+    Conflict::Text {
+        file: file.to_path_buf(),
+        left: part("Conflict Previous Base File:"),
+        right: part("Conflict Current Base File:"),
+        working: part("Conflict Previous Working File:"),
+    }
+}
+
 #[derive(PartialEq, Debug)]
 enum ConflictPart {
     Left,
@@ -153,12 +879,19 @@ fn parse_conflict_part(path: &str) -> Option<ConflictPart> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TempDir;
     use rstest::*;
 
     #[rstest]
     #[case("branch_name", Ok("branch_name".into()))]
     #[case("nested/branch_name", Ok("branch_name".into()))]
     #[case("output_missing_URL", Err(Error::BranchParseFailure))]
+    #[case(
+        "not_a_working_copy",
+        Err(Error::PathNotUnderVersionControl(
+            "svn: E155007: '/not_a_working_copy' is not a working copy".into()
+        ))
+    )]
     #[case(
         "something_bad_happened",
         Err(Error::Unknown("unknown issue with svn".into()))
@@ -168,41 +901,201 @@ mod tests {
         assert_eq!(exp, actual);
     }
 
+    #[test]
+    fn test_files_without_content() {
+        let diff = "\
+Index: a.txt
+===================================================================
+diff --git a/a.txt b/a.txt
+--- a/a.txt\t(revision 3)
++++ b/a.txt\t(working copy)
+@@ -1 +1 @@
+-one
++ONE
+Index: image.png
+===================================================================
+diff --git a/image.png b/image.png
+Cannot display: file marked as a binary type.
+svn:mime-type = application/octet-stream
+";
+        assert_eq!(vec!["image.png".to_string()], files_without_content(diff));
+    }
+
+    #[rstest]
+    #[case("U         a.txt\nA         b.txt\n", vec![])]
+    #[case("G         a.txt\n>         applied hunk @@ -1,3 +1,3 @@ with fuzz 1\n", vec![])]
+    #[case(
+        "C         a.txt\n>         rejected hunk @@ -1,3 +1,3 @@\nSummary of conflicts:\n  Text conflicts: 1\n",
+        vec!["C         a.txt"]
+    )]
+    #[case(" C        dir\nSkipped missing target: 'b.txt'\n", vec![" C        dir", "Skipped missing target: 'b.txt'"])]
+    fn test_patch_failures(#[case] output: &str, #[case] exp: Vec<&str>) {
+        assert_eq!(exp, patch_failures(output));
+    }
+
+    #[test]
+    fn test_commit_keeps_locks() {
+        let result = svn_commit_with_message("Fix the thing", true, &["a.txt"]).unwrap();
+        assert!(result.success());
+    }
+
+    #[test]
+    fn test_find_wc_root() {
+        let temp = TempDir::new("find-wc-root");
+        let root = temp.path().to_path_buf();
+        let nested = root.join("dir1").join("nested1");
+        std::fs::create_dir_all(root.join(".svn")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(Some(root.clone()), find_wc_root(&nested));
+        assert_eq!(Some(root.clone()), find_wc_root(&root));
+        drop(temp);
+        assert_eq!(None, find_wc_root(&nested));
+    }
+
+    #[test]
+    fn test_find_wc_roots() {
+        let temp = TempDir::new("find-wc-roots");
+        let outer = temp.path().to_path_buf();
+        let inner = outer.join("vendor").join("lib");
+        let nested = inner.join("src");
+        std::fs::create_dir_all(outer.join(".svn")).unwrap();
+        std::fs::create_dir_all(inner.join(".svn")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        let roots = find_wc_roots(&nested);
+        assert_eq!(vec![inner, outer], roots);
+    }
+
     #[rstest]
     #[case("", Ok(vec![]))]
-    #[case("M       path/to/file.txt", Ok(vec![(State::Modified, PathBuf::from("path/to/file.txt"))]))]
+    #[case("M       path/to/file.txt", Ok(vec![StatusEntry::new(State::Modified, "path/to/file.txt")]))]
     #[case("M       path/to/file.txt\nR       path/to/replaced_file.txt", Ok(vec![
-        (State::Modified, PathBuf::from("path/to/file.txt")),
-        (State::Replaced, PathBuf::from("path/to/replaced_file.txt")),
+        StatusEntry::new(State::Modified, "path/to/file.txt"),
+        StatusEntry::new(State::Replaced, "path/to/replaced_file.txt"),
+    ]))]
+    #[case("A  +  C dir\n      >   local dir edit, incoming dir delete upon update", Ok(vec![
+        StatusEntry { copied: true, tree_conflict: true, ..StatusEntry::new(State::Added, "dir") },
     ]))]
-    fn test_parse_svn_status(#[case] svn_status: &str, #[case] exp: Result<Vec<ParsedStatusLine>>) {
+    fn test_parse_svn_status(#[case] svn_status: &str, #[case] exp: Result<Vec<StatusEntry>>) {
         assert_eq!(exp, parse_svn_status(svn_status));
     }
 
     #[rstest]
-    #[case("M       path/to/file.txt", Ok((State::Modified, PathBuf::from("path/to/file.txt"))))]
-    #[case("C       path/to/file.txt", Ok((State::Conflicting, PathBuf::from("path/to/file.txt"))))]
-    #[case("R       path/to/file.txt", Ok((State::Replaced, PathBuf::from("path/to/file.txt"))))]
-    #[case("D       path/to/file.txt", Ok((State::Deleted, PathBuf::from("path/to/file.txt"))))]
-    #[case("!       path/to/file.txt", Ok((State::Missing, PathBuf::from("path/to/file.txt"))))]
-    #[case("?       path/to/file.txt", Ok((State::Unversioned, PathBuf::from("path/to/file.txt"))))]
-    #[case("A       path/to/file.txt", Ok((State::Added, PathBuf::from("path/to/file.txt"))))]
-    #[case(" M      path/to/file.txt", Ok((State::Clean, PathBuf::from("path/to/file.txt"))))]
-    fn test_parse_status_line(#[case] status_line: &str, #[case] exp: Result<ParsedStatusLine>) {
+    #[case(
+        "M       path/to/file.txt",
+        Ok(StatusEntry::new(State::Modified, "path/to/file.txt"))
+    )]
+    #[case(
+        "C       path/to/file.txt",
+        Ok(StatusEntry::new(State::Conflicting, "path/to/file.txt"))
+    )]
+    #[case(
+        "R       path/to/file.txt",
+        Ok(StatusEntry::new(State::Replaced, "path/to/file.txt"))
+    )]
+    #[case(
+        "D       path/to/file.txt",
+        Ok(StatusEntry::new(State::Deleted, "path/to/file.txt"))
+    )]
+    #[case(
+        "!       path/to/file.txt",
+        Ok(StatusEntry::new(State::Missing, "path/to/file.txt"))
+    )]
+    #[case(
+        "?       path/to/file.txt",
+        Ok(StatusEntry::new(State::Unversioned, "path/to/file.txt"))
+    )]
+    #[case(
+        "A       path/to/file.txt",
+        Ok(StatusEntry::new(State::Added, "path/to/file.txt"))
+    )]
+    #[case(" M      path/to/file.txt", Ok(StatusEntry { properties: Some(PropertyStatus::Modified), ..StatusEntry::new(State::Clean, "path/to/file.txt") }))]
+    #[case("MCL+SKC a.txt", Ok(StatusEntry {
+        state: State::Modified,
+        path: "a.txt".into(),
+        properties: Some(PropertyStatus::Conflicted),
+        wc_locked: true,
+        copied: true,
+        switched: true,
+        file_external: false,
+        lock: Some(LockStatus::Mine),
+        tree_conflict: true,
+    }))]
+    #[case("    X   lib/external.h", Ok(StatusEntry { file_external: true, ..StatusEntry::new(State::Clean, "lib/external.h") }))]
+    #[case("M a", Err(Error::UnrecognisedStatus("M a".into())))]
+    fn test_parse_status_line(#[case] status_line: &str, #[case] exp: Result<StatusEntry>) {
         assert_eq!(exp, parse_status_line(status_line));
     }
 
+    #[rstest]
+    #[case("", "new.txt", "new.txt")]
+    #[case("*.o\n", "new.txt", "*.o\nnew.txt")]
+    #[case("*.o\nnew.txt\n", "new.txt", "*.o\nnew.txt")]
+    fn test_add_ignore_pattern(#[case] existing: &str, #[case] pattern: &str, #[case] exp: &str) {
+        assert_eq!(exp, add_ignore_pattern(existing, pattern));
+    }
+
     #[rstest]
     #[case("", false)]
     #[case("Summary", false)]
     #[case("Summary of conflicts", false)]
     #[case("onflicts:", false)]
     #[case("Text conflicts:", false)]
+    #[case("--- Changelist 'ignore-on-commit':", false)]
     #[case("literally anything else", true)]
     fn test_svn_status_filter(#[case] line: &str, #[case] exp: bool) {
         assert_eq!(exp, svn_status_filter(line));
     }
 
+    #[test]
+    fn test_parse_changelists() {
+        let svn_status = "M       outside.txt
+
+--- Changelist 'feature':
+M       dir/in_feature.txt
+A       added.txt
+
+--- Changelist 'ignore-on-commit':
+M       config.local
+";
+        let expected = HashMap::from([
+            (PathBuf::from("dir/in_feature.txt"), "feature".to_string()),
+            (PathBuf::from("added.txt"), "feature".to_string()),
+            (
+                PathBuf::from("config.local"),
+                "ignore-on-commit".to_string(),
+            ),
+        ]);
+        assert_eq!(expected, parse_changelists(svn_status));
+        assert_eq!(4, parse_svn_status(svn_status).unwrap().len());
+    }
+
+    #[test]
+    fn test_parse_locks() {
+        let svn_status = "M    K  mine.psd
+     O  theirs.psd
+     T  stolen.psd
+     B  broken.psd
+M       unlocked.txt
+
+--- Changelist 'art':
+A    K  dir/new.psd
+";
+        let locks: Vec<Option<LockStatus>> = parse_svn_status(svn_status)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.lock)
+            .collect();
+        let expected = vec![
+            Some(LockStatus::Mine),
+            Some(LockStatus::Other),
+            Some(LockStatus::Stolen),
+            Some(LockStatus::Broken),
+            None,
+            Some(LockStatus::Mine),
+        ];
+        assert_eq!(expected, locks);
+    }
+
     #[rstest]
     #[case("derpderp.txt", "derpderp.txt")]
     #[case("", "")]
@@ -227,4 +1120,103 @@ mod tests {
     fn test_parse_conflict_part(#[case] path: &str, #[case] exp: Option<ConflictPart>) {
         assert_eq!(exp, parse_conflict_part(path));
     }
+
+    #[rstest]
+    #[case("Updating '.':\nU    a.txt\nUpdated to revision 42.\n", Some(42))]
+    #[case("Updating '.':\nAt revision 7.\n", Some(7))]
+    #[case("svn: E160006: No such revision 999\n", None)]
+    fn test_parse_updated_revision(#[case] output: &str, #[case] exp: Option<u32>) {
+        assert_eq!(exp, parse_updated_revision(output));
+    }
+
+    #[rstest]
+    #[case(
+        "https://svn.example.com/repo/trunk",
+        "src/main.rs",
+        "https://svn.example.com/repo/trunk/src/main.rs"
+    )]
+    #[case(
+        "https://svn.example.com/repo/trunk/",
+        "docs/read me#1.txt",
+        "https://svn.example.com/repo/trunk/docs/read%20me%231.txt"
+    )]
+    #[case(
+        "file:///repo/trunk",
+        "caf\u{e9}.txt",
+        "file:///repo/trunk/caf%C3%A9.txt"
+    )]
+    fn test_path_url(#[case] base: &str, #[case] path: &str, #[case] exp: &str) {
+        assert_eq!(exp, path_url(base, Path::new(path)));
+    }
+
+    #[rstest]
+    #[case(
+        "Sending        a.txt\nTransmitting file data .done\nCommitting transaction...\nCommitted revision 43.\n",
+        Some(43)
+    )]
+    #[case("svn: E155011: File 'a.txt' is out of date\n", None)]
+    fn test_parse_committed_revision(#[case] output: &str, #[case] exp: Option<u32>) {
+        assert_eq!(exp, parse_committed_revision(output));
+    }
+
+    #[test]
+    fn test_parse_diff_summary() {
+        let base = "https://svn.example.com/repo/trunk";
+        let output = "\
+M       https://svn.example.com/repo/trunk/src/a.rs
+A       https://svn.example.com/repo/trunk/src/new.rs
+D       https://svn.example.com/repo/trunk/old.txt
+ M      https://svn.example.com/repo/trunk
+";
+        let exp = vec![
+            StatusEntry::new(State::Modified, "src/a.rs"),
+            StatusEntry::new(State::Added, "src/new.rs"),
+            StatusEntry::new(State::Deleted, "old.txt"),
+            StatusEntry {
+                properties: Some(PropertyStatus::Modified),
+                ..StatusEntry::new(State::Clean, ".")
+            },
+        ];
+        assert_eq!(exp, parse_diff_summary(output, base));
+    }
+
+    #[test]
+    fn test_parse_mergeinfo_revisions() {
+        assert_eq!(
+            vec![12, 15, 20],
+            parse_mergeinfo_revisions("r12\nr15*\n\nr20\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_branch_listing() {
+        assert_eq!(
+            vec!["^/branches/feature", "^/branches/release-1.0"],
+            parse_branch_listing("^/branches", "feature/\nrelease-1.0/\nREADME.txt\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_conflict_info() {
+        let info = "\
+Path: dir/file.txt
+Name: file.txt
+Node Kind: file
+Conflict Previous Base File: file.txt.r1
+Conflict Previous Working File: file.txt.mine
+Conflict Current Base File: file.txt.r2
+Checksum: 4e1243bd22c66e76c2ba9eddc1f91394e57f9f83
+";
+        let conflict = parse_conflict_info(Path::new("dir/file.txt"), info);
+        assert_eq!(Path::new("dir/file.txt"), conflict.file());
+        assert_eq!(Some(Path::new("dir/file.txt.r1")), conflict.base());
+        assert_eq!(Some(Path::new("dir/file.txt.mine")), conflict.mine());
+        assert_eq!(Some(Path::new("dir/file.txt.r2")), conflict.theirs());
+
+        let not_conflicted = parse_conflict_info(Path::new("file.txt"), "Path: file.txt\n");
+        assert_eq!(
+            create_empty_text_conflict(Path::new("file.txt")),
+            not_conflicted
+        );
+    }
 }
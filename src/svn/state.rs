@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum State {
     Clean,       // not visible
     Modified,    // M
@@ -14,36 +14,69 @@ pub enum State {
 
 impl State {
     pub fn is_commitable(&self) -> bool {
-        match self {
-            State::Modified | State::Added | State::Deleted => true,
-            _ => false,
-        }
+        matches!(self, State::Modified | State::Added | State::Deleted)
     }
 
     pub fn is_revertable(&self) -> bool {
-        match self {
-            State::Modified
-            | State::Added
-            | State::Deleted
-            | State::Conflicting
-            | State::Missing => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            State::Modified | State::Added | State::Deleted | State::Conflicting | State::Missing
+        )
     }
 
     pub fn is_deletable(&self) -> bool {
-        match self {
-            State::Modified | State::Missing | State::Conflicting => true,
-            _ => false,
-        }
+        matches!(self, State::Modified | State::Missing | State::Conflicting)
     }
 
     pub fn is_addable(&self) -> bool {
+        matches!(self, State::Unversioned)
+    }
+
+    pub fn is_resolvable(&self) -> bool {
+        matches!(self, State::Conflicting)
+    }
+
+    pub fn is_lockable(&self) -> bool {
+        matches!(self, State::Modified | State::Replaced | State::Clean)
+    }
+
+    /// Whether 'svn export' can copy the path out of the working copy
+    pub fn is_exportable(&self) -> bool {
+        matches!(
+            self,
+            State::Clean | State::Modified | State::Added | State::Replaced | State::Conflicting
+        )
+    }
+
+    /// Whether the path is in the repository, so has a url there
+    pub fn is_in_repository(&self) -> bool {
+        !matches!(self, State::Added | State::Unversioned)
+    }
+
+    pub fn is_ignorable(&self) -> bool {
+        matches!(self, State::Unversioned)
+    }
+
+    /// The state as a word, e.g. for reports
+    pub fn name(&self) -> &'static str {
         match self {
-            State::Unversioned => true,
-            _ => false,
+            State::Clean => "clean",
+            State::Modified => "modified",
+            State::Added => "added",
+            State::Deleted => "deleted",
+            State::Unversioned => "unversioned",
+            State::Conflicting => "conflicted",
+            State::Replaced => "replaced",
+            State::Missing => "missing",
         }
     }
+
+    pub fn is_diffable(&self) -> bool {
+        matches!(
+            self,
+            State::Modified | State::Added | State::Deleted | State::Replaced | State::Conflicting
+        )
+    }
 }
 
 impl FromStr for State {
@@ -0,0 +1,138 @@
+/// A stretch of a conflicted file: either a line both sides agree on, or a region between
+/// conflict markers with each side's lines
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeBlock {
+    Common(String),
+    Conflict {
+        mine: Vec<String>,
+        /// The common ancestor's lines, `None` if the markers didn't include them
+        base: Option<Vec<String>>,
+        theirs: Vec<String>,
+    },
+}
+
+/// Splits a conflicted file on the markers svn writes into it:
+///
+/// ```text
+/// <<<<<<< .mine
+/// ||||||| .r1
+/// =======
+/// >>>>>>> .r2
+/// ```
+pub fn parse_conflict_markers(text: &str) -> Vec<MergeBlock> {
+    let mut blocks = vec![];
+    // the conflict region being read, and whether its lines are theirs yet
+    let mut region = None;
+    let mut in_theirs = false;
+    for line in text.lines() {
+        let Some(MergeBlock::Conflict { mine, base, theirs }) = region.as_mut() else {
+            match line.starts_with("<<<<<<<") {
+                true => {
+                    region = Some(MergeBlock::Conflict {
+                        mine: vec![],
+                        base: None,
+                        theirs: vec![],
+                    })
+                }
+                false => blocks.push(MergeBlock::Common(line.to_string())),
+            }
+            continue;
+        };
+        if line.starts_with("|||||||") {
+            *base = Some(vec![]);
+        } else if line.starts_with("=======") {
+            in_theirs = true;
+        } else if line.starts_with(">>>>>>>") {
+            blocks.extend(region.take());
+            in_theirs = false;
+        } else if in_theirs {
+            theirs.push(line.to_string());
+        } else if let Some(base) = base {
+            base.push(line.to_string());
+        } else {
+            mine.push(line.to_string());
+        }
+    }
+    // a region left open is still shown as a conflict rather than dropped
+    blocks.extend(region);
+    blocks
+}
+
+/// What a conflict resolves to without anyone having to choose: either side when both made
+/// the same change, give or take whitespace, or the other side when one only changed whitespace
+pub fn trivial_merge(base: &str, mine: &str, theirs: &str) -> Option<String> {
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+    let (base_words, my_words, their_words) = (words(base), words(mine), words(theirs));
+    if my_words == their_words || their_words == base_words {
+        Some(mine.to_string())
+    } else if my_words == base_words {
+        Some(theirs.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("a\nb\n", "a\nB\n", "a\nB\n", Some("a\nB\n"))]
+    #[case("a\nb\n", "a\nB\n", "a\n  B\n", Some("a\nB\n"))]
+    #[case("a\nb\n", "a\nB\n", "a\n\tb\n", Some("a\nB\n"))]
+    #[case("a\nb\n", "a  b\n", "a\nc\n", Some("a\nc\n"))]
+    #[case("a\nb\n", "a\nB\n", "a\nc\n", None)]
+    fn test_trivial_merge(
+        #[case] base: &str,
+        #[case] mine: &str,
+        #[case] theirs: &str,
+        #[case] exp: Option<&str>,
+    ) {
+        assert_eq!(exp.map(String::from), trivial_merge(base, mine, theirs));
+    }
+
+    #[test]
+    fn test_parse_conflict_markers() {
+        let text = "\
+one
+<<<<<<< .mine
+two mine
+||||||| .r1
+two
+=======
+two theirs
+also theirs
+>>>>>>> .r2
+three
+<<<<<<< .working
+four mine
+=======
+>>>>>>> .merge-right.r5
+";
+        let lines = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                MergeBlock::Common("one".into()),
+                MergeBlock::Conflict {
+                    mine: lines(&["two mine"]),
+                    base: Some(lines(&["two"])),
+                    theirs: lines(&["two theirs", "also theirs"]),
+                },
+                MergeBlock::Common("three".into()),
+                MergeBlock::Conflict {
+                    mine: lines(&["four mine"]),
+                    base: None,
+                    theirs: vec![],
+                },
+            ],
+            parse_conflict_markers(text)
+        );
+        assert_eq!(
+            vec![MergeBlock::Common("no markers".into())],
+            parse_conflict_markers("no markers\n")
+        );
+    }
+}
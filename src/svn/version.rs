@@ -0,0 +1,108 @@
+use super::{Error, Result};
+use crate::command::run_command;
+
+/// The version of the svn command line client, e.g. 1.14.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SvnVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl SvnVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Reads the output of 'svn --version --quiet', e.g. "1.14.3" or "1.14.3 (r1914484)"
+    pub fn parse(output: &str) -> Option<Self> {
+        let version = output.split_whitespace().next()?;
+        let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+        let major = parts.next()??;
+        let minor = parts.next()??;
+        // some builds only give the major and minor numbers
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl std::fmt::Display for SvnVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Something the app runs svn for that older clients can't do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientFeature {
+    /// 'svn patch', used to commit some of a file's hunks
+    Patch,
+    /// 'svn info --show-item', used to find the repository and branches
+    ShowItem,
+}
+
+impl ClientFeature {
+    /// The oldest client that has the feature
+    pub fn min_version(&self) -> SvnVersion {
+        match self {
+            ClientFeature::Patch => SvnVersion::new(1, 7, 0),
+            ClientFeature::ShowItem => SvnVersion::new(1, 9, 0),
+        }
+    }
+
+    /// Why the feature can't be used with the version, if it can't
+    pub fn unsupported_by(&self, version: SvnVersion) -> Option<String> {
+        let min = self.min_version();
+        (version < min)
+            .then(|| format!("needs svn {}.{}+, this is {version}", min.major, min.minor))
+    }
+}
+
+/// The version of the installed svn client. Fails with an [`Error::Io`] of kind `NotFound`
+/// when svn isn't installed.
+pub fn get_svn_version() -> Result<SvnVersion> {
+    let res = run_command("svn", &["--version", "--quiet"])?;
+    if !res.success() {
+        return Err(Error::from(&res));
+    }
+    SvnVersion::parse(res.output()).ok_or(Error::Unknown(format!(
+        "unrecognised svn version: {}",
+        res.output().trim()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("1.14.3\n", Some(SvnVersion::new(1, 14, 3)))]
+    #[case("1.9.7 (r1800392)\n", Some(SvnVersion::new(1, 9, 7)))]
+    #[case("1.15-dev\n", None)]
+    #[case("1.15\n", Some(SvnVersion::new(1, 15, 0)))]
+    #[case("", None)]
+    fn test_parse(#[case] output: &str, #[case] exp: Option<SvnVersion>) {
+        assert_eq!(exp, SvnVersion::parse(output));
+    }
+
+    #[test]
+    fn test_unsupported_by() {
+        assert_eq!(
+            Some("needs svn 1.7+, this is 1.6.17".to_string()),
+            ClientFeature::Patch.unsupported_by(SvnVersion::new(1, 6, 17))
+        );
+        assert_eq!(
+            None,
+            ClientFeature::ShowItem.unsupported_by(SvnVersion::new(1, 14, 0))
+        );
+        assert_eq!(
+            None,
+            ClientFeature::Patch.unsupported_by(SvnVersion::new(1, 7, 0))
+        );
+    }
+}
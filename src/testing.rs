@@ -0,0 +1,38 @@
+//! Helpers shared by the tests of several modules
+
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static NEXT_TEMP_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory under the system temp dir that only one test uses, removed with everything in
+/// it when dropped, so a failed assertion doesn't leave it behind for the next run
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates e.g. "svn-util-test-favorites-1234-0", unique to the process and the call
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "svn-util-test-{name}-{}-{}",
+            std::process::id(),
+            NEXT_TEMP_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,89 @@
+use super::VcsBackend;
+use crate::{
+    command::run_command,
+    svn::{Error, FileList, Result, StatusEntry, state::State},
+};
+use std::path::{Path, PathBuf};
+
+/// A read-only backend for plain git (and git-svn) checkouts
+#[derive(Debug, Default)]
+pub struct GitBackend;
+
+impl VcsBackend for GitBackend {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn branch_name(&self, path: &Path) -> Result<String> {
+        git(path, &["rev-parse", "--abbrev-ref", "HEAD"]).map(|out| out.trim().to_string())
+    }
+
+    fn status(&self, path: &Path) -> Result<FileList> {
+        let root = PathBuf::from(git(path, &["rev-parse", "--show-toplevel"])?.trim());
+        let porcelain = git(path, &["status", "--porcelain=v1", "--untracked-files=all"])?;
+        let mut list = FileList::empty();
+        *list.list_mut() = porcelain
+            .lines()
+            .filter_map(|line| parse_porcelain_line(line, &root))
+            .collect();
+        Ok(list)
+    }
+
+    fn diff(&self, path: &Path) -> Result<String> {
+        let dir = path.parent().unwrap_or(path);
+        git(dir, &["diff", "HEAD", "--", &path.to_string_lossy()])
+    }
+}
+
+fn git(path: &Path, args: &[&str]) -> Result<String> {
+    let mut with_dir = vec!["-C", path.to_str().ok_or("path is not valid utf-8")?];
+    with_dir.extend_from_slice(args);
+    let res = run_command("git", &with_dir)?;
+    match res.success() {
+        true => Ok(res.output().to_string()),
+        false => Err(Error::from(res.output())),
+    }
+}
+
+/// Parses a line of `git status --porcelain=v1`, e.g. ` M src/main.rs`, into the same states
+/// that svn uses. Paths are relative to the repository root, so they are joined onto it.
+fn parse_porcelain_line(line: &str, root: &Path) -> Option<StatusEntry> {
+    let (xy, path) = (line.get(..2)?, line.get(3..)?);
+    // renames are shown as "old -> new"
+    let path = path.rsplit(" -> ").next()?.trim_matches('"');
+    let state = match xy {
+        "??" => State::Unversioned,
+        "DD" | "AA" => State::Conflicting,
+        xy if xy.contains('U') => State::Conflicting,
+        xy if xy.starts_with('R') => State::Replaced,
+        " D" => State::Missing,
+        xy if xy.starts_with('D') => State::Deleted,
+        xy if xy.starts_with('A') => State::Added,
+        xy if xy.contains('M') || xy.contains('T') => State::Modified,
+        _ => return None,
+    };
+    Some(StatusEntry::new(state, root.join(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(" M src/main.rs", Some((State::Modified, "src/main.rs")))]
+    #[case("M  src/main.rs", Some((State::Modified, "src/main.rs")))]
+    #[case("A  new.rs", Some((State::Added, "new.rs")))]
+    #[case("D  gone.rs", Some((State::Deleted, "gone.rs")))]
+    #[case(" D gone.rs", Some((State::Missing, "gone.rs")))]
+    #[case("?? scratch.txt", Some((State::Unversioned, "scratch.txt")))]
+    #[case("UU both.rs", Some((State::Conflicting, "both.rs")))]
+    #[case("R  old.rs -> new.rs", Some((State::Replaced, "new.rs")))]
+    #[case("!! ignored.txt", None)]
+    #[case("", None)]
+    fn test_parse_porcelain_line(#[case] line: &str, #[case] exp: Option<(State, &str)>) {
+        let root = Path::new("/repo");
+        let exp = exp.map(|(state, path)| StatusEntry::new(state, root.join(path)));
+        assert_eq!(exp, parse_porcelain_line(line, root));
+    }
+}
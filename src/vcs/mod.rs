@@ -0,0 +1,104 @@
+pub mod git;
+
+use crate::svn::{self, FileList, Result};
+use std::path::{Path, PathBuf};
+
+/// Provides the status, branch and diffs of a working copy, so the app isn't tied to svn.
+/// svn is the primary backend; others are read-only, the svn actions aren't offered for them.
+pub trait VcsBackend: std::fmt::Debug {
+    fn name(&self) -> &'static str;
+
+    fn branch_name(&self, path: &Path) -> Result<String>;
+
+    fn status(&self, path: &Path) -> Result<FileList>;
+
+    /// The status limited to a depth, e.g. "files" for only the files directly inside the path
+    fn status_with_depth(&self, path: &Path, _depth: &str) -> Result<FileList> {
+        self.status(path)
+    }
+
+    /// Whether `status_with_depth` actually limits the depth, so status can be loaded in chunks
+    fn supports_depth(&self) -> bool {
+        false
+    }
+
+    fn diff(&self, path: &Path) -> Result<String>;
+
+    /// Whether the actions that change the working copy can be used
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SvnBackend;
+
+impl VcsBackend for SvnBackend {
+    fn name(&self) -> &'static str {
+        "svn"
+    }
+
+    fn branch_name(&self, path: &Path) -> Result<String> {
+        svn::get_branch_name(path)
+    }
+
+    fn status(&self, path: &Path) -> Result<FileList> {
+        svn::get_svn_status(path)
+    }
+
+    fn status_with_depth(&self, path: &Path, depth: &str) -> Result<FileList> {
+        svn::get_svn_status_with_depth(path, depth)
+    }
+
+    fn supports_depth(&self) -> bool {
+        true
+    }
+
+    fn diff(&self, path: &Path) -> Result<String> {
+        svn::get_diff(path)
+    }
+
+    fn read_only(&self) -> bool {
+        false
+    }
+}
+
+/// The root of the working copy containing the path, i.e. the nearest directory with a `.svn`
+/// or `.git` directory in it
+pub fn find_working_copy(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".svn").is_dir() || dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Picks the backend for the working copy containing the path, whichever of a `.svn` or `.git`
+/// directory is closest. Defaults to svn so that its errors are shown for unversioned paths.
+pub fn detect_backend(path: &Path) -> Box<dyn VcsBackend> {
+    for dir in path.ancestors() {
+        if dir.join(".svn").is_dir() {
+            return Box::new(SvnBackend);
+        }
+        if dir.join(".git").exists() {
+            return Box::new(git::GitBackend);
+        }
+    }
+    Box::new(SvnBackend)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_detect_backend() {
+        let temp = TempDir::new("detect-backend");
+        let root = temp.path();
+        let svn_in_git = root.join("svn_wc");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(svn_in_git.join(".svn")).unwrap();
+        std::fs::create_dir_all(svn_in_git.join("dir")).unwrap();
+        assert_eq!("git", detect_backend(root).name());
+        assert_eq!("svn", detect_backend(&svn_in_git.join("dir")).name());
+    }
+}